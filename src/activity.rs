@@ -0,0 +1,93 @@
+//! Motion activity updates and activity guesses from speed
+//!
+//! With the `activity-recognition` feature, [`watch_activity`](crate::watch_activity)
+//! reports the platform's motion activity (`CMMotionActivityManager` on iOS,
+//! Activity Recognition on Android). Where those are unavailable or not
+//! permitted, the ground speed of a fix is usually enough to tell standing
+//! still from walking or driving.
+
+#[cfg(feature = "activity-recognition")]
+use crate::Result;
+#[cfg(feature = "activity-recognition")]
+use std::sync::Arc;
+
+#[cfg(all(target_os = "android", feature = "activity-recognition"))]
+use crate::android as platform;
+#[cfg(all(target_os = "ios", feature = "activity-recognition"))]
+use crate::darwin as platform;
+
+/// Callback invoked by a platform activity watch for each detected activity.
+#[cfg(feature = "activity-recognition")]
+pub(crate) type ActivityCallback = Arc<dyn Fn(ActivityType) + Send + Sync>;
+
+/// A running platform activity watch. Updates stop when it is dropped.
+#[cfg(feature = "activity-recognition")]
+pub(crate) type ActivityWatch = platform::ActivityWatch;
+
+/// Start activity updates, calling `callback` for each detected activity.
+#[cfg(feature = "activity-recognition")]
+pub(crate) fn watch(callback: ActivityCallback) -> Result<ActivityWatch> {
+    platform::watch_activity(callback)
+}
+
+/// What the user is probably doing, as guessed by
+/// [`detect_activity_from_speed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ActivityType {
+    /// The speed is unknown, negative or not a number.
+    Unknown,
+    /// Below 0.5 m/s, which also covers GPS drift while standing still.
+    Still,
+    /// 0.5-2.5 m/s.
+    Walking,
+    /// 2.5-6 m/s.
+    Running,
+    /// 6-35 m/s (up to 126 km/h). Cycling and driving overlap too much to
+    /// be told apart by speed alone.
+    Vehicle,
+    /// Above 35 m/s, e.g. a train or a car on the motorway.
+    FastVehicle,
+}
+
+/// Guess the activity from a ground speed in meters per second, such as
+/// [`Location::speed`](crate::Location::speed).
+///
+/// A single fix is easily misread, e.g. a car waiting at a traffic light
+/// looks [`Still`](ActivityType::Still), so consider the most common result
+/// over the last few fixes before acting on it, for example to choose a
+/// larger distance filter while the user is driving.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::{detect_activity_from_speed, ActivityType};
+///
+/// assert_eq!(detect_activity_from_speed(1.4), ActivityType::Walking);
+/// assert_eq!(detect_activity_from_speed(13.9), ActivityType::Vehicle);
+/// assert_eq!(detect_activity_from_speed(-1.0), ActivityType::Unknown);
+/// ```
+pub fn detect_activity_from_speed(speed_ms: f64) -> ActivityType {
+    match speed_ms {
+        speed if speed.is_nan() || speed < 0.0 => ActivityType::Unknown,
+        speed if speed < 0.5 => ActivityType::Still,
+        speed if speed < 2.5 => ActivityType::Walking,
+        speed if speed < 6.0 => ActivityType::Running,
+        speed if speed <= 35.0 => ActivityType::Vehicle,
+        _ => ActivityType::FastVehicle,
+    }
+}
+
+/// Platforms without a motion activity API
+#[cfg(all(
+    feature = "activity-recognition",
+    not(any(target_os = "android", target_os = "ios"))
+))]
+mod platform {
+    use super::ActivityCallback;
+    use crate::{Error, Result};
+
+    pub struct ActivityWatch;
+
+    pub fn watch_activity(_callback: ActivityCallback) -> Result<ActivityWatch> {
+        Err(Error::PermanentlyUnavailable)
+    }
+}
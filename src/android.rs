@@ -1,16 +1,44 @@
+#[cfg(feature = "activity-recognition")]
+use crate::activity::ActivityCallback;
+#[cfg(feature = "barometer")]
+use crate::barometer::AltitudeCallback;
+use crate::heading::{Heading, HeadingCallback};
+#[cfg(feature = "pedometer")]
+use crate::pedometer::{PedometerCallback, PedometerUpdate};
+use crate::satellites::{GpsSatellite, SatelliteCallback};
+use crate::watch::LocationCallback;
+#[cfg(feature = "activity-recognition")]
+use crate::ActivityType;
+use crate::{
+    now, Error, Location, LocationOptions, LocationProviderInfo, PermissionStatus, PowerLevel,
+    Result,
+};
 use dioxus_platform_bridge::android::{
     check_self_permission, load_class_from_classloader, new_object_array, new_string,
     request_permissions_via_helper, set_object_array_element, with_activity,
 };
 use jni::{
-    objects::{JObject, JValue},
-    JNIEnv,
+    objects::{GlobalRef, JClass, JDoubleArray, JFloatArray, JObject, JString, JValue},
+    sys::{jboolean, jdouble, jint, jlong},
+    JNIEnv, NativeMethod,
 };
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 const PERMISSION_GRANTED: i32 = 0;
 
+// `android.location.Criteria` power requirement constants
+const CRITERIA_POWER_LOW: i32 = 1;
+const CRITERIA_POWER_MEDIUM: i32 = 2;
+
+/// Whether `request_permission()` has been called in this process
+static PERMISSION_REQUESTED: AtomicBool = AtomicBool::new(false);
+
 /// Request location permission at runtime
 pub fn request_permission() -> bool {
+    PERMISSION_REQUESTED.store(true, Ordering::Relaxed);
     with_activity(|env, activity| {
         if matches!(env.exception_check(), Ok(true)) {
             let _ = env.exception_describe();
@@ -71,70 +99,1420 @@ pub fn request_permission() -> bool {
     .unwrap_or(false)
 }
 
+/// Get the current location permission state without prompting
+///
+/// Android can't tell "never asked" from "denied" without asking, so a
+/// missing permission counts as denied only after `request_permission()`
+/// was called in this process.
+pub fn permission_status() -> PermissionStatus {
+    with_activity(|env, activity| {
+        let mut granted =
+            |permission: &str| check_self_permission(env, activity, permission).unwrap_or(false);
+
+        let status = if granted("android.permission.ACCESS_FINE_LOCATION") {
+            if granted("android.permission.ACCESS_BACKGROUND_LOCATION") {
+                PermissionStatus::GrantedAlways
+            } else {
+                PermissionStatus::GrantedFine
+            }
+        } else if granted("android.permission.ACCESS_COARSE_LOCATION") {
+            PermissionStatus::GrantedCoarse
+        } else if PERMISSION_REQUESTED.load(Ordering::Relaxed) {
+            PermissionStatus::Denied
+        } else {
+            PermissionStatus::NotDetermined
+        };
+        Some(status)
+    })
+    .unwrap_or(PermissionStatus::NotDetermined)
+}
+
 /// Get the last known location
-pub fn last_known() -> Option<(f64, f64)> {
+pub fn last_known() -> Option<Location> {
+    with_activity(|env, activity| last_known_location(env, activity))
+}
+
+/// Get the time of the last known location fix
+pub fn last_fix_time() -> Option<SystemTime> {
+    with_activity(|env, activity| last_known_location(env, activity)?.timestamp)
+}
+
+/// Whether the last known location was set by a mock location provider
+pub fn last_known_is_mock() -> bool {
     with_activity(|env, activity| {
-        // Check permission inline to avoid lifetime issues
-        let mut has_permission = false;
-        
-        #[cfg(feature = "location-fine")]
-        {
-            has_permission |= check_self_permission(env, activity, "android.permission.ACCESS_FINE_LOCATION").unwrap_or(false);
+        if !has_location_permission(env, activity) {
+            return None;
         }
-        
-        #[cfg(feature = "location-coarse")]
+        let location = last_known_location_object(env, activity)?;
+        Some(is_mock(env, &location))
+    })
+    .unwrap_or(false)
+}
+
+/// Call `Location.isMock()`, added in API level 31, or the deprecated
+/// `isFromMockProvider()` on older devices
+fn is_mock(env: &mut JNIEnv<'_>, location: &JObject<'_>) -> bool {
+    for method in ["isMock", "isFromMockProvider"] {
+        match env
+            .call_method(location, method, "()Z", &[])
+            .and_then(|mock| mock.z())
         {
-            has_permission |= check_self_permission(env, activity, "android.permission.ACCESS_COARSE_LOCATION").unwrap_or(false);
+            Ok(mock) => return mock,
+            Err(_) => {
+                let _ = env.exception_clear();
+            }
         }
+    }
+    false
+}
 
-        #[cfg(not(any(feature = "location-fine", feature = "location-coarse")))]
-        {
-            has_permission = true;
+/// Callbacks of the running `LocationUpdates` listeners, keyed by watch id
+static WATCHERS: Mutex<Vec<(u64, LocationCallback)>> = Mutex::new(Vec::new());
+static NEXT_WATCH_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A running `LocationUpdates` listener. Updates stop when it is dropped.
+pub struct Watch {
+    id: u64,
+    updates: GlobalRef,
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        lock_watchers().retain(|(id, _)| *id != self.id);
+        with_activity(|env, _activity| {
+            if env
+                .call_method(self.updates.as_obj(), "stop", "()V", &[])
+                .is_err()
+            {
+                let _ = env.exception_describe();
+                let _ = env.exception_clear();
+            }
+            Some(())
+        });
+    }
+}
+
+/// Start location updates, calling `callback` on the main looper for each fix
+pub fn watch(options: &LocationOptions, callback: LocationCallback) -> Result<Watch> {
+    let id = NEXT_WATCH_ID.fetch_add(1, Ordering::Relaxed);
+    lock_watchers().push((id, callback));
+
+    let updates = with_activity(|env, activity| Some(start_updates(env, activity, id, options)))
+        .unwrap_or(Err(Error::AndroidEnvironment { source: None }));
+
+    match updates {
+        Ok(updates) => Ok(Watch { id, updates }),
+        Err(error) => {
+            lock_watchers().retain(|(watch_id, _)| *watch_id != id);
+            Err(error)
         }
+    }
+}
 
-        if !has_permission {
-            return None;
+/// Create a `LocationUpdates` listener feeding the watch `id`
+fn start_updates(
+    env: &mut JNIEnv<'_>,
+    activity: &JObject<'_>,
+    id: u64,
+    options: &LocationOptions,
+) -> Result<GlobalRef> {
+    if !has_location_permission(env, activity) {
+        return Err(Error::AuthorizationDenied);
+    }
+
+    let class = match load_class_from_classloader(env, "dioxus.mobile.geolocation.LocationUpdates")
+    {
+        Ok(class) => class,
+        Err(_) => {
+            let _ = env.exception_describe();
+            let _ = env.exception_clear();
+            return Err(Error::AndroidEnvironment { source: None });
         }
+    };
 
-        let service_name = new_string(env, "location").ok()?;
-        let location_manager = env
-            .call_method(
-                activity,
-                "getSystemService",
-                "(Ljava/lang/String;)Ljava/lang/Object;",
-                &[JValue::Object(&service_name)],
-            )
-            .ok()?
-            .l()
-            .ok()?;
+    // Re-registering is harmless, so there is no need to track whether the
+    // class was already loaded.
+    env.register_native_methods(
+        &class,
+        &[
+            NativeMethod {
+                name: "nativeOnLocation".into(),
+                sig: "(JLandroid/location/Location;)V".into(),
+                fn_ptr: native_on_location as *mut c_void,
+            },
+            NativeMethod {
+                name: "nativeOnProviderDisabled".into(),
+                sig: "(J)V".into(),
+                fn_ptr: native_on_provider_disabled as *mut c_void,
+            },
+        ],
+    )?;
 
-        let provider = new_string(env, "gps").ok()?;
-        let mut location = get_last_known_location(env, &location_manager, &provider)?;
+    let updates = match env.call_static_method(
+        &class,
+        "start",
+        "(Landroid/content/Context;JZZJF)Ldioxus/mobile/geolocation/LocationUpdates;",
+        &[
+            JValue::Object(activity),
+            JValue::Long(id as jlong),
+            JValue::Bool(u8::from(options.high_accuracy)),
+            JValue::Bool(u8::from(options.wants_indoor_mapping())),
+            JValue::Long(0),
+            JValue::Float(options.distance_filter.max(0.0) as f32),
+        ],
+    ) {
+        Ok(value) => value.l()?,
+        Err(_) => {
+            let _ = env.exception_describe();
+            let _ = env.exception_clear();
+            return Err(Error::AndroidEnvironment { source: None });
+        }
+    };
 
-        if location.is_null() {
-            let fused_provider = new_string(env, "fused").ok()?;
-            location = get_last_known_location(env, &location_manager, &fused_provider)?;
+    // `start` returns null when every location provider is switched off
+    if updates.is_null() {
+        return Err(Error::TemporarilyUnavailable);
+    }
+
+    Ok(env.new_global_ref(updates)?)
+}
+
+extern "system" fn native_on_location<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    id: jlong,
+    location: JObject<'local>,
+) {
+    if let Some(location) = location_from_object(&mut env, &location) {
+        deliver(id as u64, Ok(location));
+    }
+}
+
+extern "system" fn native_on_provider_disabled<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    id: jlong,
+) {
+    deliver(id as u64, Err(Error::TemporarilyUnavailable));
+}
+
+/// Call the callback of watch `id`, outside the registry lock so it may
+/// start or stop watches itself
+fn deliver(id: u64, result: Result<Location>) {
+    let callback = lock_watchers()
+        .iter()
+        .find(|(watch_id, _)| *watch_id == id)
+        .map(|(_, callback)| callback.clone());
+    if let Some(callback) = callback {
+        callback(result);
+    }
+}
+
+fn lock_watchers() -> MutexGuard<'static, Vec<(u64, LocationCallback)>> {
+    WATCHERS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Callbacks of the running `HeadingUpdates` listeners, keyed by watch id
+static HEADING_WATCHERS: Mutex<Vec<(u64, HeadingCallback)>> = Mutex::new(Vec::new());
+static NEXT_HEADING_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A running `HeadingUpdates` listener. Headings stop when it is dropped.
+pub struct HeadingWatch {
+    id: u64,
+    updates: GlobalRef,
+}
+
+impl Drop for HeadingWatch {
+    fn drop(&mut self) {
+        lock_heading_watchers().retain(|(id, _)| *id != self.id);
+        with_activity(|env, _activity| {
+            if env
+                .call_method(self.updates.as_obj(), "stop", "()V", &[])
+                .is_err()
+            {
+                let _ = env.exception_describe();
+                let _ = env.exception_clear();
+            }
+            Some(())
+        });
+    }
+}
+
+/// Start rotation vector sensor updates, calling `callback` on the main
+/// looper for each heading
+pub fn watch_heading(callback: HeadingCallback) -> Result<HeadingWatch> {
+    let id = NEXT_HEADING_ID.fetch_add(1, Ordering::Relaxed);
+    lock_heading_watchers().push((id, callback));
+
+    let updates = with_activity(|env, activity| Some(start_heading_updates(env, activity, id)))
+        .unwrap_or(Err(Error::AndroidEnvironment { source: None }));
+
+    match updates {
+        Ok(updates) => Ok(HeadingWatch { id, updates }),
+        Err(error) => {
+            lock_heading_watchers().retain(|(watch_id, _)| *watch_id != id);
+            Err(error)
+        }
+    }
+}
+
+/// Create a `HeadingUpdates` listener feeding the heading watch `id`
+fn start_heading_updates(
+    env: &mut JNIEnv<'_>,
+    activity: &JObject<'_>,
+    id: u64,
+) -> Result<GlobalRef> {
+    let class = match load_class_from_classloader(env, "dioxus.mobile.geolocation.HeadingUpdates") {
+        Ok(class) => class,
+        Err(_) => {
+            let _ = env.exception_describe();
+            let _ = env.exception_clear();
+            return Err(Error::AndroidEnvironment { source: None });
+        }
+    };
+
+    env.register_native_methods(
+        &class,
+        &[NativeMethod {
+            name: "nativeOnHeading".into(),
+            sig: "(JDD)V".into(),
+            fn_ptr: native_on_heading as *mut c_void,
+        }],
+    )?;
+
+    let updates = match env.call_static_method(
+        &class,
+        "start",
+        "(Landroid/content/Context;J)Ldioxus/mobile/geolocation/HeadingUpdates;",
+        &[JValue::Object(activity), JValue::Long(id as jlong)],
+    ) {
+        Ok(value) => value.l()?,
+        Err(_) => {
+            let _ = env.exception_describe();
+            let _ = env.exception_clear();
+            return Err(Error::AndroidEnvironment { source: None });
         }
+    };
 
-        if location.is_null() {
+    // `start` returns null when the device has no rotation vector sensor
+    if updates.is_null() {
+        return Err(Error::PermanentlyUnavailable);
+    }
+
+    Ok(env.new_global_ref(updates)?)
+}
+
+extern "system" fn native_on_heading<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    id: jlong,
+    heading: jdouble,
+    accuracy: jdouble,
+) {
+    let callback = lock_heading_watchers()
+        .iter()
+        .find(|(watch_id, _)| *watch_id == id as u64)
+        .map(|(_, callback)| callback.clone());
+    if let Some(callback) = callback {
+        callback(Ok(Heading {
+            magnetic_heading: heading,
+            true_heading: None,
+            accuracy,
+            timestamp: now(),
+        }));
+    }
+}
+
+fn lock_heading_watchers() -> MutexGuard<'static, Vec<(u64, HeadingCallback)>> {
+    HEADING_WATCHERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Callbacks of the running `SatelliteUpdates` listeners, keyed by watch id
+static SATELLITE_WATCHERS: Mutex<Vec<(u64, SatelliteCallback)>> = Mutex::new(Vec::new());
+static NEXT_SATELLITE_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Number of values `SatelliteUpdates` sends per satellite
+const SATELLITE_FIELDS: usize = 5;
+
+/// A running `SatelliteUpdates` listener. Updates stop when it is dropped.
+pub struct SatelliteWatch {
+    id: u64,
+    updates: GlobalRef,
+}
+
+impl Drop for SatelliteWatch {
+    fn drop(&mut self) {
+        lock_satellite_watchers().retain(|(id, _)| *id != self.id);
+        with_activity(|env, _activity| {
+            if env
+                .call_method(self.updates.as_obj(), "stop", "()V", &[])
+                .is_err()
+            {
+                let _ = env.exception_describe();
+                let _ = env.exception_clear();
+            }
+            Some(())
+        });
+    }
+}
+
+/// Start `GnssStatus` updates, calling `callback` on the main looper with
+/// the satellites of each status
+pub fn watch_satellites(callback: SatelliteCallback) -> Result<SatelliteWatch> {
+    let id = NEXT_SATELLITE_ID.fetch_add(1, Ordering::Relaxed);
+    lock_satellite_watchers().push((id, callback));
+
+    let updates = with_activity(|env, activity| Some(start_satellite_updates(env, activity, id)))
+        .unwrap_or(Err(Error::AndroidEnvironment { source: None }));
+
+    match updates {
+        Ok(updates) => Ok(SatelliteWatch { id, updates }),
+        Err(error) => {
+            lock_satellite_watchers().retain(|(watch_id, _)| *watch_id != id);
+            Err(error)
+        }
+    }
+}
+
+/// Create a `SatelliteUpdates` listener feeding the satellite watch `id`
+fn start_satellite_updates(
+    env: &mut JNIEnv<'_>,
+    activity: &JObject<'_>,
+    id: u64,
+) -> Result<GlobalRef> {
+    let class = match load_class_from_classloader(env, "dioxus.mobile.geolocation.SatelliteUpdates")
+    {
+        Ok(class) => class,
+        Err(_) => {
+            let _ = env.exception_describe();
+            let _ = env.exception_clear();
+            return Err(Error::AndroidEnvironment { source: None });
+        }
+    };
+
+    env.register_native_methods(
+        &class,
+        &[NativeMethod {
+            name: "nativeOnSatellites".into(),
+            sig: "(J[F)V".into(),
+            fn_ptr: native_on_satellites as *mut c_void,
+        }],
+    )?;
+
+    let updates = match env.call_static_method(
+        &class,
+        "start",
+        "(Landroid/content/Context;J)Ldioxus/mobile/geolocation/SatelliteUpdates;",
+        &[JValue::Object(activity), JValue::Long(id as jlong)],
+    ) {
+        Ok(value) => value.l()?,
+        Err(_) => {
+            let _ = env.exception_describe();
+            let _ = env.exception_clear();
+            return Err(Error::AndroidEnvironment { source: None });
+        }
+    };
+
+    // `start` returns null before API level 24 and without permission
+    if updates.is_null() {
+        return Err(if has_location_permission(env, activity) {
+            Error::PermanentlyUnavailable
+        } else {
+            Error::AuthorizationDenied
+        });
+    }
+
+    Ok(env.new_global_ref(updates)?)
+}
+
+extern "system" fn native_on_satellites<'local>(
+    env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    id: jlong,
+    values: JFloatArray<'local>,
+) {
+    let callback = lock_satellite_watchers()
+        .iter()
+        .find(|(watch_id, _)| *watch_id == id as u64)
+        .map(|(_, callback)| callback.clone());
+    let Some(callback) = callback else {
+        return;
+    };
+
+    let len = env.get_array_length(&values).unwrap_or(0) as usize;
+    let mut fields = vec![0.0; len];
+    if env.get_float_array_region(&values, 0, &mut fields).is_err() {
+        return;
+    }
+    let satellites = fields
+        .chunks_exact(SATELLITE_FIELDS)
+        .map(|satellite| GpsSatellite {
+            prn: satellite[0] as u8,
+            snr: satellite[1],
+            azimuth: satellite[2],
+            elevation: satellite[3],
+            used_in_fix: satellite[4] != 0.0,
+        })
+        .collect();
+    callback(satellites);
+}
+
+fn lock_satellite_watchers() -> MutexGuard<'static, Vec<(u64, SatelliteCallback)>> {
+    SATELLITE_WATCHERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Callbacks of the running `AltitudeUpdates` listeners, keyed by watch id
+#[cfg(feature = "barometer")]
+static ALTITUDE_WATCHERS: Mutex<Vec<(u64, AltitudeCallback)>> = Mutex::new(Vec::new());
+#[cfg(feature = "barometer")]
+static NEXT_ALTITUDE_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A running `AltitudeUpdates` listener. Readings stop when it is dropped.
+#[cfg(feature = "barometer")]
+pub struct AltitudeWatch {
+    id: u64,
+    updates: GlobalRef,
+}
+
+#[cfg(feature = "barometer")]
+impl Drop for AltitudeWatch {
+    fn drop(&mut self) {
+        lock_altitude_watchers().retain(|(id, _)| *id != self.id);
+        with_activity(|env, _activity| {
+            if env
+                .call_method(self.updates.as_obj(), "stop", "()V", &[])
+                .is_err()
+            {
+                let _ = env.exception_describe();
+                let _ = env.exception_clear();
+            }
+            Some(())
+        });
+    }
+}
+
+/// Start pressure sensor updates, calling `callback` on the main looper with
+/// the altitude change since the first reading
+#[cfg(feature = "barometer")]
+pub fn watch_altitude(callback: AltitudeCallback) -> Result<AltitudeWatch> {
+    let id = NEXT_ALTITUDE_ID.fetch_add(1, Ordering::Relaxed);
+    lock_altitude_watchers().push((id, callback));
+
+    let updates = with_activity(|env, activity| Some(start_altitude_updates(env, activity, id)))
+        .unwrap_or(Err(Error::AndroidEnvironment { source: None }));
+
+    match updates {
+        Ok(updates) => Ok(AltitudeWatch { id, updates }),
+        Err(error) => {
+            lock_altitude_watchers().retain(|(watch_id, _)| *watch_id != id);
+            Err(error)
+        }
+    }
+}
+
+/// Create an `AltitudeUpdates` listener feeding the altitude watch `id`
+#[cfg(feature = "barometer")]
+fn start_altitude_updates(
+    env: &mut JNIEnv<'_>,
+    activity: &JObject<'_>,
+    id: u64,
+) -> Result<GlobalRef> {
+    let class = match load_class_from_classloader(env, "dioxus.mobile.geolocation.AltitudeUpdates")
+    {
+        Ok(class) => class,
+        Err(_) => {
+            let _ = env.exception_describe();
+            let _ = env.exception_clear();
+            return Err(Error::AndroidEnvironment { source: None });
+        }
+    };
+
+    env.register_native_methods(
+        &class,
+        &[NativeMethod {
+            name: "nativeOnAltitude".into(),
+            sig: "(JD)V".into(),
+            fn_ptr: native_on_altitude as *mut c_void,
+        }],
+    )?;
+
+    let updates = match env.call_static_method(
+        &class,
+        "start",
+        "(Landroid/content/Context;J)Ldioxus/mobile/geolocation/AltitudeUpdates;",
+        &[JValue::Object(activity), JValue::Long(id as jlong)],
+    ) {
+        Ok(value) => value.l()?,
+        Err(_) => {
+            let _ = env.exception_describe();
+            let _ = env.exception_clear();
+            return Err(Error::AndroidEnvironment { source: None });
+        }
+    };
+
+    // `start` returns null when the device has no pressure sensor
+    if updates.is_null() {
+        return Err(Error::PermanentlyUnavailable);
+    }
+
+    Ok(env.new_global_ref(updates)?)
+}
+
+#[cfg(feature = "barometer")]
+extern "system" fn native_on_altitude<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    id: jlong,
+    altitude: jdouble,
+) {
+    let callback = lock_altitude_watchers()
+        .iter()
+        .find(|(watch_id, _)| *watch_id == id as u64)
+        .map(|(_, callback)| callback.clone());
+    if let Some(callback) = callback {
+        callback(Ok(altitude));
+    }
+}
+
+#[cfg(feature = "barometer")]
+fn lock_altitude_watchers() -> MutexGuard<'static, Vec<(u64, AltitudeCallback)>> {
+    ALTITUDE_WATCHERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Callbacks of the running `StepUpdates` listeners, keyed by watch id
+#[cfg(feature = "pedometer")]
+static PEDOMETER_WATCHERS: Mutex<Vec<(u64, PedometerCallback)>> = Mutex::new(Vec::new());
+#[cfg(feature = "pedometer")]
+static NEXT_PEDOMETER_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A running `StepUpdates` listener. Updates stop when it is dropped.
+#[cfg(feature = "pedometer")]
+pub struct PedometerWatch {
+    id: u64,
+    updates: GlobalRef,
+}
+
+#[cfg(feature = "pedometer")]
+impl Drop for PedometerWatch {
+    fn drop(&mut self) {
+        lock_pedometer_watchers().retain(|(id, _)| *id != self.id);
+        with_activity(|env, _activity| {
+            if env
+                .call_method(self.updates.as_obj(), "stop", "()V", &[])
+                .is_err()
+            {
+                let _ = env.exception_describe();
+                let _ = env.exception_clear();
+            }
+            Some(())
+        });
+    }
+}
+
+/// Start step counter updates, calling `callback` on the main looper with
+/// the steps taken since the first reading
+#[cfg(feature = "pedometer")]
+pub fn watch_pedometer(callback: PedometerCallback) -> Result<PedometerWatch> {
+    let id = NEXT_PEDOMETER_ID.fetch_add(1, Ordering::Relaxed);
+    lock_pedometer_watchers().push((id, callback));
+
+    let updates = with_activity(|env, activity| Some(start_step_updates(env, activity, id)))
+        .unwrap_or(Err(Error::AndroidEnvironment { source: None }));
+
+    match updates {
+        Ok(updates) => Ok(PedometerWatch { id, updates }),
+        Err(error) => {
+            lock_pedometer_watchers().retain(|(watch_id, _)| *watch_id != id);
+            Err(error)
+        }
+    }
+}
+
+/// Create a `StepUpdates` listener feeding the pedometer watch `id`
+#[cfg(feature = "pedometer")]
+fn start_step_updates(env: &mut JNIEnv<'_>, activity: &JObject<'_>, id: u64) -> Result<GlobalRef> {
+    let class = match load_class_from_classloader(env, "dioxus.mobile.geolocation.StepUpdates") {
+        Ok(class) => class,
+        Err(_) => {
+            let _ = env.exception_describe();
+            let _ = env.exception_clear();
+            return Err(Error::AndroidEnvironment { source: None });
+        }
+    };
+
+    env.register_native_methods(
+        &class,
+        &[NativeMethod {
+            name: "nativeOnSteps".into(),
+            sig: "(JI)V".into(),
+            fn_ptr: native_on_steps as *mut c_void,
+        }],
+    )?;
+
+    let updates = match env.call_static_method(
+        &class,
+        "start",
+        "(Landroid/content/Context;J)Ldioxus/mobile/geolocation/StepUpdates;",
+        &[JValue::Object(activity), JValue::Long(id as jlong)],
+    ) {
+        Ok(value) => value.l()?,
+        Err(_) => {
+            let _ = env.exception_describe();
+            let _ = env.exception_clear();
+            return Err(Error::AndroidEnvironment { source: None });
+        }
+    };
+
+    // `start` returns null when the device has no step counter
+    if updates.is_null() {
+        return Err(Error::PermanentlyUnavailable);
+    }
+
+    Ok(env.new_global_ref(updates)?)
+}
+
+#[cfg(feature = "pedometer")]
+extern "system" fn native_on_steps<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    id: jlong,
+    steps: jint,
+) {
+    let callback = lock_pedometer_watchers()
+        .iter()
+        .find(|(watch_id, _)| *watch_id == id as u64)
+        .map(|(_, callback)| callback.clone());
+    if let Some(callback) = callback {
+        // The step counter measures nothing but steps
+        callback(Ok(PedometerUpdate {
+            steps: steps.max(0) as u32,
+            distance: None,
+            floors_ascending: None,
+            floors_descending: None,
+            current_pace: None,
+        }));
+    }
+}
+
+#[cfg(feature = "pedometer")]
+fn lock_pedometer_watchers() -> MutexGuard<'static, Vec<(u64, PedometerCallback)>> {
+    PEDOMETER_WATCHERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+// `DetectedActivity` types reported by Play services Activity Recognition
+#[cfg(feature = "activity-recognition")]
+const DETECTED_ACTIVITY_IN_VEHICLE: jint = 0;
+#[cfg(feature = "activity-recognition")]
+const DETECTED_ACTIVITY_ON_BICYCLE: jint = 1;
+#[cfg(feature = "activity-recognition")]
+const DETECTED_ACTIVITY_ON_FOOT: jint = 2;
+#[cfg(feature = "activity-recognition")]
+const DETECTED_ACTIVITY_STILL: jint = 3;
+#[cfg(feature = "activity-recognition")]
+const DETECTED_ACTIVITY_WALKING: jint = 7;
+#[cfg(feature = "activity-recognition")]
+const DETECTED_ACTIVITY_RUNNING: jint = 8;
+
+/// Callbacks of the running `ActivityUpdates` receivers, keyed by watch id
+#[cfg(feature = "activity-recognition")]
+static ACTIVITY_WATCHERS: Mutex<Vec<(u64, ActivityCallback)>> = Mutex::new(Vec::new());
+#[cfg(feature = "activity-recognition")]
+static NEXT_ACTIVITY_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A running `ActivityUpdates` receiver. Updates stop when it is dropped.
+#[cfg(feature = "activity-recognition")]
+pub struct ActivityWatch {
+    id: u64,
+    updates: GlobalRef,
+}
+
+#[cfg(feature = "activity-recognition")]
+impl Drop for ActivityWatch {
+    fn drop(&mut self) {
+        lock_activity_watchers().retain(|(id, _)| *id != self.id);
+        with_activity(|env, _activity| {
+            if env
+                .call_method(self.updates.as_obj(), "stop", "()V", &[])
+                .is_err()
+            {
+                let _ = env.exception_describe();
+                let _ = env.exception_clear();
+            }
+            Some(())
+        });
+    }
+}
+
+/// Start Play services Activity Recognition updates, calling `callback` on
+/// the main looper for each detected activity
+#[cfg(feature = "activity-recognition")]
+pub fn watch_activity(callback: ActivityCallback) -> Result<ActivityWatch> {
+    let id = NEXT_ACTIVITY_ID.fetch_add(1, Ordering::Relaxed);
+    lock_activity_watchers().push((id, callback));
+
+    let updates = with_activity(|env, activity| Some(start_activity_updates(env, activity, id)))
+        .unwrap_or(Err(Error::AndroidEnvironment { source: None }));
+
+    match updates {
+        Ok(updates) => Ok(ActivityWatch { id, updates }),
+        Err(error) => {
+            lock_activity_watchers().retain(|(watch_id, _)| *watch_id != id);
+            Err(error)
+        }
+    }
+}
+
+/// Create an `ActivityUpdates` receiver feeding the activity watch `id`
+#[cfg(feature = "activity-recognition")]
+fn start_activity_updates(
+    env: &mut JNIEnv<'_>,
+    activity: &JObject<'_>,
+    id: u64,
+) -> Result<GlobalRef> {
+    let class = match load_class_from_classloader(env, "dioxus.mobile.geolocation.ActivityUpdates")
+    {
+        Ok(class) => class,
+        Err(_) => {
+            let _ = env.exception_describe();
+            let _ = env.exception_clear();
+            return Err(Error::AndroidEnvironment { source: None });
+        }
+    };
+
+    env.register_native_methods(
+        &class,
+        &[NativeMethod {
+            name: "nativeOnActivity".into(),
+            sig: "(JI)V".into(),
+            fn_ptr: native_on_activity as *mut c_void,
+        }],
+    )?;
+
+    let updates = match env.call_static_method(
+        &class,
+        "start",
+        "(Landroid/content/Context;J)Ldioxus/mobile/geolocation/ActivityUpdates;",
+        &[JValue::Object(activity), JValue::Long(id as jlong)],
+    ) {
+        Ok(value) => value.l()?,
+        Err(_) => {
+            let _ = env.exception_describe();
+            let _ = env.exception_clear();
+            return Err(Error::AndroidEnvironment { source: None });
+        }
+    };
+
+    // `start` returns null without Play services
+    if updates.is_null() {
+        return Err(Error::PermanentlyUnavailable);
+    }
+
+    Ok(env.new_global_ref(updates)?)
+}
+
+#[cfg(feature = "activity-recognition")]
+extern "system" fn native_on_activity<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    id: jlong,
+    activity_type: jint,
+) {
+    let callback = lock_activity_watchers()
+        .iter()
+        .find(|(watch_id, _)| *watch_id == id as u64)
+        .map(|(_, callback)| callback.clone());
+    if let Some(callback) = callback {
+        callback(match activity_type {
+            // Cycling and driving are one type, as for speed-based guesses
+            DETECTED_ACTIVITY_IN_VEHICLE | DETECTED_ACTIVITY_ON_BICYCLE => ActivityType::Vehicle,
+            DETECTED_ACTIVITY_ON_FOOT | DETECTED_ACTIVITY_WALKING => ActivityType::Walking,
+            DETECTED_ACTIVITY_RUNNING => ActivityType::Running,
+            DETECTED_ACTIVITY_STILL => ActivityType::Still,
+            _ => ActivityType::Unknown,
+        });
+    }
+}
+
+#[cfg(feature = "activity-recognition")]
+fn lock_activity_watchers() -> MutexGuard<'static, Vec<(u64, ActivityCallback)>> {
+    ACTIVITY_WATCHERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// A foreground-only watch: its options and callback, and its location
+/// updates while the activity is in the foreground
+struct ForegroundEntry {
+    id: u64,
+    options: LocationOptions,
+    callback: LocationCallback,
+    watch: Option<Watch>,
+}
+
+/// The running `LifecycleUpdates` listeners' watches, keyed by watch id
+static FOREGROUND_WATCHES: Mutex<Vec<ForegroundEntry>> = Mutex::new(Vec::new());
+static NEXT_FOREGROUND_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A location watch that stops while the activity is paused. Updates and
+/// the `LifecycleUpdates` listener stop when it is dropped.
+pub struct ForegroundWatch {
+    id: u64,
+    lifecycle: GlobalRef,
+}
+
+impl Drop for ForegroundWatch {
+    fn drop(&mut self) {
+        // Drop the watch outside the registry lock, as stopping it calls Java
+        let entry = {
+            let mut watches = lock_foreground_watches();
+            let index = watches.iter().position(|entry| entry.id == self.id);
+            index.map(|index| watches.remove(index))
+        };
+        drop(entry);
+        with_activity(|env, _activity| {
+            if env
+                .call_method(self.lifecycle.as_obj(), "stop", "()V", &[])
+                .is_err()
+            {
+                let _ = env.exception_describe();
+                let _ = env.exception_clear();
+            }
+            Some(())
+        });
+    }
+}
+
+/// Start location updates that stop while the activity is paused and
+/// restart when it resumes, calling `callback` on the main looper for each
+/// fix
+///
+/// The activity is taken to be in the foreground when this is called.
+pub fn watch_foreground_only(
+    options: &LocationOptions,
+    callback: LocationCallback,
+) -> Result<ForegroundWatch> {
+    let watch = watch(options, callback.clone())?;
+    let id = NEXT_FOREGROUND_ID.fetch_add(1, Ordering::Relaxed);
+    lock_foreground_watches().push(ForegroundEntry {
+        id,
+        options: *options,
+        callback,
+        watch: Some(watch),
+    });
+
+    let lifecycle = with_activity(|env, activity| Some(start_lifecycle_updates(env, activity, id)))
+        .unwrap_or(Err(Error::AndroidEnvironment { source: None }));
+
+    match lifecycle {
+        Ok(lifecycle) => Ok(ForegroundWatch { id, lifecycle }),
+        Err(error) => {
+            let entry = {
+                let mut watches = lock_foreground_watches();
+                let index = watches.iter().position(|entry| entry.id == id);
+                index.map(|index| watches.remove(index))
+            };
+            drop(entry);
+            Err(error)
+        }
+    }
+}
+
+/// Create a `LifecycleUpdates` listener feeding the foreground-only watch
+/// `id`
+fn start_lifecycle_updates(
+    env: &mut JNIEnv<'_>,
+    activity: &JObject<'_>,
+    id: u64,
+) -> Result<GlobalRef> {
+    let class = match load_class_from_classloader(env, "dioxus.mobile.geolocation.LifecycleUpdates")
+    {
+        Ok(class) => class,
+        Err(_) => {
+            let _ = env.exception_describe();
+            let _ = env.exception_clear();
+            return Err(Error::AndroidEnvironment { source: None });
+        }
+    };
+
+    env.register_native_methods(
+        &class,
+        &[NativeMethod {
+            name: "nativeOnForeground".into(),
+            sig: "(JZ)V".into(),
+            fn_ptr: native_on_foreground as *mut c_void,
+        }],
+    )?;
+
+    let updates = match env.call_static_method(
+        &class,
+        "start",
+        "(Landroid/app/Activity;J)Ldioxus/mobile/geolocation/LifecycleUpdates;",
+        &[JValue::Object(activity), JValue::Long(id as jlong)],
+    ) {
+        Ok(value) => value.l()?,
+        Err(_) => {
+            let _ = env.exception_describe();
+            let _ = env.exception_clear();
+            return Err(Error::AndroidEnvironment { source: None });
+        }
+    };
+
+    Ok(env.new_global_ref(updates)?)
+}
+
+extern "system" fn native_on_foreground<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    id: jlong,
+    foreground: jboolean,
+) {
+    let id = id as u64;
+    if foreground == 0 {
+        // Stop the updates outside the registry lock, as that calls Java
+        let watch = lock_foreground_watches()
+            .iter_mut()
+            .find(|entry| entry.id == id)
+            .and_then(|entry| entry.watch.take());
+        drop(watch);
+        return;
+    }
+
+    let paused = lock_foreground_watches()
+        .iter()
+        .find(|entry| entry.id == id && entry.watch.is_none())
+        .map(|entry| (entry.options, entry.callback.clone()));
+    let Some((options, callback)) = paused else {
+        return;
+    };
+
+    // The user may have revoked the permission in the system settings while
+    // the app was in the background. Checking it also emits
+    // `LocationEvent::PermissionDenied` then.
+    if !crate::permission_status().is_granted() {
+        callback(Err(Error::AuthorizationDenied));
+        return;
+    }
+    match watch(&options, callback.clone()) {
+        Ok(watch) => {
+            // The watch may have been dropped while the updates started
+            let unclaimed = match lock_foreground_watches()
+                .iter_mut()
+                .find(|entry| entry.id == id)
+            {
+                Some(entry) => entry.watch.replace(watch),
+                None => Some(watch),
+            };
+            drop(unclaimed);
+        }
+        Err(error) => callback(Err(error)),
+    }
+}
+
+fn lock_foreground_watches() -> MutexGuard<'static, Vec<ForegroundEntry>> {
+    FOREGROUND_WATCHES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Read every available field of an `android.location.Location`
+fn location_from_object(env: &mut JNIEnv<'_>, location: &JObject<'_>) -> Option<Location> {
+    let latitude = env
+        .call_method(location, "getLatitude", "()D", &[])
+        .ok()?
+        .d()
+        .ok()?;
+    let longitude = env
+        .call_method(location, "getLongitude", "()D", &[])
+        .ok()?
+        .d()
+        .ok()?;
+
+    // `Location.getTime()` is UTC milliseconds since the epoch
+    let millis = env
+        .call_method(location, "getTime", "()J", &[])
+        .ok()?
+        .j()
+        .ok()?;
+
+    Some(Location {
+        latitude,
+        longitude,
+        altitude: optional_field(env, location, "hasAltitude", "getAltitude", "()D"),
+        horizontal_accuracy: optional_field(env, location, "hasAccuracy", "getAccuracy", "()F"),
+        // Vertical accuracy was added in API level 26
+        vertical_accuracy: optional_field(
+            env,
+            location,
+            "hasVerticalAccuracy",
+            "getVerticalAccuracyMeters",
+            "()F",
+        ),
+        speed: optional_field(env, location, "hasSpeed", "getSpeed", "()F"),
+        course: optional_field(env, location, "hasBearing", "getBearing", "()F"),
+        timestamp: (millis > 0).then(|| UNIX_EPOCH + Duration::from_millis(millis as u64)),
+    })
+}
+
+/// Call the `has_method` / `get_method` pair of an `android.location.Location`
+/// field, returning `None` if the field isn't set or the methods don't exist
+/// on this API level. `signature` is the getter's, returning `D` or `F`.
+fn optional_field(
+    env: &mut JNIEnv<'_>,
+    location: &JObject<'_>,
+    has_method: &str,
+    get_method: &str,
+    signature: &str,
+) -> Option<f64> {
+    let value = env
+        .call_method(location, has_method, "()Z", &[])
+        .and_then(|has| has.z())
+        .and_then(|has| {
+            if has {
+                env.call_method(location, get_method, signature, &[])
+                    .map(Some)
+            } else {
+                Ok(None)
+            }
+        });
+
+    match value {
+        Ok(Some(value)) if signature == "()D" => value.d().ok(),
+        Ok(Some(value)) => value.f().map(f64::from).ok(),
+        Ok(None) => None,
+        Err(_) => {
+            let _ = env.exception_clear();
+            None
+        }
+    }
+}
+
+/// Number of values in the array returned by
+/// `PermissionsHelper.getFusedLastLocation()`
+const FUSED_LOCATION_FIELDS: usize = 8;
+
+/// The last known location, preferring Google Play services'
+/// `FusedLocationProviderClient` and falling back to `LocationManager` when
+/// Play services are missing or have no fix. Returns `None` if location
+/// permission has not been granted or no fix is cached.
+fn last_known_location(env: &mut JNIEnv<'_>, activity: &JObject<'_>) -> Option<Location> {
+    if !has_location_permission(env, activity) {
+        return None;
+    }
+
+    if let Some(location) = fused_last_location(env, activity) {
+        return Some(location);
+    }
+
+    let location = last_known_location_object(env, activity)?;
+    location_from_object(env, &location)
+}
+
+/// Ask `FusedLocationProviderClient.getLastLocation()` through
+/// `PermissionsHelper`, which looks up Play services by reflection and
+/// flattens the result to a `double[]` so no Play services class crosses
+/// JNI. Returns `None` if Play services are unavailable or have no fix.
+fn fused_last_location(env: &mut JNIEnv<'_>, activity: &JObject<'_>) -> Option<Location> {
+    let helper_class =
+        match load_class_from_classloader(env, "dioxus.mobile.geolocation.PermissionsHelper") {
+            Ok(class) => class,
+            Err(_) => {
+                let _ = env.exception_describe();
+                let _ = env.exception_clear();
+                return None;
+            }
+        };
+
+    let values = match env.call_static_method(
+        &helper_class,
+        "getFusedLastLocation",
+        "(Landroid/content/Context;)[D",
+        &[JValue::Object(activity)],
+    ) {
+        Ok(value) => value.l().ok()?,
+        Err(_) => {
+            let _ = env.exception_describe();
+            let _ = env.exception_clear();
             return None;
         }
+    };
+
+    if values.is_null() {
+        return None;
+    }
+
+    let mut fields = [jdouble::NAN; FUSED_LOCATION_FIELDS];
+    env.get_double_array_region(JDoubleArray::from(values), 0, &mut fields)
+        .ok()?;
+    Some(location_from_fields(&fields))
+}
+
+/// Build a `Location` from `[latitude, longitude, altitude, accuracy,
+/// verticalAccuracy, speed, bearing, timeMs]`, where `NaN` marks a missing
+/// field
+fn location_from_fields(fields: &[jdouble; FUSED_LOCATION_FIELDS]) -> Location {
+    let [latitude, longitude, altitude, horizontal_accuracy, vertical_accuracy, speed, course, millis] =
+        *fields;
+    let optional = |value: f64| (!value.is_nan()).then_some(value);
+
+    Location {
+        latitude,
+        longitude,
+        altitude: optional(altitude),
+        horizontal_accuracy: optional(horizontal_accuracy),
+        vertical_accuracy: optional(vertical_accuracy),
+        speed: optional(speed),
+        course: optional(course),
+        timestamp: (millis > 0.0).then(|| UNIX_EPOCH + Duration::from_millis(millis as u64)),
+    }
+}
+
+/// Query `LocationManager` for the last known `android.location.Location`,
+/// preferring GPS over the fused provider. Returns `None` if no fix is
+/// cached.
+fn last_known_location_object<'env>(
+    env: &mut JNIEnv<'env>,
+    activity: &JObject<'_>,
+) -> Option<JObject<'env>> {
+    let location_manager = get_location_manager(env, activity)?;
+
+    let provider = new_string(env, "gps").ok()?;
+    let mut location = get_last_known_location(env, &location_manager, &provider)?;
+
+    if location.is_null() {
+        let fused_provider = new_string(env, "fused").ok()?;
+        location = get_last_known_location(env, &location_manager, &fused_provider)?;
+    }
+
+    if location.is_null() {
+        return None;
+    }
+
+    Some(location)
+}
+
+/// Whether any of the location permissions enabled by the crate's features
+/// has been granted.
+fn has_location_permission(env: &mut JNIEnv<'_>, activity: &JObject<'_>) -> bool {
+    let mut has_permission = false;
+
+    #[cfg(feature = "location-fine")]
+    {
+        has_permission |=
+            check_self_permission(env, activity, "android.permission.ACCESS_FINE_LOCATION")
+                .unwrap_or(false);
+    }
+
+    #[cfg(feature = "location-coarse")]
+    {
+        has_permission |=
+            check_self_permission(env, activity, "android.permission.ACCESS_COARSE_LOCATION")
+                .unwrap_or(false);
+    }
+
+    #[cfg(not(any(feature = "location-fine", feature = "location-coarse")))]
+    {
+        has_permission = true;
+    }
+
+    has_permission
+}
+
+/// Expected battery impact of the provider location requests would use
+pub fn power_level() -> PowerLevel {
+    with_activity(|env, activity| {
+        if !has_location_permission(env, activity) {
+            return Some(PowerLevel::None);
+        }
+
+        let location_manager = get_location_manager(env, activity)?;
 
-        let latitude = env
-            .call_method(&location, "getLatitude", "()D", &[])
+        // Same preference order as `last_known()`: GPS first, then network
+        for name in ["gps", "network"] {
+            let name = new_string(env, name).ok()?;
+            let enabled = env
+                .call_method(
+                    &location_manager,
+                    "isProviderEnabled",
+                    "(Ljava/lang/String;)Z",
+                    &[JValue::Object(&name)],
+                )
+                .and_then(|value| value.z())
+                .unwrap_or(false);
+            if !enabled {
+                continue;
+            }
+
+            let provider = match env.call_method(
+                &location_manager,
+                "getProvider",
+                "(Ljava/lang/String;)Landroid/location/LocationProvider;",
+                &[JValue::Object(&name)],
+            ) {
+                Ok(value) => value.l().ok()?,
+                Err(_) => {
+                    let _ = env.exception_describe();
+                    let _ = env.exception_clear();
+                    continue;
+                }
+            };
+            if provider.is_null() {
+                continue;
+            }
+
+            let requirement = env
+                .call_method(&provider, "getPowerRequirement", "()I", &[])
+                .ok()?
+                .i()
+                .ok()?;
+            return Some(match requirement {
+                CRITERIA_POWER_LOW => PowerLevel::Low,
+                CRITERIA_POWER_MEDIUM => PowerLevel::Medium,
+                _ => PowerLevel::High,
+            });
+        }
+
+        Some(PowerLevel::None)
+    })
+    .unwrap_or(PowerLevel::None)
+}
+
+/// List every location provider known to `LocationManager`
+pub fn list_providers() -> Vec<LocationProviderInfo> {
+    with_activity(|env, activity| {
+        let location_manager = get_location_manager(env, activity)?;
+        let providers = env
+            .call_method(
+                &location_manager,
+                "getAllProviders",
+                "()Ljava/util/List;",
+                &[],
+            )
             .ok()?
-            .d()
+            .l()
             .ok()?;
-        let longitude = env
-            .call_method(&location, "getLongitude", "()D", &[])
+        let count = env
+            .call_method(&providers, "size", "()I", &[])
             .ok()?
-            .d()
+            .i()
             .ok()?;
 
-        Some((latitude, longitude))
+        let mut infos = Vec::with_capacity(count.max(0) as usize);
+        for index in 0..count {
+            let name = env
+                .call_method(
+                    &providers,
+                    "get",
+                    "(I)Ljava/lang/Object;",
+                    &[JValue::Int(index)],
+                )
+                .ok()?
+                .l()
+                .ok()?;
+            if let Some(info) = provider_info(env, &location_manager, &name) {
+                infos.push(info);
+            }
+        }
+        Some(infos)
+    })
+    .unwrap_or_default()
+}
+
+/// Describe the provider called `name`. Capability flags default to `false`
+/// if the provider can't be queried.
+fn provider_info<'env>(
+    env: &mut JNIEnv<'env>,
+    manager: &JObject<'env>,
+    name: &JObject<'env>,
+) -> Option<LocationProviderInfo> {
+    let name_string: String = env.get_string(<&JString>::from(name)).ok()?.into();
+
+    let enabled = env
+        .call_method(
+            manager,
+            "isProviderEnabled",
+            "(Ljava/lang/String;)Z",
+            &[JValue::Object(name)],
+        )
+        .and_then(|value| value.z())
+        .unwrap_or(false);
+
+    let provider = match env.call_method(
+        manager,
+        "getProvider",
+        "(Ljava/lang/String;)Landroid/location/LocationProvider;",
+        &[JValue::Object(name)],
+    ) {
+        Ok(value) => value.l().ok().filter(|provider| !provider.is_null()),
+        Err(_) => {
+            let _ = env.exception_describe();
+            let _ = env.exception_clear();
+            None
+        }
+    };
+    let mut requirement = |method: &str| {
+        provider.as_ref().is_some_and(|provider| {
+            env.call_method(provider, method, "()Z", &[])
+                .and_then(|value| value.z())
+                .unwrap_or(false)
+        })
+    };
+    let requires_network = requirement("requiresNetwork");
+    let requires_satellite = requirement("requiresSatellite");
+    let requires_cell = requirement("requiresCell");
+
+    // Android only reports accuracy in meters per fix, so use the provider's
+    // last known fix. Returns `None` without location permission.
+    let accuracy_meters = get_last_known_location(env, manager, name)
+        .filter(|location| !location.is_null())
+        .and_then(|location| {
+            let has_accuracy = env
+                .call_method(&location, "hasAccuracy", "()Z", &[])
+                .ok()?
+                .z()
+                .ok()?;
+            if !has_accuracy {
+                return None;
+            }
+            env.call_method(&location, "getAccuracy", "()F", &[])
+                .ok()?
+                .f()
+                .ok()
+        });
+
+    Some(LocationProviderInfo {
+        name: name_string,
+        enabled,
+        requires_network,
+        requires_satellite,
+        requires_cell,
+        accuracy_meters,
     })
 }
 
+fn get_location_manager<'env>(
+    env: &mut JNIEnv<'env>,
+    activity: &JObject<'_>,
+) -> Option<JObject<'env>> {
+    let service_name = new_string(env, "location").ok()?;
+    env.call_method(
+        activity,
+        "getSystemService",
+        "(Ljava/lang/String;)Ljava/lang/Object;",
+        &[JValue::Object(&service_name)],
+    )
+    .ok()?
+    .l()
+    .ok()
+}
+
 fn get_last_known_location<'env>(
     env: &mut JNIEnv<'env>,
     manager: &JObject<'env>,
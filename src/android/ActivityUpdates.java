@@ -0,0 +1,110 @@
+package dioxus.mobile.geolocation;
+
+import android.app.PendingIntent;
+import android.content.BroadcastReceiver;
+import android.content.Context;
+import android.content.Intent;
+import android.content.IntentFilter;
+import android.os.Build;
+
+/**
+ * Forwards Play services Activity Recognition results to Rust.
+ *
+ * Play services deliver the results to a {@link PendingIntent}, which is
+ * broadcast to this receiver. Like {@link PermissionsHelper}, this reaches
+ * Play services by reflection so apps without them still work.
+ *
+ * Each instance is identified by the id of the Rust activity watch it feeds;
+ * the native methods are registered from Rust when the class is first used.
+ */
+public final class ActivityUpdates extends BroadcastReceiver {
+    /** Prefix of the broadcast action, followed by the watch id */
+    private static final String ACTION = "dioxus.mobile.geolocation.ACTIVITY_UPDATE.";
+    /** How often Play services should detect the activity */
+    private static final long INTERVAL_MS = 10000;
+
+    private final long id;
+    private final Context context;
+    private final Object client;
+    private final PendingIntent pendingIntent;
+
+    private ActivityUpdates(long id, Context context, Object client, PendingIntent pendingIntent) {
+        this.id = id;
+        this.context = context;
+        this.client = client;
+        this.pendingIntent = pendingIntent;
+    }
+
+    /**
+     * Start delivering activities to the Rust watch {@code id}.
+     *
+     * Returns {@code null} if Play services are unavailable.
+     */
+    public static ActivityUpdates start(final Context context, final long id) {
+        try {
+            if (!PermissionsHelper.isPlayServicesAvailable(context)) {
+                return null;
+            }
+
+            Object client = Class.forName("com.google.android.gms.location.ActivityRecognition")
+                    .getMethod("getClient", Context.class)
+                    .invoke(null, context);
+
+            String action = ACTION + id;
+            Intent intent = new Intent(action).setPackage(context.getPackageName());
+            int flags = PendingIntent.FLAG_UPDATE_CURRENT;
+            if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.S) {
+                // Play services fill in the result
+                flags |= PendingIntent.FLAG_MUTABLE;
+            }
+            PendingIntent pendingIntent = PendingIntent.getBroadcast(context, (int) id, intent, flags);
+
+            ActivityUpdates updates = new ActivityUpdates(id, context, client, pendingIntent);
+            IntentFilter filter = new IntentFilter(action);
+            if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.TIRAMISU) {
+                context.registerReceiver(updates, filter, Context.RECEIVER_NOT_EXPORTED);
+            } else {
+                context.registerReceiver(updates, filter);
+            }
+
+            client.getClass()
+                    .getMethod("requestActivityUpdates", long.class, PendingIntent.class)
+                    .invoke(client, INTERVAL_MS, pendingIntent);
+            return updates;
+        } catch (Exception e) {
+            return null;
+        }
+    }
+
+    /** Stop delivering activities. */
+    public void stop() {
+        try {
+            client.getClass()
+                    .getMethod("removeActivityUpdates", PendingIntent.class)
+                    .invoke(client, pendingIntent);
+        } catch (Exception e) {
+            // Play services stop sending once the intent is cancelled
+        }
+        context.unregisterReceiver(this);
+        pendingIntent.cancel();
+    }
+
+    @Override
+    public void onReceive(Context context, Intent intent) {
+        try {
+            Class<?> resultClass =
+                    Class.forName("com.google.android.gms.location.ActivityRecognitionResult");
+            if (!(Boolean) resultClass.getMethod("hasResult", Intent.class).invoke(null, intent)) {
+                return;
+            }
+            Object result = resultClass.getMethod("extractResult", Intent.class).invoke(null, intent);
+            Object activity = resultClass.getMethod("getMostProbableActivity").invoke(result);
+            int type = (Integer) activity.getClass().getMethod("getType").invoke(activity);
+            nativeOnActivity(id, type);
+        } catch (Exception e) {
+            // Not an Activity Recognition result
+        }
+    }
+
+    private static native void nativeOnActivity(long id, int type);
+}
@@ -0,0 +1,71 @@
+package dioxus.mobile.geolocation;
+
+import android.content.Context;
+import android.hardware.Sensor;
+import android.hardware.SensorEvent;
+import android.hardware.SensorEventListener;
+import android.hardware.SensorManager;
+import android.os.Handler;
+import android.os.Looper;
+
+/**
+ * Forwards pressure sensor readings to Rust as altitude changes in meters
+ * since the first reading.
+ *
+ * Each instance is identified by the id of the Rust altitude watch it feeds;
+ * the native methods are registered from Rust when the class is first used.
+ */
+public final class AltitudeUpdates implements SensorEventListener {
+    private final long id;
+    private final SensorManager manager;
+    private float startAltitude = Float.NaN;
+
+    private AltitudeUpdates(long id, SensorManager manager) {
+        this.id = id;
+        this.manager = manager;
+    }
+
+    /**
+     * Start delivering altitude changes to the Rust watch {@code id}.
+     *
+     * Returns {@code null} if the device has no pressure sensor.
+     */
+    public static AltitudeUpdates start(final Context context, final long id) {
+        SensorManager manager =
+                (SensorManager) context.getSystemService(Context.SENSOR_SERVICE);
+        if (manager == null) {
+            return null;
+        }
+
+        Sensor sensor = manager.getDefaultSensor(Sensor.TYPE_PRESSURE);
+        if (sensor == null) {
+            return null;
+        }
+
+        AltitudeUpdates updates = new AltitudeUpdates(id, manager);
+        manager.registerListener(
+                updates, sensor, SensorManager.SENSOR_DELAY_UI, new Handler(Looper.getMainLooper()));
+        return updates;
+    }
+
+    /** Stop delivering altitude changes. */
+    public void stop() {
+        manager.unregisterListener(this);
+    }
+
+    @Override
+    public void onSensorChanged(SensorEvent event) {
+        // values[0] is the pressure in hPa
+        float altitude =
+                SensorManager.getAltitude(SensorManager.PRESSURE_STANDARD_ATMOSPHERE, event.values[0]);
+        if (Float.isNaN(startAltitude)) {
+            startAltitude = altitude;
+        }
+        nativeOnAltitude(id, altitude - startAltitude);
+    }
+
+    @Override
+    public void onAccuracyChanged(Sensor sensor, int accuracy) {}
+
+    private static native void nativeOnAltitude(long id, double altitude);
+}
@@ -0,0 +1,74 @@
+package dioxus.mobile.geolocation;
+
+import android.content.Context;
+import android.hardware.Sensor;
+import android.hardware.SensorEvent;
+import android.hardware.SensorEventListener;
+import android.hardware.SensorManager;
+import android.os.Handler;
+import android.os.Looper;
+
+/**
+ * Forwards rotation vector sensor readings to Rust as compass headings.
+ *
+ * Each instance is identified by the id of the Rust heading watch it feeds;
+ * the native methods are registered from Rust when the class is first used.
+ */
+public final class HeadingUpdates implements SensorEventListener {
+    private final long id;
+    private final SensorManager manager;
+    private final float[] rotation = new float[9];
+    private final float[] orientation = new float[3];
+
+    private HeadingUpdates(long id, SensorManager manager) {
+        this.id = id;
+        this.manager = manager;
+    }
+
+    /**
+     * Start delivering headings to the Rust watch {@code id}.
+     *
+     * Returns {@code null} if the device has no rotation vector sensor.
+     */
+    public static HeadingUpdates start(final Context context, final long id) {
+        SensorManager manager =
+                (SensorManager) context.getSystemService(Context.SENSOR_SERVICE);
+        if (manager == null) {
+            return null;
+        }
+
+        Sensor sensor = manager.getDefaultSensor(Sensor.TYPE_ROTATION_VECTOR);
+        if (sensor == null) {
+            return null;
+        }
+
+        HeadingUpdates updates = new HeadingUpdates(id, manager);
+        manager.registerListener(
+                updates, sensor, SensorManager.SENSOR_DELAY_UI, new Handler(Looper.getMainLooper()));
+        return updates;
+    }
+
+    /** Stop delivering headings. */
+    public void stop() {
+        manager.unregisterListener(this);
+    }
+
+    @Override
+    public void onSensorChanged(SensorEvent event) {
+        SensorManager.getRotationMatrixFromVector(rotation, event.values);
+        SensorManager.getOrientation(rotation, orientation);
+        double heading = (Math.toDegrees(orientation[0]) + 360.0) % 360.0;
+
+        // values[4] is the estimated heading accuracy in radians, or -1 if unknown
+        double accuracy = -1.0;
+        if (event.values.length > 4 && event.values[4] >= 0) {
+            accuracy = Math.toDegrees(event.values[4]);
+        }
+        nativeOnHeading(id, heading, accuracy);
+    }
+
+    @Override
+    public void onAccuracyChanged(Sensor sensor, int accuracy) {}
+
+    private static native void nativeOnHeading(long id, double heading, double accuracy);
+}
@@ -0,0 +1,68 @@
+package dioxus.mobile.geolocation;
+
+import android.app.Activity;
+import android.app.Application;
+import android.os.Bundle;
+
+/**
+ * Forwards an activity's pause and resume events to Rust, so foreground-only
+ * watches can stop while the app is in the background.
+ *
+ * Each instance is identified by the id of the Rust watch it feeds; the
+ * native methods are registered from Rust when the class is first used.
+ */
+public final class LifecycleUpdates implements Application.ActivityLifecycleCallbacks {
+    private final long id;
+    private final Activity activity;
+
+    private LifecycleUpdates(long id, Activity activity) {
+        this.id = id;
+        this.activity = activity;
+    }
+
+    /**
+     * Start delivering the pause and resume events of {@code activity} to
+     * the Rust watch {@code id}.
+     */
+    public static LifecycleUpdates start(final Activity activity, final long id) {
+        LifecycleUpdates updates = new LifecycleUpdates(id, activity);
+        activity.getApplication().registerActivityLifecycleCallbacks(updates);
+        return updates;
+    }
+
+    /** Stop delivering events. */
+    public void stop() {
+        activity.getApplication().unregisterActivityLifecycleCallbacks(this);
+    }
+
+    @Override
+    public void onActivityResumed(Activity activity) {
+        if (activity == this.activity) {
+            nativeOnForeground(id, true);
+        }
+    }
+
+    @Override
+    public void onActivityPaused(Activity activity) {
+        if (activity == this.activity) {
+            nativeOnForeground(id, false);
+        }
+    }
+
+    @Override
+    public void onActivityCreated(Activity activity, Bundle savedInstanceState) {}
+
+    @Override
+    public void onActivityStarted(Activity activity) {}
+
+    @Override
+    public void onActivityStopped(Activity activity) {}
+
+    @Override
+    public void onActivitySaveInstanceState(Activity activity, Bundle outState) {}
+
+    @Override
+    public void onActivityDestroyed(Activity activity) {}
+
+    private static native void nativeOnForeground(long id, boolean foreground);
+}
@@ -0,0 +1,89 @@
+package dioxus.mobile.geolocation;
+
+import android.content.Context;
+import android.location.Location;
+import android.location.LocationListener;
+import android.location.LocationManager;
+import android.os.Bundle;
+import android.os.Looper;
+
+/**
+ * Forwards {@link LocationManager} updates to Rust.
+ *
+ * Each instance is identified by the id of the Rust watch it feeds; the
+ * native methods are registered from Rust when the class is first used.
+ */
+public final class LocationUpdates implements LocationListener {
+    private final long id;
+    private final LocationManager manager;
+
+    private LocationUpdates(long id, LocationManager manager) {
+        this.id = id;
+        this.manager = manager;
+    }
+
+    /**
+     * Start delivering updates to the Rust watch {@code id}.
+     *
+     * Prefers GPS when {@code highAccuracy} is set and the network provider
+     * otherwise, falling back to whichever is enabled. {@code indoor} prefers
+     * the network provider even with {@code highAccuracy}, as it locates the
+     * device from Wi-Fi where satellites can't be seen. Returns {@code null}
+     * if no provider is enabled.
+     */
+    public static LocationUpdates start(
+            final Context context,
+            final long id,
+            final boolean highAccuracy,
+            final boolean indoor,
+            final long minTimeMs,
+            final float minDistanceM
+    ) {
+        LocationManager manager =
+                (LocationManager) context.getSystemService(Context.LOCATION_SERVICE);
+        if (manager == null) {
+            return null;
+        }
+
+        boolean gps = manager.isProviderEnabled(LocationManager.GPS_PROVIDER);
+        boolean network = manager.isProviderEnabled(LocationManager.NETWORK_PROVIDER);
+        String provider;
+        if (gps && ((highAccuracy && !indoor) || !network)) {
+            provider = LocationManager.GPS_PROVIDER;
+        } else if (network) {
+            provider = LocationManager.NETWORK_PROVIDER;
+        } else {
+            return null;
+        }
+
+        LocationUpdates updates = new LocationUpdates(id, manager);
+        manager.requestLocationUpdates(
+                provider, minTimeMs, minDistanceM, updates, Looper.getMainLooper());
+        return updates;
+    }
+
+    /** Stop delivering updates. */
+    public void stop() {
+        manager.removeUpdates(this);
+    }
+
+    @Override
+    public void onLocationChanged(Location location) {
+        nativeOnLocation(id, location);
+    }
+
+    @Override
+    public void onProviderDisabled(String provider) {
+        nativeOnProviderDisabled(id);
+    }
+
+    @Override
+    public void onProviderEnabled(String provider) {}
+
+    @Override
+    public void onStatusChanged(String provider, int status, Bundle extras) {}
+
+    private static native void nativeOnLocation(long id, Location location);
+
+    private static native void nativeOnProviderDisabled(long id);
+}
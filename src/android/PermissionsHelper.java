@@ -1,11 +1,22 @@
 package dioxus.mobile.geolocation;
 
 import android.app.Activity;
+import android.content.Context;
+import android.location.Location;
+import android.os.Build;
+import java.lang.reflect.Method;
+import java.util.concurrent.TimeUnit;
 
 /**
- * Utility to ensure permission requests execute on the main thread.
+ * Utility to ensure permission requests execute on the main thread, and to
+ * reach Google Play services without linking against them.
  */
 public final class PermissionsHelper {
+    /** {@code ConnectionResult.SUCCESS} */
+    private static final int PLAY_SERVICES_SUCCESS = 0;
+    /** How long to wait for the fused provider's cached fix */
+    private static final long FUSED_TIMEOUT_MS = 5000;
+
     private PermissionsHelper() {}
 
     public static void requestPermissionsOnUiThread(
@@ -20,4 +31,68 @@ public final class PermissionsHelper {
             }
         });
     }
+
+    /**
+     * The last location of Play services' {@code FusedLocationProviderClient}.
+     *
+     * Play services are looked up by reflection so apps without them still
+     * work. The location is flattened to {@code [latitude, longitude,
+     * altitude, accuracy, verticalAccuracy, speed, bearing, timeMs]}, with
+     * {@code NaN} for missing fields. Returns {@code null} if Play services
+     * are unavailable, no fix is cached, or this is the main thread, where
+     * waiting for the result isn't allowed.
+     */
+    public static double[] getFusedLastLocation(final Context context) {
+        try {
+            if (!isPlayServicesAvailable(context)) {
+                return null;
+            }
+
+            Object client = Class.forName("com.google.android.gms.location.LocationServices")
+                    .getMethod("getFusedLocationProviderClient", Context.class)
+                    .invoke(null, context);
+            Object task = client.getClass().getMethod("getLastLocation").invoke(client);
+
+            Class<?> taskClass = Class.forName("com.google.android.gms.tasks.Task");
+            Method await = Class.forName("com.google.android.gms.tasks.Tasks")
+                    .getMethod("await", taskClass, long.class, TimeUnit.class);
+            Location location = (Location) await.invoke(
+                    null, task, FUSED_TIMEOUT_MS, TimeUnit.MILLISECONDS);
+
+            return location == null ? null : flatten(location);
+        } catch (Exception e) {
+            // Missing classes, no permission, timeouts and main thread calls
+            // all fall back to LocationManager
+            return null;
+        }
+    }
+
+    static boolean isPlayServicesAvailable(final Context context) throws Exception {
+        Class<?> availabilityClass;
+        try {
+            availabilityClass = Class.forName("com.google.android.gms.common.GoogleApiAvailability");
+        } catch (ClassNotFoundException e) {
+            return false;
+        }
+        Object availability = availabilityClass.getMethod("getInstance").invoke(null);
+        Object result = availabilityClass
+                .getMethod("isGooglePlayServicesAvailable", Context.class)
+                .invoke(availability, context);
+        return (Integer) result == PLAY_SERVICES_SUCCESS;
+    }
+
+    private static double[] flatten(final Location location) {
+        boolean hasVerticalAccuracy = Build.VERSION.SDK_INT >= Build.VERSION_CODES.O
+                && location.hasVerticalAccuracy();
+        return new double[] {
+            location.getLatitude(),
+            location.getLongitude(),
+            location.hasAltitude() ? location.getAltitude() : Double.NaN,
+            location.hasAccuracy() ? location.getAccuracy() : Double.NaN,
+            hasVerticalAccuracy ? location.getVerticalAccuracyMeters() : Double.NaN,
+            location.hasSpeed() ? location.getSpeed() : Double.NaN,
+            location.hasBearing() ? location.getBearing() : Double.NaN,
+            location.getTime(),
+        };
+    }
 }
@@ -0,0 +1,76 @@
+package dioxus.mobile.geolocation;
+
+import android.content.Context;
+import android.location.GnssStatus;
+import android.location.LocationManager;
+import android.os.Build;
+import android.os.Handler;
+import android.os.Looper;
+
+/**
+ * Forwards GNSS satellite status to Rust.
+ *
+ * Each instance is identified by the id of the Rust satellite watch it feeds;
+ * the native methods are registered from Rust when the class is first used.
+ */
+public final class SatelliteUpdates extends GnssStatus.Callback {
+    /** Values sent to Rust per satellite: svid, C/N0, azimuth, elevation, used in fix */
+    private static final int FIELDS = 5;
+
+    private final long id;
+    private final LocationManager manager;
+
+    private SatelliteUpdates(long id, LocationManager manager) {
+        this.id = id;
+        this.manager = manager;
+    }
+
+    /**
+     * Start delivering satellite status to the Rust watch {@code id}.
+     *
+     * Returns {@code null} before API level 24, without a location service,
+     * or without fine location permission.
+     */
+    public static SatelliteUpdates start(final Context context, final long id) {
+        if (Build.VERSION.SDK_INT < Build.VERSION_CODES.N) {
+            return null;
+        }
+
+        LocationManager manager =
+                (LocationManager) context.getSystemService(Context.LOCATION_SERVICE);
+        if (manager == null) {
+            return null;
+        }
+
+        SatelliteUpdates updates = new SatelliteUpdates(id, manager);
+        try {
+            if (!manager.registerGnssStatusCallback(updates, new Handler(Looper.getMainLooper()))) {
+                return null;
+            }
+        } catch (SecurityException e) {
+            return null;
+        }
+        return updates;
+    }
+
+    /** Stop delivering satellite status. */
+    public void stop() {
+        manager.unregisterGnssStatusCallback(this);
+    }
+
+    @Override
+    public void onSatelliteStatusChanged(GnssStatus status) {
+        int count = status.getSatelliteCount();
+        float[] values = new float[count * FIELDS];
+        for (int i = 0; i < count; i++) {
+            values[i * FIELDS] = status.getSvid(i);
+            values[i * FIELDS + 1] = status.getCn0DbHz(i);
+            values[i * FIELDS + 2] = status.getAzimuthDegrees(i);
+            values[i * FIELDS + 3] = status.getElevationDegrees(i);
+            values[i * FIELDS + 4] = status.usedInFix(i) ? 1f : 0f;
+        }
+        nativeOnSatellites(id, values);
+    }
+
+    private static native void nativeOnSatellites(long id, float[] values);
+}
@@ -0,0 +1,70 @@
+package dioxus.mobile.geolocation;
+
+import android.content.Context;
+import android.hardware.Sensor;
+import android.hardware.SensorEvent;
+import android.hardware.SensorEventListener;
+import android.hardware.SensorManager;
+import android.os.Handler;
+import android.os.Looper;
+
+/**
+ * Forwards step counter readings to Rust as the steps taken since the first
+ * reading.
+ *
+ * Each instance is identified by the id of the Rust pedometer watch it
+ * feeds; the native methods are registered from Rust when the class is
+ * first used.
+ */
+public final class StepUpdates implements SensorEventListener {
+    private final long id;
+    private final SensorManager manager;
+    private float startSteps = Float.NaN;
+
+    private StepUpdates(long id, SensorManager manager) {
+        this.id = id;
+        this.manager = manager;
+    }
+
+    /**
+     * Start delivering step counts to the Rust watch {@code id}.
+     *
+     * Returns {@code null} if the device has no step counter.
+     */
+    public static StepUpdates start(final Context context, final long id) {
+        SensorManager manager =
+                (SensorManager) context.getSystemService(Context.SENSOR_SERVICE);
+        if (manager == null) {
+            return null;
+        }
+
+        Sensor sensor = manager.getDefaultSensor(Sensor.TYPE_STEP_COUNTER);
+        if (sensor == null) {
+            return null;
+        }
+
+        StepUpdates updates = new StepUpdates(id, manager);
+        manager.registerListener(
+                updates, sensor, SensorManager.SENSOR_DELAY_UI, new Handler(Looper.getMainLooper()));
+        return updates;
+    }
+
+    /** Stop delivering step counts. */
+    public void stop() {
+        manager.unregisterListener(this);
+    }
+
+    @Override
+    public void onSensorChanged(SensorEvent event) {
+        // values[0] is the number of steps since the last reboot
+        if (Float.isNaN(startSteps)) {
+            startSteps = event.values[0];
+        }
+        nativeOnSteps(id, (int) (event.values[0] - startSteps));
+    }
+
+    @Override
+    public void onAccuracyChanged(Sensor sensor, int accuracy) {}
+
+    private static native void nativeOnSteps(long id, int steps);
+}
@@ -0,0 +1,37 @@
+//! Barometric altitude updates
+//!
+//! The barometer measures air pressure, so it tracks height changes much
+//! faster and more precisely than GPS altitude, but not the height above
+//! sea level. Readings are reported relative to the first one.
+
+use crate::Result;
+use std::sync::Arc;
+
+#[cfg(target_os = "android")]
+use crate::android as platform;
+#[cfg(target_os = "ios")]
+use crate::darwin as platform;
+
+/// Callback invoked by a platform altitude watch for each reading or error.
+pub(crate) type AltitudeCallback = Arc<dyn Fn(Result<f64>) + Send + Sync>;
+
+/// A running platform altitude watch. Updates stop when it is dropped.
+pub(crate) type AltitudeWatch = platform::AltitudeWatch;
+
+/// Start altitude updates, calling `callback` for each reading or error.
+pub(crate) fn watch(callback: AltitudeCallback) -> Result<AltitudeWatch> {
+    platform::watch_altitude(callback)
+}
+
+/// Platforms without a barometer API
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod platform {
+    use super::AltitudeCallback;
+    use crate::{Error, Result};
+
+    pub struct AltitudeWatch;
+
+    pub fn watch_altitude(_callback: AltitudeCallback) -> Result<AltitudeWatch> {
+        Err(Error::PermanentlyUnavailable)
+    }
+}
@@ -0,0 +1,71 @@
+//! Base64-encoded binary coordinate arrays
+//!
+//! Each coordinate is stored as its latitude and longitude, both as
+//! little-endian IEEE 754 doubles, and the byte stream is written in standard
+//! padded base64. That is about 21 characters per coordinate, with no loss of
+//! precision.
+
+use crate::{Coordinates, Error, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+/// Bytes per encoded coordinate: two `f64`s
+const COORDINATE_BYTES: usize = 16;
+
+/// Encode `coords` as base64 of their little-endian latitude/longitude
+/// doubles.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::{
+///     decode_coordinates_from_base64, encode_coordinates_as_base64, Coordinates,
+/// };
+///
+/// let route = [Coordinates::new(52.52, 13.405), Coordinates::new(48.8566, 2.3522)];
+/// let encoded = encode_coordinates_as_base64(&route);
+/// assert_eq!(encoded.len(), 44);
+///
+/// let decoded = decode_coordinates_from_base64(&encoded).unwrap();
+/// assert_eq!(decoded[1].longitude, route[1].longitude);
+/// ```
+pub fn encode_coordinates_as_base64(coords: &[Coordinates]) -> String {
+    let mut bytes = Vec::with_capacity(coords.len() * COORDINATE_BYTES);
+    for coord in coords {
+        bytes.extend_from_slice(&coord.latitude.to_le_bytes());
+        bytes.extend_from_slice(&coord.longitude.to_le_bytes());
+    }
+    STANDARD.encode(bytes)
+}
+
+/// Decode coordinates written by [`encode_coordinates_as_base64`].
+///
+/// Fails with [`Error::InvalidCoordinates`] if `s` isn't valid base64, its
+/// length isn't a whole number of coordinates, or a latitude or longitude
+/// is out of range.
+pub fn decode_coordinates_from_base64(s: &str) -> Result<Vec<Coordinates>> {
+    let bytes = STANDARD.decode(s).map_err(|_| Error::InvalidCoordinates)?;
+    if bytes.len() % COORDINATE_BYTES != 0 {
+        return Err(Error::InvalidCoordinates);
+    }
+
+    bytes
+        .chunks_exact(COORDINATE_BYTES)
+        .map(|chunk| {
+            let (latitude, longitude) = chunk.split_at(COORDINATE_BYTES / 2);
+            let (latitude, longitude) = (read_f64(latitude), read_f64(longitude));
+            if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+                return Err(Error::InvalidCoordinates);
+            }
+            Ok(Coordinates {
+                latitude,
+                longitude,
+            })
+        })
+        .collect()
+}
+
+/// A little-endian `f64` from exactly eight bytes
+fn read_f64(bytes: &[u8]) -> f64 {
+    let mut array = [0; 8];
+    array.copy_from_slice(bytes);
+    f64::from_le_bytes(array)
+}
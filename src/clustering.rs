@@ -0,0 +1,50 @@
+//! Clusters of nearby points
+
+use crate::geo_math::{from_unit_vector, to_unit_vector};
+use crate::Coordinates;
+
+/// Geographic centroid of each cluster in `clusters`, where a cluster lists
+/// indices into `points`, as produced by DBSCAN.
+///
+/// Each centroid is the spherical mean: the direction of the sum of the
+/// points' unit vectors, so a cluster straddling the antimeridian is
+/// centered on it rather than on the other side of the globe. An empty
+/// cluster, or one whose points cancel out, gets `NaN` coordinates.
+///
+/// # Panics
+///
+/// Panics if an index is out of bounds for `points`.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::{cluster_centroids, Coordinates};
+///
+/// let points = [
+///     Coordinates::new(-17.0, 179.0),
+///     Coordinates::new(-17.0, -179.0),
+///     Coordinates::new(52.52, 13.405),
+/// ];
+/// let centroids = cluster_centroids(&[vec![0, 1], vec![2]], &points);
+/// assert!((centroids[0].longitude.abs() - 180.0).abs() < 1e-9);
+/// assert!((centroids[1].latitude - 52.52).abs() < 1e-9);
+/// ```
+pub fn cluster_centroids(clusters: &[Vec<usize>], points: &[Coordinates]) -> Vec<Coordinates> {
+    clusters
+        .iter()
+        .map(|cluster| {
+            let (x, y, z) = cluster
+                .iter()
+                .map(|&index| to_unit_vector(points[index]))
+                .fold((0.0, 0.0, 0.0), |sum, v| {
+                    (sum.0 + v.0, sum.1 + v.1, sum.2 + v.2)
+                });
+            if (x * x + y * y + z * z).sqrt() < 1e-12 {
+                Coordinates {
+                    latitude: f64::NAN,
+                    longitude: f64::NAN,
+                }
+            } else {
+                from_unit_vector((x, y, z))
+            }
+        })
+        .collect()
+}
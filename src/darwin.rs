@@ -4,30 +4,709 @@
 //! APIs for location services. This module provides a unified implementation
 //! for both platforms.
 
+use crate::events;
+use crate::geofence::{
+    GeofenceCallback, GeofenceEvent, GeofenceEventKind, GeofenceRegion, MAX_GEOFENCES,
+};
+use crate::heading::{Heading, HeadingCallback};
+use crate::watch::LocationCallback;
+use crate::{AccuracyTier, Error, Location, LocationOptions, PermissionStatus, PowerLevel, Result};
 use dioxus_platform_bridge::darwin::MainThreadCell;
 use objc2::rc::Retained;
-use objc2::MainThreadMarker;
-use objc2_core_location::{CLAuthorizationStatus, CLLocation, CLLocationManager};
+use objc2::runtime::ProtocolObject;
+use objc2::{
+    define_class, msg_send, sel, AllocAnyThread, ClassType, MainThreadMarker, MainThreadOnly,
+};
+use objc2_core_location::{
+    kCLDistanceFilterNone, kCLLocationAccuracyBest, kCLLocationAccuracyBestForNavigation,
+    kCLLocationAccuracyHundredMeters, kCLLocationAccuracyKilometer,
+    kCLLocationAccuracyNearestTenMeters, kCLLocationAccuracyThreeKilometers,
+    CLAccuracyAuthorization, CLAuthorizationStatus, CLCircularRegion, CLHeading, CLLocation,
+    CLLocationAccuracy, CLLocationCoordinate2D, CLLocationManager, CLLocationManagerDelegate,
+    CLRegion,
+};
+use objc2_foundation::{
+    NSArray, NSDate, NSDefaultRunLoopMode, NSError, NSNotification, NSNotificationCenter, NSObject,
+    NSObjectProtocol, NSRunLoop, NSString,
+};
+use std::cell::{Cell, RefCell};
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// Keychain persistence
+#[cfg(feature = "secure-storage")]
+mod keychain;
+
+#[cfg(feature = "secure-storage")]
+pub use keychain::{load_location_from_keychain, save_last_location_to_keychain};
+
+// CoreMotion sensors, which macOS lacks
+#[cfg(all(
+    target_os = "ios",
+    any(
+        feature = "barometer",
+        feature = "pedometer",
+        feature = "activity-recognition"
+    )
+))]
+mod motion;
+
+#[cfg(all(target_os = "ios", feature = "activity-recognition"))]
+pub use motion::{watch_activity, ActivityWatch};
+
+#[cfg(all(target_os = "ios", feature = "barometer"))]
+pub use motion::{watch_altitude, AltitudeWatch};
+#[cfg(all(target_os = "ios", feature = "pedometer"))]
+pub use motion::{watch_pedometer, PedometerWatch};
 
 /// Global location manager instance
 static LOCATION_MANAGER: MainThreadCell<Retained<CLLocationManager>> = MainThreadCell::new();
 
-/// Get or create the global location manager
-fn get_location_manager(mtm: MainThreadMarker) -> &'static Retained<CLLocationManager> {
-    LOCATION_MANAGER.get_or_init_with(mtm, || {
+/// Delegate of the global location manager, which only holds it weakly
+static LOCATION_DELEGATE: MainThreadCell<Retained<LocationDelegate>> = MainThreadCell::new();
+
+/// Observer of the app lifecycle notifications, which `NSNotificationCenter`
+/// only holds weakly
+static LIFECYCLE_OBSERVER: MainThreadCell<Retained<LifecycleObserver>> = MainThreadCell::new();
+
+// Notifications posted when the app moves to the background and back. A
+// Mac app has no background state, so hiding it counts as one.
+#[cfg(target_os = "ios")]
+const BACKGROUND_NOTIFICATION: &str = "UIApplicationDidEnterBackgroundNotification";
+#[cfg(target_os = "ios")]
+const FOREGROUND_NOTIFICATION: &str = "UIApplicationWillEnterForegroundNotification";
+#[cfg(target_os = "macos")]
+const BACKGROUND_NOTIFICATION: &str = "NSApplicationDidHideNotification";
+#[cfg(target_os = "macos")]
+const FOREGROUND_NOTIFICATION: &str = "NSApplicationDidUnhideNotification";
+
+// `CLError` codes delivered to `locationManager:didFailWithError:`
+const CL_ERROR_LOCATION_UNKNOWN: isize = 0;
+const CL_ERROR_DENIED: isize = 1;
+const CL_ERROR_NETWORK: isize = 2;
+
+/// How long `last_known` waits for a first fix when none is cached
+const LAST_KNOWN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How long `last_known` runs the run loop between checks for a fix
+const RUN_LOOP_SLICE: Duration = Duration::from_millis(50);
+
+/// Accuracy chosen with `set_desired_accuracy`, which overrides
+/// `LocationOptions::high_accuracy` for watches started afterwards
+static DESIRED_ACCURACY: Mutex<Option<AccuracyTier>> = Mutex::new(None);
+
+/// Get or create the global location manager, setting its `desiredAccuracy`
+/// to `accuracy` if given
+fn get_location_manager(
+    mtm: MainThreadMarker,
+    accuracy: Option<AccuracyTier>,
+) -> &'static Retained<CLLocationManager> {
+    let manager = LOCATION_MANAGER.get_or_init_with(mtm, || {
         // SAFETY: `CLLocationManager` is main-thread-only; the marker provided to
         // `get_or_init_with` ensures we're on the main thread.
-        unsafe { CLLocationManager::new() }
+        let manager = unsafe { CLLocationManager::new() };
+        let delegate = LOCATION_DELEGATE.get_or_init_with(mtm, || LocationDelegate::new(mtm));
+        unsafe { manager.setDelegate(Some(ProtocolObject::from_ref(&**delegate))) };
+        manager
+    });
+    if let Some(tier) = accuracy {
+        unsafe { manager.setDesiredAccuracy(cl_accuracy(tier)) };
+    }
+    manager
+}
+
+/// The `kCLLocationAccuracy*` constant of an accuracy tier
+fn cl_accuracy(tier: AccuracyTier) -> CLLocationAccuracy {
+    unsafe {
+        match tier {
+            AccuracyTier::Navigation => kCLLocationAccuracyBestForNavigation,
+            AccuracyTier::Best => kCLLocationAccuracyBest,
+            AccuracyTier::TenMeters => kCLLocationAccuracyNearestTenMeters,
+            AccuracyTier::HundredMeters => kCLLocationAccuracyHundredMeters,
+            AccuracyTier::Kilometer => kCLLocationAccuracyKilometer,
+            AccuracyTier::ThreeKilometers => kCLLocationAccuracyThreeKilometers,
+        }
+    }
+}
+
+/// Set the accuracy of all watches started from now on, and of the running
+/// ones if called on the main thread
+pub fn set_desired_accuracy(tier: AccuracyTier) {
+    *DESIRED_ACCURACY
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) = Some(tier);
+    if let Some(mtm) = MainThreadMarker::new() {
+        get_location_manager(mtm, Some(tier));
+    }
+}
+
+define_class!(
+    /// Forwards `CLLocationManager` events to the active watches.
+    #[unsafe(super(NSObject))]
+    #[thread_kind = MainThreadOnly]
+    #[name = "DioxusGeolocationDelegate"]
+    struct LocationDelegate;
+
+    unsafe impl NSObjectProtocol for LocationDelegate {}
+
+    unsafe impl CLLocationManagerDelegate for LocationDelegate {
+        #[unsafe(method(locationManager:didUpdateLocations:))]
+        fn did_update_locations(
+            &self,
+            _manager: &CLLocationManager,
+            locations: &NSArray<CLLocation>,
+        ) {
+            if let Some(location) = locations.lastObject() {
+                deliver(Ok(location_from_cl(&location)));
+            }
+        }
+
+        #[unsafe(method(locationManager:didFailWithError:))]
+        fn did_fail_with_error(&self, _manager: &CLLocationManager, error: &NSError) {
+            let error = match error.code() {
+                // CoreLocation keeps trying; a fix may still arrive
+                CL_ERROR_LOCATION_UNKNOWN => return,
+                CL_ERROR_DENIED => Error::AuthorizationDenied,
+                CL_ERROR_NETWORK => Error::Network,
+                _ => Error::Unknown,
+            };
+            deliver(Err(error));
+        }
+
+        #[unsafe(method(locationManagerDidChangeAuthorization:))]
+        fn did_change_authorization(&self, manager: &CLLocationManager) {
+            // Also called once with the initial status, which `permission_seen`
+            // records without emitting
+            let status = permission_from_manager(manager);
+            events::permission_seen(status);
+            if matches!(
+                status,
+                PermissionStatus::Denied | PermissionStatus::Restricted
+            ) {
+                deliver(Err(Error::AuthorizationDenied));
+            }
+        }
+
+        #[unsafe(method(locationManager:didUpdateHeading:))]
+        fn did_update_heading(&self, _manager: &CLLocationManager, heading: &CLHeading) {
+            // CoreLocation marks readings it couldn't determine with a
+            // negative accuracy, unlike platforms that just don't know it
+            let heading = heading_from_cl(heading);
+            if heading.is_valid() && heading.accuracy >= 0.0 {
+                deliver_heading(heading);
+            }
+        }
+
+        #[unsafe(method(locationManager:didEnterRegion:))]
+        fn did_enter_region(&self, _manager: &CLLocationManager, region: &CLRegion) {
+            deliver_geofence(region, Ok(GeofenceEventKind::Enter));
+        }
+
+        #[unsafe(method(locationManager:didExitRegion:))]
+        fn did_exit_region(&self, _manager: &CLLocationManager, region: &CLRegion) {
+            deliver_geofence(region, Ok(GeofenceEventKind::Exit));
+        }
+
+        #[unsafe(method(locationManager:monitoringDidFailForRegion:withError:))]
+        fn monitoring_did_fail(
+            &self,
+            _manager: &CLLocationManager,
+            region: Option<&CLRegion>,
+            error: &NSError,
+        ) {
+            let error = match error.code() {
+                CL_ERROR_DENIED => Error::AuthorizationDenied,
+                _ => Error::PermanentlyUnavailable,
+            };
+            if let Some(region) = region {
+                deliver_geofence(region, Err(error));
+            }
+        }
+    }
+);
+
+impl LocationDelegate {
+    fn new(mtm: MainThreadMarker) -> Retained<Self> {
+        let this = Self::alloc(mtm).set_ivars(());
+        unsafe { msg_send![super(this), init] }
+    }
+}
+
+thread_local! {
+    // Delegate callbacks arrive on the main thread, like the watches themselves
+    static WATCHERS: RefCell<Vec<(u64, LocationCallback)>> = const { RefCell::new(Vec::new()) };
+    static NEXT_WATCH_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Call every active watch's callback with `result`
+fn deliver(result: Result<Location>) {
+    // Snapshot the list so callbacks may start or stop watches
+    let callbacks: Vec<LocationCallback> = WATCHERS.with(|watchers| {
+        watchers
+            .borrow()
+            .iter()
+            .map(|(_, callback)| callback.clone())
+            .collect()
+    });
+    for callback in callbacks {
+        callback(result.clone());
+    }
+}
+
+/// An active watch on the global location manager. Updates stop when the
+/// last watch is dropped.
+pub struct Watch {
+    id: u64,
+    // The location manager is main-thread-only, so the watch must stay there
+    _not_send: PhantomData<Rc<()>>,
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        let remaining = WATCHERS.with(|watchers| {
+            let mut watchers = watchers.borrow_mut();
+            watchers.retain(|(id, _)| *id != self.id);
+            watchers.len()
+        });
+
+        if remaining == 0 {
+            if let Some(mtm) = MainThreadMarker::new() {
+                unsafe { get_location_manager(mtm, None).stopUpdatingLocation() };
+            }
+        }
+    }
+}
+
+/// Start location updates, calling `callback` on the main thread for each fix
+///
+/// All watches share the global location manager, so the most recently
+/// started watch's accuracy applies to all of them. A tier set with
+/// `set_desired_accuracy` takes precedence over `options.high_accuracy` and
+/// `options.indoor_mapping`.
+pub fn watch(options: &LocationOptions, callback: LocationCallback) -> Result<Watch> {
+    let mtm = MainThreadMarker::new().ok_or(Error::NotMainThread)?;
+    let desired_accuracy = *DESIRED_ACCURACY
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    // CoreLocation only uses indoor positioning at the best accuracy
+    let best = options.high_accuracy || options.wants_indoor_mapping();
+    let accuracy = desired_accuracy.unwrap_or(if best {
+        AccuracyTier::Best
+    } else {
+        AccuracyTier::HundredMeters
+    });
+    let manager = get_location_manager(mtm, Some(accuracy));
+
+    let auth_status = unsafe { manager.authorizationStatus() };
+    match auth_status {
+        CLAuthorizationStatus::Denied | CLAuthorizationStatus::Restricted => {
+            return Err(Error::AuthorizationDenied);
+        }
+        // Updates start once the user answers the prompt
+        CLAuthorizationStatus::NotDetermined => unsafe { manager.requestWhenInUseAuthorization() },
+        _ => {}
+    }
+
+    let id = NEXT_WATCH_ID.with(|next| {
+        let id = next.get();
+        next.set(id + 1);
+        id
+    });
+    WATCHERS.with(|watchers| watchers.borrow_mut().push((id, callback)));
+
+    unsafe {
+        manager.setDistanceFilter(if options.distance_filter > 0.0 {
+            options.distance_filter
+        } else {
+            kCLDistanceFilterNone
+        });
+        manager.startUpdatingLocation();
+    }
+
+    Ok(Watch {
+        id,
+        _not_send: PhantomData,
+    })
+}
+
+define_class!(
+    /// Stops the foreground-only watches while the app is in the background.
+    #[unsafe(super(NSObject))]
+    #[thread_kind = MainThreadOnly]
+    #[name = "DioxusGeolocationLifecycleObserver"]
+    struct LifecycleObserver;
+
+    impl LifecycleObserver {
+        #[unsafe(method(didEnterBackground:))]
+        fn did_enter_background(&self, _notification: &NSNotification) {
+            set_foreground(false);
+        }
+
+        #[unsafe(method(willEnterForeground:))]
+        fn will_enter_foreground(&self, _notification: &NSNotification) {
+            set_foreground(true);
+        }
+    }
+);
+
+impl LifecycleObserver {
+    fn new(mtm: MainThreadMarker) -> Retained<Self> {
+        let this = Self::alloc(mtm).set_ivars(());
+        unsafe { msg_send![super(this), init] }
+    }
+}
+
+/// Create the lifecycle observer and register it for the background and
+/// foreground notifications, unless that already happened
+fn observe_lifecycle(mtm: MainThreadMarker) {
+    LIFECYCLE_OBSERVER.get_or_init_with(mtm, || {
+        let observer = LifecycleObserver::new(mtm);
+        unsafe {
+            let center = NSNotificationCenter::defaultCenter();
+            center.addObserver_selector_name_object(
+                &observer,
+                sel!(didEnterBackground:),
+                Some(&NSString::from_str(BACKGROUND_NOTIFICATION)),
+                None,
+            );
+            center.addObserver_selector_name_object(
+                &observer,
+                sel!(willEnterForeground:),
+                Some(&NSString::from_str(FOREGROUND_NOTIFICATION)),
+                None,
+            );
+        }
+        observer
+    });
+}
+
+/// A foreground-only watch: its options and callback, and its location
+/// updates while the app is in the foreground
+struct ForegroundEntry {
+    id: u64,
+    options: LocationOptions,
+    callback: LocationCallback,
+    watch: Option<Watch>,
+}
+
+thread_local! {
+    static FOREGROUND_WATCHES: RefCell<Vec<ForegroundEntry>> = const { RefCell::new(Vec::new()) };
+    static NEXT_FOREGROUND_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+/// A location watch that stops while the app is in the background. Updates
+/// stop when it is dropped.
+pub struct ForegroundWatch {
+    id: u64,
+    // The location manager is main-thread-only, so the watch must stay there
+    _not_send: PhantomData<Rc<()>>,
+}
+
+impl Drop for ForegroundWatch {
+    fn drop(&mut self) {
+        // Drop the entry outside the registry borrow
+        let entry = FOREGROUND_WATCHES.with(|watches| {
+            let mut watches = watches.borrow_mut();
+            let index = watches.iter().position(|entry| entry.id == self.id);
+            index.map(|index| watches.remove(index))
+        });
+        drop(entry);
+    }
+}
+
+/// Start location updates that stop while the app is in the background and
+/// restart when it returns, calling `callback` on the main thread for each
+/// fix
+///
+/// The app is taken to be in the foreground when this is called.
+pub fn watch_foreground_only(
+    options: &LocationOptions,
+    callback: LocationCallback,
+) -> Result<ForegroundWatch> {
+    let mtm = MainThreadMarker::new().ok_or(Error::NotMainThread)?;
+    let watch = watch(options, callback.clone())?;
+    observe_lifecycle(mtm);
+
+    let id = NEXT_FOREGROUND_ID.with(|next| {
+        let id = next.get();
+        next.set(id + 1);
+        id
+    });
+    FOREGROUND_WATCHES.with(|watches| {
+        watches.borrow_mut().push(ForegroundEntry {
+            id,
+            options: *options,
+            callback,
+            watch: Some(watch),
+        })
+    });
+
+    Ok(ForegroundWatch {
+        id,
+        _not_send: PhantomData,
     })
 }
 
+/// Stop the foreground-only watches when the app moves to the background,
+/// and restart them when it returns
+///
+/// Watches are stopped and started outside the registry borrow, as a
+/// callback reporting an error may drop its watch.
+fn set_foreground(foreground: bool) {
+    if !foreground {
+        let stopped: Vec<Watch> = FOREGROUND_WATCHES.with(|watches| {
+            watches
+                .borrow_mut()
+                .iter_mut()
+                .filter_map(|entry| entry.watch.take())
+                .collect()
+        });
+        drop(stopped);
+        return;
+    }
+
+    let paused: Vec<(u64, LocationOptions, LocationCallback)> =
+        FOREGROUND_WATCHES.with(|watches| {
+            watches
+                .borrow()
+                .iter()
+                .filter(|entry| entry.watch.is_none())
+                .map(|entry| (entry.id, entry.options, entry.callback.clone()))
+                .collect()
+        });
+    for (id, options, callback) in paused {
+        match watch(&options, callback.clone()) {
+            Ok(watch) => {
+                let unclaimed = FOREGROUND_WATCHES.with(|watches| {
+                    match watches.borrow_mut().iter_mut().find(|entry| entry.id == id) {
+                        Some(entry) => entry.watch.replace(watch),
+                        None => Some(watch),
+                    }
+                });
+                drop(unclaimed);
+            }
+            Err(error) => callback(Err(error)),
+        }
+    }
+}
+
+thread_local! {
+    static GEOFENCES: RefCell<Vec<(u64, GeofenceCallback)>> = const { RefCell::new(Vec::new()) };
+    static NEXT_GEOFENCE_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Call the callback of the monitor owning `region`
+///
+/// Region identifiers are `"<monitor id>:<region id>"`, so that monitors
+/// can share the location manager and reuse region ids.
+fn deliver_geofence(region: &CLRegion, kind: Result<GeofenceEventKind>) {
+    let identifier = unsafe { region.identifier() }.to_string();
+    let Some((id, region_id)) = identifier.split_once(':') else {
+        return;
+    };
+    let callback = GEOFENCES.with(|geofences| {
+        geofences
+            .borrow()
+            .iter()
+            .find(|(monitor_id, _)| id.parse() == Ok(*monitor_id))
+            .map(|(_, callback)| callback.clone())
+    });
+    if let Some(callback) = callback {
+        callback(kind.map(|kind| GeofenceEvent {
+            region_id: region_id.to_string(),
+            kind,
+        }));
+    }
+}
+
+/// Regions monitored by the global location manager. Monitoring of these
+/// regions stops when it is dropped.
+pub struct GeofenceMonitor {
+    id: u64,
+    regions: Vec<Retained<CLCircularRegion>>,
+    // The location manager is main-thread-only, so the monitor must stay there
+    _not_send: PhantomData<Rc<()>>,
+}
+
+impl Drop for GeofenceMonitor {
+    fn drop(&mut self) {
+        GEOFENCES.with(|geofences| geofences.borrow_mut().retain(|(id, _)| *id != self.id));
+
+        if let Some(mtm) = MainThreadMarker::new() {
+            let manager = get_location_manager(mtm, None);
+            for region in &self.regions {
+                unsafe { manager.stopMonitoringForRegion(region) };
+            }
+        }
+    }
+}
+
+/// Start monitoring `regions`, calling `callback` on the main thread when
+/// the device enters or leaves one of them
+///
+/// CoreLocation only reports boundary crossings, so a region the device is
+/// already inside isn't reported as entered.
+pub fn monitor_geofences(
+    regions: &[GeofenceRegion],
+    callback: GeofenceCallback,
+) -> Result<GeofenceMonitor> {
+    let mtm = MainThreadMarker::new().ok_or(Error::NotMainThread)?;
+    if !unsafe { CLLocationManager::isMonitoringAvailableForClass(CLCircularRegion::class()) } {
+        return Err(Error::PermanentlyUnavailable);
+    }
+    let manager = get_location_manager(mtm, None);
+
+    // The limit is per app, so regions of other collections count too, as do
+    // regions left over from earlier launches
+    let monitored = unsafe { manager.monitoredRegions() }.count();
+    if monitored + regions.len() > MAX_GEOFENCES {
+        return Err(Error::TooManyGeofences {
+            limit: MAX_GEOFENCES,
+        });
+    }
+
+    let auth_status = unsafe { manager.authorizationStatus() };
+    match auth_status {
+        CLAuthorizationStatus::Denied | CLAuthorizationStatus::Restricted => {
+            return Err(Error::AuthorizationDenied);
+        }
+        // Monitoring starts once the user answers the prompt
+        CLAuthorizationStatus::NotDetermined => unsafe { manager.requestWhenInUseAuthorization() },
+        _ => {}
+    }
+
+    let id = NEXT_GEOFENCE_ID.with(|next| {
+        let id = next.get();
+        next.set(id + 1);
+        id
+    });
+    GEOFENCES.with(|geofences| geofences.borrow_mut().push((id, callback)));
+
+    let regions = regions
+        .iter()
+        .map(|region| {
+            let center = CLLocationCoordinate2D {
+                latitude: region.center.latitude,
+                longitude: region.center.longitude,
+            };
+            let identifier = NSString::from_str(&format!("{id}:{}", region.id));
+            // Deprecated in favor of `CLMonitor`, which is Swift-only
+            #[allow(deprecated)]
+            let circle = unsafe {
+                CLCircularRegion::initWithCenter_radius_identifier(
+                    CLCircularRegion::alloc(),
+                    center,
+                    region.radius_m,
+                    &identifier,
+                )
+            };
+            unsafe { manager.startMonitoringForRegion(&circle) };
+            circle
+        })
+        .collect();
+
+    Ok(GeofenceMonitor {
+        id,
+        regions,
+        _not_send: PhantomData,
+    })
+}
+
+thread_local! {
+    static HEADING_WATCHERS: RefCell<Vec<(u64, HeadingCallback)>> =
+        const { RefCell::new(Vec::new()) };
+    static NEXT_HEADING_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Call every active heading watch's callback with `heading`
+fn deliver_heading(heading: Heading) {
+    let callbacks: Vec<HeadingCallback> = HEADING_WATCHERS.with(|watchers| {
+        watchers
+            .borrow()
+            .iter()
+            .map(|(_, callback)| callback.clone())
+            .collect()
+    });
+    for callback in callbacks {
+        callback(Ok(heading));
+    }
+}
+
+/// An active heading watch on the global location manager. Heading updates
+/// stop when the last one is dropped.
+pub struct HeadingWatch {
+    id: u64,
+    // The location manager is main-thread-only, so the watch must stay there
+    _not_send: PhantomData<Rc<()>>,
+}
+
+impl Drop for HeadingWatch {
+    fn drop(&mut self) {
+        let remaining = HEADING_WATCHERS.with(|watchers| {
+            let mut watchers = watchers.borrow_mut();
+            watchers.retain(|(id, _)| *id != self.id);
+            watchers.len()
+        });
+
+        if remaining == 0 {
+            if let Some(mtm) = MainThreadMarker::new() {
+                unsafe { get_location_manager(mtm, None).stopUpdatingHeading() };
+            }
+        }
+    }
+}
+
+/// Start heading updates, calling `callback` on the main thread for each
+/// reading
+pub fn watch_heading(callback: HeadingCallback) -> Result<HeadingWatch> {
+    let mtm = MainThreadMarker::new().ok_or(Error::NotMainThread)?;
+    if !unsafe { CLLocationManager::headingAvailable() } {
+        return Err(Error::PermanentlyUnavailable);
+    }
+
+    let id = NEXT_HEADING_ID.with(|next| {
+        let id = next.get();
+        next.set(id + 1);
+        id
+    });
+    HEADING_WATCHERS.with(|watchers| watchers.borrow_mut().push((id, callback)));
+    unsafe { get_location_manager(mtm, None).startUpdatingHeading() };
+
+    Ok(HeadingWatch {
+        id,
+        _not_send: PhantomData,
+    })
+}
+
+/// Read a `CLHeading`, which marks an unknown true heading as negative
+fn heading_from_cl(heading: &CLHeading) -> Heading {
+    let true_heading = unsafe { heading.trueHeading() };
+    let timestamp = unsafe { heading.timestamp() }.timeIntervalSince1970();
+
+    Heading {
+        magnetic_heading: unsafe { heading.magneticHeading() },
+        true_heading: (true_heading >= 0.0).then_some(true_heading),
+        accuracy: unsafe { heading.headingAccuracy() },
+        timestamp: UNIX_EPOCH + Duration::from_secs_f64(timestamp.max(0.0)),
+    }
+}
+
+/// Get the location manager's cached fix, without starting updates
+pub fn cached_location() -> Option<Location> {
+    let mtm = MainThreadMarker::new()?;
+    let location: Retained<CLLocation> = unsafe { get_location_manager(mtm, None).location() }?;
+    Some(location_from_cl(&location))
+}
+
 /// Request location authorization
 pub fn request_permission() -> bool {
     let Some(mtm) = MainThreadMarker::new() else {
         return false;
     };
 
-    let manager = get_location_manager(mtm);
+    let manager = get_location_manager(mtm, None);
 
     // Check authorization status first
     let auth_status = unsafe { manager.authorizationStatus() };
@@ -42,56 +721,194 @@ pub fn request_permission() -> bool {
     true
 }
 
+/// Get the current authorization state without prompting
+///
+/// Reports `NotDetermined` off the main thread, where the location manager
+/// can't be used.
+pub fn permission_status() -> PermissionStatus {
+    let Some(mtm) = MainThreadMarker::new() else {
+        return PermissionStatus::NotDetermined;
+    };
+
+    permission_from_manager(&get_location_manager(mtm, None))
+}
+
+/// Map the authorization of `manager` to a `PermissionStatus`
+fn permission_from_manager(manager: &CLLocationManager) -> PermissionStatus {
+    let auth_status = unsafe { manager.authorizationStatus() };
+    match auth_status {
+        CLAuthorizationStatus::AuthorizedAlways => PermissionStatus::GrantedAlways,
+        CLAuthorizationStatus::AuthorizedWhenInUse => {
+            // The user can grant only an approximate location (iOS 14+)
+            let accuracy = unsafe { manager.accuracyAuthorization() };
+            if accuracy == CLAccuracyAuthorization::ReducedAccuracy {
+                PermissionStatus::GrantedCoarse
+            } else {
+                PermissionStatus::GrantedFine
+            }
+        }
+        CLAuthorizationStatus::Denied => PermissionStatus::Denied,
+        CLAuthorizationStatus::Restricted => PermissionStatus::Restricted,
+        _ => PermissionStatus::NotDetermined,
+    }
+}
+
 /// Get the last known location
-pub fn last_known() -> Option<(f64, f64)> {
+///
+/// Without a cached fix this starts updates and runs the main run loop,
+/// which delivers the delegate callbacks, until the first fix arrives or
+/// `LAST_KNOWN_TIMEOUT` passes. Blocking on a condvar instead would
+/// deadlock, as the delegate is called on this same thread.
+pub fn last_known() -> Option<Location> {
     let mtm = MainThreadMarker::new()?;
 
-    let manager = get_location_manager(mtm);
+    let manager = get_location_manager(mtm, None);
 
-    // Check authorization status before attempting to get location
+    // A fix cached before permission was revoked may still be readable
+    let location: Option<Retained<CLLocation>> = unsafe { manager.location() };
+    if let Some(loc) = location {
+        return Some(location_from_cl(&loc));
+    }
+
+    // No fix can arrive before the user allows location access
     let auth_status = unsafe { manager.authorizationStatus() };
+    if !matches!(
+        auth_status,
+        CLAuthorizationStatus::AuthorizedAlways | CLAuthorizationStatus::AuthorizedWhenInUse
+    ) {
+        return None;
+    }
 
-    // Only proceed if authorized
-    match auth_status {
-        CLAuthorizationStatus::AuthorizedAlways | CLAuthorizationStatus::AuthorizedWhenInUse => {
-            // Can proceed to get location
-        }
-        _ => {
-            // Not authorized - try to get last known location anyway
-            // This might work for locations cached before permission was revoked
+    let first_fix = Arc::new(Mutex::new(None));
+    let updates = watch(&LocationOptions::default(), {
+        let first_fix = first_fix.clone();
+        Arc::new(move |result| {
+            first_fix
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .get_or_insert(result);
+        })
+    })
+    .ok()?;
+
+    let has_fix = || {
+        first_fix
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_some()
+    };
+    let deadline = Instant::now() + LAST_KNOWN_TIMEOUT;
+    let run_loop = NSRunLoop::currentRunLoop();
+    while !has_fix() && Instant::now() < deadline {
+        let until = NSDate::dateWithTimeIntervalSinceNow(RUN_LOOP_SLICE.as_secs_f64());
+        let handled = unsafe { run_loop.runMode_beforeDate(NSDefaultRunLoopMode, &until) };
+        if !handled {
+            // The run loop has no sources yet; don't spin on it
+            std::thread::sleep(RUN_LOOP_SLICE);
         }
     }
 
-    // First, try to get the cached location without starting updates
-    let location: Option<Retained<CLLocation>> = unsafe { manager.location() };
+    // Stops updates to conserve battery, unless other watches are active
+    drop(updates);
+
+    let result = first_fix
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take();
+    result?.ok()
+}
+
+/// Read every valid field of a `CLLocation`
+///
+/// CoreLocation marks fields it couldn't determine with a negative accuracy
+/// (or a negative speed or course) rather than leaving them out.
+fn location_from_cl(location: &CLLocation) -> Location {
+    let coordinate = unsafe { location.coordinate() };
+    let horizontal_accuracy = unsafe { location.horizontalAccuracy() };
+    let vertical_accuracy = unsafe { location.verticalAccuracy() };
+    let speed = unsafe { location.speed() };
+    let course = unsafe { location.course() };
+    let timestamp = unsafe { location.timestamp() }.timeIntervalSince1970();
 
-    if location.is_some() {
-        let loc = location.unwrap();
-        let coordinate = unsafe { loc.coordinate() };
-        return Some((coordinate.latitude, coordinate.longitude));
+    Location {
+        latitude: coordinate.latitude,
+        longitude: coordinate.longitude,
+        altitude: (vertical_accuracy >= 0.0).then(|| unsafe { location.altitude() }),
+        horizontal_accuracy: (horizontal_accuracy >= 0.0).then_some(horizontal_accuracy),
+        vertical_accuracy: (vertical_accuracy >= 0.0).then_some(vertical_accuracy),
+        speed: (speed >= 0.0).then_some(speed),
+        course: (course >= 0.0).then_some(course),
+        timestamp: Some(UNIX_EPOCH + Duration::from_secs_f64(timestamp.max(0.0))),
     }
+}
 
-    // If no cached location, start updates
-    // Note: In a proper implementation, we would set up a delegate to receive
-    // location updates asynchronously. For now, we'll use a simple approach
-    // that starts updates and then checks after a delay.
-    unsafe {
-        manager.startUpdatingLocation();
+/// Get the time of the cached location fix
+pub fn last_fix_time() -> Option<SystemTime> {
+    let mtm = MainThreadMarker::new()?;
+
+    let manager = get_location_manager(mtm, None);
+    let location: Retained<CLLocation> = unsafe { manager.location() }?;
+
+    location_from_cl(&location).timestamp
+}
+
+/// Whether the cached fix was simulated by software, such as Xcode's
+/// location simulation
+pub fn last_location_is_simulated() -> bool {
+    let Some(mtm) = MainThreadMarker::new() else {
+        return false;
+    };
+    let Some(location) = (unsafe { get_location_manager(mtm, None).location() }) else {
+        return false;
+    };
+
+    // `sourceInformation` was added in iOS 15 and macOS 12
+    if !location.respondsToSelector(sel!(sourceInformation)) {
+        return false;
     }
+    unsafe { location.sourceInformation() }
+        .is_some_and(|source| unsafe { source.isSimulatedBySoftware() })
+}
 
-    // Wait for location to be obtained (allowing GPS to get a fix)
-    std::thread::sleep(std::time::Duration::from_millis(1000));
+/// Battery charge in percent from `UIDevice.batteryLevel`, or `None` off
+/// the main thread or while it is unknown, e.g. in the simulator
+#[cfg(target_os = "ios")]
+pub fn battery_level_percent() -> Option<u8> {
+    MainThreadMarker::new()?;
+    let device: Retained<NSObject> = unsafe { msg_send![objc2::class!(UIDevice), currentDevice] };
+    let level: f32 = unsafe {
+        // The level reads as -1 until monitoring is enabled
+        let () = msg_send![&device, setBatteryMonitoringEnabled: true];
+        msg_send![&device, batteryLevel]
+    };
+    (level >= 0.0).then(|| (level * 100.0).round() as u8)
+}
 
-    // Try again now that updates are running
-    let location: Option<Retained<CLLocation>> = unsafe { manager.location() };
+/// Expected battery impact of the location manager's `desiredAccuracy`
+pub fn power_level() -> PowerLevel {
+    let Some(mtm) = MainThreadMarker::new() else {
+        return PowerLevel::None;
+    };
 
-    // Stop updating to conserve battery
-    unsafe {
-        manager.stopUpdatingLocation();
+    let manager = get_location_manager(mtm, None);
+
+    let auth_status = unsafe { manager.authorizationStatus() };
+    if !matches!(
+        auth_status,
+        CLAuthorizationStatus::AuthorizedAlways | CLAuthorizationStatus::AuthorizedWhenInUse
+    ) {
+        return PowerLevel::None;
     }
 
-    location.map(|loc| {
-        let coordinate = unsafe { loc.coordinate() };
-        (coordinate.latitude, coordinate.longitude)
-    })
+    // `kCLLocationAccuracyBest` and `kCLLocationAccuracyBestForNavigation` are
+    // negative; anything finer than 100 m needs GPS, anything coarser than a
+    // kilometer can be served from cell towers.
+    let accuracy = unsafe { manager.desiredAccuracy() };
+    if accuracy < 100.0 {
+        PowerLevel::High
+    } else if accuracy < 1000.0 {
+        PowerLevel::Medium
+    } else {
+        PowerLevel::Low
+    }
 }
@@ -0,0 +1,164 @@
+//! Keychain persistence for the last known location
+//!
+//! Stores the fix as a generic password item through `Security.framework`,
+//! so it is encrypted at rest and excluded from unencrypted backups. The
+//! CoreFoundation types used by `SecItem*` are toll-free bridged to their
+//! Foundation counterparts, which is what this module builds.
+
+use crate::{Coordinates, Error, Result};
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+use objc2_foundation::{NSData, NSDictionary, NSNumber, NSString};
+use std::ptr;
+
+type OSStatus = i32;
+
+const ERR_SEC_SUCCESS: OSStatus = 0;
+const ERR_SEC_ITEM_NOT_FOUND: OSStatus = -25300;
+const ERR_SEC_DUPLICATE_ITEM: OSStatus = -25299;
+const ERR_SEC_INTERACTION_NOT_ALLOWED: OSStatus = -25308;
+
+/// Keychain account name of the stored location
+const ACCOUNT: &str = "last-location";
+
+#[link(name = "Security", kind = "framework")]
+extern "C" {
+    static kSecClass: &'static NSString;
+    static kSecClassGenericPassword: &'static NSString;
+    static kSecAttrService: &'static NSString;
+    static kSecAttrAccount: &'static NSString;
+    static kSecAttrAccessible: &'static NSString;
+    static kSecAttrAccessibleAfterFirstUnlockThisDeviceOnly: &'static NSString;
+    static kSecValueData: &'static NSString;
+    static kSecReturnData: &'static NSString;
+    static kSecMatchLimit: &'static NSString;
+    static kSecMatchLimitOne: &'static NSString;
+
+    fn SecItemAdd(
+        attributes: &NSDictionary<NSString, AnyObject>,
+        result: *mut *mut AnyObject,
+    ) -> OSStatus;
+    fn SecItemUpdate(
+        query: &NSDictionary<NSString, AnyObject>,
+        attributes: &NSDictionary<NSString, AnyObject>,
+    ) -> OSStatus;
+    fn SecItemCopyMatching(
+        query: &NSDictionary<NSString, AnyObject>,
+        result: *mut *mut AnyObject,
+    ) -> OSStatus;
+}
+
+/// Save `c` to the keychain, replacing any previously saved location
+pub fn save_last_location_to_keychain(c: Coordinates) -> Result<()> {
+    let mut bytes = [0u8; 16];
+    bytes[..8].copy_from_slice(&c.latitude.to_le_bytes());
+    bytes[8..].copy_from_slice(&c.longitude.to_le_bytes());
+    let data = NSData::with_bytes(&bytes);
+    let data: &AnyObject = &data;
+
+    let service = service();
+    let account = NSString::from_str(ACCOUNT);
+
+    // SAFETY: The `kSec*` constants are immutable strings exported by
+    // Security.framework, and every dictionary passed to `SecItem*` only
+    // holds Foundation objects bridged to the CoreFoundation types it expects.
+    let status = unsafe {
+        let query = item_query(&service, &account, &[]);
+        let attributes = dictionary(&[(kSecValueData, data)]);
+        let accessible: &AnyObject = kSecAttrAccessibleAfterFirstUnlockThisDeviceOnly;
+
+        match SecItemUpdate(&query, &attributes) {
+            ERR_SEC_ITEM_NOT_FOUND => {
+                let new_item = item_query(
+                    &service,
+                    &account,
+                    &[(kSecValueData, data), (kSecAttrAccessible, accessible)],
+                );
+                match SecItemAdd(&new_item, ptr::null_mut()) {
+                    // Another thread added the item in the meantime
+                    ERR_SEC_DUPLICATE_ITEM => SecItemUpdate(&query, &attributes),
+                    status => status,
+                }
+            }
+            status => status,
+        }
+    };
+
+    check(status)
+}
+
+/// Load the location saved by `save_last_location_to_keychain()`
+///
+/// Returns `Ok(None)` if no location has been saved.
+pub fn load_location_from_keychain() -> Result<Option<Coordinates>> {
+    let service = service();
+    let account = NSString::from_str(ACCOUNT);
+    let return_data = NSNumber::new_bool(true);
+    let return_data: &AnyObject = &return_data;
+
+    let mut result: *mut AnyObject = ptr::null_mut();
+    // SAFETY: See `save_last_location_to_keychain()`. On success,
+    // `SecItemCopyMatching` stores a +1 retained `CFDataRef` in `result`.
+    let data = unsafe {
+        let match_limit: &AnyObject = kSecMatchLimitOne;
+        let query = item_query(
+            &service,
+            &account,
+            &[(kSecReturnData, return_data), (kSecMatchLimit, match_limit)],
+        );
+        let status = SecItemCopyMatching(&query, &mut result);
+        if status == ERR_SEC_ITEM_NOT_FOUND {
+            return Ok(None);
+        }
+        check(status)?;
+        Retained::from_raw(result.cast::<NSData>()).ok_or(Error::Unknown)?
+    };
+
+    let bytes = data.to_vec();
+    if bytes.len() != 16 {
+        return Err(Error::Unknown);
+    }
+    let latitude = f64::from_le_bytes(bytes[..8].try_into().unwrap());
+    let longitude = f64::from_le_bytes(bytes[8..].try_into().unwrap());
+    Ok(Some(Coordinates::new(latitude, longitude)))
+}
+
+/// Keychain service name of the stored item
+fn service() -> Retained<NSString> {
+    NSString::from_str(env!("CARGO_PKG_NAME"))
+}
+
+/// Query matching the stored location item, with `extra` entries appended.
+///
+/// # Safety
+///
+/// Reads the `kSec*` constants exported by Security.framework.
+unsafe fn item_query(
+    service: &AnyObject,
+    account: &AnyObject,
+    extra: &[(&NSString, &AnyObject)],
+) -> Retained<NSDictionary<NSString, AnyObject>> {
+    let class: &AnyObject = kSecClassGenericPassword;
+    let mut entries: Vec<(&NSString, &AnyObject)> = vec![
+        (kSecClass, class),
+        (kSecAttrService, service),
+        (kSecAttrAccount, account),
+    ];
+    entries.extend_from_slice(extra);
+    dictionary(&entries)
+}
+
+fn dictionary(entries: &[(&NSString, &AnyObject)]) -> Retained<NSDictionary<NSString, AnyObject>> {
+    let keys: Vec<&NSString> = entries.iter().map(|(key, _)| *key).collect();
+    let values: Vec<&AnyObject> = entries.iter().map(|(_, value)| *value).collect();
+    NSDictionary::from_slices(&keys, &values)
+}
+
+fn check(status: OSStatus) -> Result<()> {
+    match status {
+        ERR_SEC_SUCCESS => Ok(()),
+        // The device is locked and the item isn't accessible yet
+        ERR_SEC_INTERACTION_NOT_ALLOWED => Err(Error::TemporarilyUnavailable),
+        _ => Err(Error::Unknown),
+    }
+}
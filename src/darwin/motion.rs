@@ -0,0 +1,194 @@
+//! CoreMotion sensors
+//!
+//! Where CoreMotion takes an `NSOperationQueue` for its handler blocks, this
+//! module passes the main queue, so callbacks run on the main thread like
+//! the location callbacks. `CMPedometer` picks its own queue instead.
+
+#[cfg(feature = "activity-recognition")]
+use crate::activity::ActivityCallback;
+#[cfg(feature = "barometer")]
+use crate::barometer::AltitudeCallback;
+#[cfg(feature = "pedometer")]
+use crate::pedometer::{PedometerCallback, PedometerUpdate};
+#[cfg(feature = "activity-recognition")]
+use crate::ActivityType;
+use crate::{Error, Result};
+use block2::RcBlock;
+use objc2::rc::Retained;
+#[cfg(feature = "barometer")]
+use objc2_core_motion::{CMAltimeter, CMAltitudeData};
+#[cfg(feature = "activity-recognition")]
+use objc2_core_motion::{CMMotionActivity, CMMotionActivityManager};
+#[cfg(feature = "pedometer")]
+use objc2_core_motion::{CMPedometer, CMPedometerData};
+#[cfg(any(feature = "barometer", feature = "pedometer"))]
+use objc2_foundation::NSError;
+#[cfg(any(feature = "barometer", feature = "activity-recognition"))]
+use objc2_foundation::NSOperationQueue;
+#[cfg(feature = "pedometer")]
+use objc2_foundation::{NSDate, NSNumber};
+
+// `CMError` codes passed to the handler blocks
+#[cfg(any(feature = "barometer", feature = "pedometer"))]
+const CM_ERROR_MOTION_ACTIVITY_NOT_AVAILABLE: isize = 104;
+#[cfg(any(feature = "barometer", feature = "pedometer"))]
+const CM_ERROR_MOTION_ACTIVITY_NOT_AUTHORIZED: isize = 105;
+
+/// A running `CMAltimeter`. Readings stop when it is dropped.
+#[cfg(feature = "barometer")]
+pub struct AltitudeWatch {
+    altimeter: Retained<CMAltimeter>,
+}
+
+#[cfg(feature = "barometer")]
+impl Drop for AltitudeWatch {
+    fn drop(&mut self) {
+        unsafe { self.altimeter.stopRelativeAltitudeUpdates() };
+    }
+}
+
+/// Start relative altitude updates, calling `callback` on the main thread
+/// with the change in meters since they started
+#[cfg(feature = "barometer")]
+pub fn watch_altitude(callback: AltitudeCallback) -> Result<AltitudeWatch> {
+    if !unsafe { CMAltimeter::isRelativeAltitudeAvailable() } {
+        return Err(Error::PermanentlyUnavailable);
+    }
+
+    let handler = RcBlock::new(move |data: *mut CMAltitudeData, error: *mut NSError| {
+        // CoreMotion passes either the reading or the error
+        let result = match unsafe { data.as_ref() } {
+            Some(data) => Ok(unsafe { data.relativeAltitude() }.doubleValue()),
+            None => Err(unsafe { error.as_ref() }.map_or(Error::Unknown, motion_error)),
+        };
+        callback(result);
+    });
+
+    let altimeter = unsafe { CMAltimeter::new() };
+    unsafe {
+        altimeter.startRelativeAltitudeUpdatesToQueue_withHandler(
+            &NSOperationQueue::mainQueue(),
+            RcBlock::as_ptr(&handler),
+        )
+    };
+    Ok(AltitudeWatch { altimeter })
+}
+
+/// A running `CMPedometer`. Updates stop when it is dropped.
+#[cfg(feature = "pedometer")]
+pub struct PedometerWatch {
+    pedometer: Retained<CMPedometer>,
+}
+
+#[cfg(feature = "pedometer")]
+impl Drop for PedometerWatch {
+    fn drop(&mut self) {
+        unsafe { self.pedometer.stopPedometerUpdates() };
+    }
+}
+
+/// Start pedometer updates, calling `callback` on a CoreMotion queue with
+/// the activity counted since they started
+#[cfg(feature = "pedometer")]
+pub fn watch_pedometer(callback: PedometerCallback) -> Result<PedometerWatch> {
+    if !unsafe { CMPedometer::isStepCountingAvailable() } {
+        return Err(Error::PermanentlyUnavailable);
+    }
+
+    let handler = RcBlock::new(move |data: *mut CMPedometerData, error: *mut NSError| {
+        // CoreMotion passes either the update or the error
+        let result = match unsafe { data.as_ref() } {
+            Some(data) => Ok(pedometer_update(data)),
+            None => Err(unsafe { error.as_ref() }.map_or(Error::Unknown, motion_error)),
+        };
+        callback(result);
+    });
+
+    let pedometer = unsafe { CMPedometer::new() };
+    unsafe {
+        pedometer
+            .startPedometerUpdatesFromDate_withHandler(&NSDate::now(), RcBlock::as_ptr(&handler))
+    };
+    Ok(PedometerWatch { pedometer })
+}
+
+/// Read a `CMPedometerData`, whose optional fields are nil where the device
+/// doesn't measure them
+#[cfg(feature = "pedometer")]
+fn pedometer_update(data: &CMPedometerData) -> PedometerUpdate {
+    let count = |number: Option<Retained<NSNumber>>| number.map(|number| number.unsignedIntValue());
+    PedometerUpdate {
+        steps: unsafe { data.numberOfSteps() }.unsignedIntValue(),
+        distance: unsafe { data.distance() }.map(|distance| distance.doubleValue()),
+        floors_ascending: count(unsafe { data.floorsAscended() }),
+        floors_descending: count(unsafe { data.floorsDescended() }),
+        current_pace: unsafe { data.currentPace() }.map(|pace| pace.doubleValue()),
+    }
+}
+
+/// A running `CMMotionActivityManager`. Updates stop when it is dropped.
+#[cfg(feature = "activity-recognition")]
+pub struct ActivityWatch {
+    manager: Retained<CMMotionActivityManager>,
+}
+
+#[cfg(feature = "activity-recognition")]
+impl Drop for ActivityWatch {
+    fn drop(&mut self) {
+        unsafe { self.manager.stopActivityUpdates() };
+    }
+}
+
+/// Start motion activity updates, calling `callback` on the main thread for
+/// each detected activity
+#[cfg(feature = "activity-recognition")]
+pub fn watch_activity(callback: ActivityCallback) -> Result<ActivityWatch> {
+    if !unsafe { CMMotionActivityManager::isActivityAvailable() } {
+        return Err(Error::PermanentlyUnavailable);
+    }
+
+    // Without permission the handler is never called
+    let handler = RcBlock::new(move |activity: *mut CMMotionActivity| {
+        if let Some(activity) = unsafe { activity.as_ref() } {
+            callback(activity_type(activity));
+        }
+    });
+
+    let manager = unsafe { CMMotionActivityManager::new() };
+    unsafe {
+        manager.startActivityUpdatesToQueue_withHandler(
+            &NSOperationQueue::mainQueue(),
+            RcBlock::as_ptr(&handler),
+        )
+    };
+    Ok(ActivityWatch { manager })
+}
+
+/// The activity a `CMMotionActivity` is most likely about. Several flags can
+/// be set at once, e.g. `stationary` and `automotive` at a traffic light.
+#[cfg(feature = "activity-recognition")]
+fn activity_type(activity: &CMMotionActivity) -> ActivityType {
+    unsafe {
+        if activity.automotive() || activity.cycling() {
+            ActivityType::Vehicle
+        } else if activity.running() {
+            ActivityType::Running
+        } else if activity.walking() {
+            ActivityType::Walking
+        } else if activity.stationary() {
+            ActivityType::Still
+        } else {
+            ActivityType::Unknown
+        }
+    }
+}
+
+/// Map a `CMError` to the crate's error type
+#[cfg(any(feature = "barometer", feature = "pedometer"))]
+fn motion_error(error: &NSError) -> Error {
+    match error.code() {
+        CM_ERROR_MOTION_ACTIVITY_NOT_AVAILABLE => Error::PermanentlyUnavailable,
+        CM_ERROR_MOTION_ACTIVITY_NOT_AUTHORIZED => Error::AuthorizationDenied,
+        _ => Error::Unknown,
+    }
+}
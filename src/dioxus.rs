@@ -0,0 +1,90 @@
+//! Dioxus hooks for reading the location from components
+//!
+//! ```rust,no_run
+//! use dioxus::prelude::*;
+//! use dioxus_mobile_geolocation::dioxus::use_location;
+//! use dioxus_mobile_geolocation::LocationOptions;
+//!
+//! #[component]
+//! fn Position() -> Element {
+//!     let state = use_location(LocationOptions::default());
+//!     let state = state.read();
+//!     match (&state.location, &state.error) {
+//!         (Some(location), _) => rsx! { "{location.latitude}, {location.longitude}" },
+//!         (None, Some(error)) => rsx! { "Location unavailable: {error}" },
+//!         (None, None) => rsx! { "Locating…" },
+//!     }
+//! }
+//! ```
+
+use crate::{get_location, watch_position, Error, Location, LocationOptions, Result};
+use ::dioxus::prelude::*;
+use futures_core::Stream;
+use std::future::poll_fn;
+use std::pin::Pin;
+
+/// Location as seen by a component.
+#[derive(Debug, Clone)]
+pub struct LocationState {
+    /// `true` until the first fix or error arrives.
+    pub loading: bool,
+    /// The most recent fix, kept when a later update fails.
+    pub location: Option<Location>,
+    /// The error from the most recent update, cleared by the next fix.
+    pub error: Option<Error>,
+}
+
+impl Default for LocationState {
+    fn default() -> Self {
+        Self {
+            loading: true,
+            location: None,
+            error: None,
+        }
+    }
+}
+
+impl LocationState {
+    fn update(&mut self, result: Result<Location>) {
+        self.loading = false;
+        match result {
+            Ok(location) => {
+                self.location = Some(location);
+                self.error = None;
+            }
+            Err(error) => self.error = Some(error),
+        }
+    }
+}
+
+/// Track the location while the component is mounted.
+///
+/// Starts [`watch_position`] when the component mounts and writes every
+/// update into the returned signal. The updates stop when the component
+/// unmounts.
+pub fn use_location(options: LocationOptions) -> Signal<LocationState> {
+    let mut state = use_signal(LocationState::default);
+    use_future(move || async move {
+        let mut updates = watch_position(&options);
+        while let Some(result) = poll_fn(|cx| Pin::new(&mut updates).poll_next(cx)).await {
+            state.write().update(result);
+        }
+        state.write().loading = false;
+    });
+    state
+}
+
+/// Get the location once when the component mounts.
+///
+/// Calls [`get_location`] a single time and writes the fix or error into the
+/// returned signal.
+pub fn use_location_once(options: LocationOptions) -> Signal<LocationState> {
+    let mut state = use_signal(LocationState::default);
+    use_hook(move || {
+        spawn(async move {
+            let result = get_location(&options).await;
+            state.write().update(result);
+        });
+    });
+    state
+}
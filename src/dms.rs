@@ -0,0 +1,277 @@
+//! Degrees-minutes-seconds notation
+//!
+//! Formats and parses coordinates such as `40°26'46"N 079°58'56"W`, as used
+//! on paper maps, by GPS receivers and in many user-facing displays.
+
+use crate::Coordinates;
+use std::fmt;
+
+/// Why a string couldn't be parsed by
+/// [`Coordinates::parse_dms`](crate::Coordinates::parse_dms).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParseError {
+    /// The string is empty or only whitespace.
+    EmptyInput,
+    /// The string isn't a pair of degrees-minutes-seconds angles.
+    InvalidFormat,
+    /// A latitude beyond ±90° or a longitude beyond ±180°, in decimal
+    /// degrees.
+    OutOfRange(f64),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::EmptyInput => write!(f, "Empty coordinate string"),
+            ParseError::InvalidFormat => write!(f, "Invalid degrees-minutes-seconds format"),
+            ParseError::OutOfRange(degrees) => write!(f, "Coordinate {degrees}° is out of range"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl Coordinates {
+    /// Format as degrees, minutes and whole seconds with hemisphere letters,
+    /// e.g. `40°26'46"N 079°58'56"W`.
+    ///
+    /// Longitude degrees are padded to three digits so both halves line up.
+    /// The equator and the prime meridian are reported as `N` and `E`.
+    ///
+    /// ```rust
+    /// use dioxus_mobile_geolocation::Coordinates;
+    ///
+    /// let pittsburgh = Coordinates::new(40.446111, -79.982222);
+    /// assert_eq!(pittsburgh.to_dms(), "40°26'46\"N 079°58'56\"W");
+    /// ```
+    pub fn to_dms(&self) -> String {
+        format!(
+            "{} {}",
+            format_angle(self.latitude, 2, ['N', 'S']),
+            format_angle(self.longitude, 3, ['E', 'W'])
+        )
+    }
+
+    /// Parse a latitude and longitude in degrees-minutes-seconds notation.
+    ///
+    /// Accepts the output of [`to_dms`](Self::to_dms) and common variants:
+    ///
+    /// - `°` or `d` after the degrees, `'` or `′` after the minutes, and
+    ///   `"`, `″` or `''` after the seconds
+    /// - minutes and seconds may be left out, and may have decimals
+    /// - a hemisphere letter (`N`, `S`, `E`, `W`) before or after each
+    ///   angle, or a sign instead; with letters the longitude may come first
+    /// - whitespace or a comma between the two angles
+    ///
+    /// ```rust
+    /// use dioxus_mobile_geolocation::Coordinates;
+    ///
+    /// let a = Coordinates::parse_dms("40°26'46\"N 79°58'56\"W").unwrap();
+    /// let b = Coordinates::parse_dms("40d 26' 46\", -79d 58' 56\"").unwrap();
+    /// assert_eq!(a.latitude, b.latitude);
+    /// assert_eq!(a.longitude, b.longitude);
+    /// ```
+    pub fn parse_dms(s: &str) -> Result<Coordinates, ParseError> {
+        if s.trim().is_empty() {
+            return Err(ParseError::EmptyInput);
+        }
+
+        let mut parser = Parser {
+            chars: s.chars().collect(),
+            position: 0,
+        };
+        let first = parser.angle()?;
+        let second = parser.angle()?;
+        parser.skip_separators();
+        if !parser.at_end() {
+            return Err(ParseError::InvalidFormat);
+        }
+
+        let (latitude, longitude) = match (first.axis, second.axis) {
+            (Some(Axis::Longitude), Some(Axis::Latitude)) => (second.degrees, first.degrees),
+            (Some(Axis::Latitude), Some(Axis::Longitude))
+            | (Some(Axis::Latitude), None)
+            | (None, Some(Axis::Longitude))
+            | (None, None) => (first.degrees, second.degrees),
+            _ => return Err(ParseError::InvalidFormat),
+        };
+
+        if !(-90.0..=90.0).contains(&latitude) {
+            return Err(ParseError::OutOfRange(latitude));
+        }
+        if !(-180.0..=180.0).contains(&longitude) {
+            return Err(ParseError::OutOfRange(longitude));
+        }
+        Ok(Coordinates {
+            latitude,
+            longitude,
+        })
+    }
+}
+
+/// `degrees` as `DD°MM'SS"H`, with the degrees padded to `width` digits and
+/// `hemispheres` holding the letters for positive and negative values
+fn format_angle(degrees: f64, width: usize, hemispheres: [char; 2]) -> String {
+    // Round once so 59.6" carries into the minutes instead of printing 60"
+    let seconds = (degrees.abs() * 3600.0).round() as u64;
+    let hemisphere = if degrees < 0.0 && seconds > 0 {
+        hemispheres[1]
+    } else {
+        hemispheres[0]
+    };
+    format!(
+        "{:0width$}°{:02}'{:02}\"{hemisphere}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Axis {
+    Latitude,
+    Longitude,
+}
+
+/// One parsed angle, and the axis its hemisphere letter names
+struct Angle {
+    degrees: f64,
+    axis: Option<Axis>,
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    /// `[H] [sign] D° [M' [S"]] [H]`
+    fn angle(&mut self) -> Result<Angle, ParseError> {
+        self.skip_separators();
+        let prefix = self.hemisphere();
+        self.skip_whitespace();
+        let negative = match self.peek() {
+            Some(sign @ ('-' | '+')) => {
+                self.position += 1;
+                sign == '-'
+            }
+            _ => false,
+        };
+
+        let degrees = self.number()?;
+        self.skip_whitespace();
+        if !self.marker(&["°", "º", "d", "D"]) {
+            return Err(ParseError::InvalidFormat);
+        }
+
+        let mut value = degrees.value;
+        let mut last_fraction = degrees.has_fraction;
+        self.skip_whitespace();
+        if self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            let minutes = self.number()?;
+            self.skip_whitespace();
+            if last_fraction || minutes.value >= 60.0 || !self.marker(&["'", "′", "’"]) {
+                return Err(ParseError::InvalidFormat);
+            }
+            value += minutes.value / 60.0;
+            last_fraction = minutes.has_fraction;
+
+            self.skip_whitespace();
+            if self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                let seconds = self.number()?;
+                self.skip_whitespace();
+                if last_fraction || seconds.value >= 60.0 {
+                    return Err(ParseError::InvalidFormat);
+                }
+                // The seconds mark may be left out
+                self.marker(&["\"", "''", "″", "”"]);
+                value += seconds.value / 3600.0;
+            }
+        }
+
+        // A letter after an angle with a prefix belongs to the next angle
+        let hemisphere = match prefix {
+            Some(prefix) => Some(prefix),
+            None => {
+                self.skip_whitespace();
+                self.hemisphere()
+            }
+        };
+        if negative && hemisphere.is_some() {
+            return Err(ParseError::InvalidFormat);
+        }
+
+        let negative = negative || hemisphere.is_some_and(|(_, south_or_west)| south_or_west);
+        Ok(Angle {
+            degrees: if negative { -value } else { value },
+            axis: hemisphere.map(|(axis, _)| axis),
+        })
+    }
+
+    /// A hemisphere letter, as its axis and whether it is south or west
+    fn hemisphere(&mut self) -> Option<(Axis, bool)> {
+        let hemisphere = match self.peek()?.to_ascii_uppercase() {
+            'N' => (Axis::Latitude, false),
+            'S' => (Axis::Latitude, true),
+            'E' => (Axis::Longitude, false),
+            'W' => (Axis::Longitude, true),
+            _ => return None,
+        };
+        self.position += 1;
+        Some(hemisphere)
+    }
+
+    fn number(&mut self) -> Result<Number, ParseError> {
+        let start = self.position;
+        while self.peek().is_some_and(|c| c.is_ascii_digit() || c == '.') {
+            self.position += 1;
+        }
+        let text: String = self.chars[start..self.position].iter().collect();
+        let value = text.parse().map_err(|_| ParseError::InvalidFormat)?;
+        Ok(Number {
+            value,
+            has_fraction: text.contains('.'),
+        })
+    }
+
+    /// Consume the first of `markers` found at the current position
+    fn marker(&mut self, markers: &[&str]) -> bool {
+        for marker in markers {
+            let len = marker.chars().count();
+            let matches = self
+                .chars
+                .get(self.position..self.position + len)
+                .is_some_and(|chars| chars.iter().copied().eq(marker.chars()));
+            if matches {
+                self.position += len;
+                return true;
+            }
+        }
+        false
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    fn skip_separators(&mut self) {
+        while self.peek().is_some_and(|c| c.is_whitespace() || c == ',') {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn at_end(&self) -> bool {
+        self.position == self.chars.len()
+    }
+}
+
+struct Number {
+    value: f64,
+    has_fraction: bool,
+}
@@ -0,0 +1,130 @@
+//! Unified location event stream
+//!
+//! Collects every location-related transition (permission changes, new fixes,
+//! errors, service start/stop) into a single [`LocationEvent`] type, which
+//! maps naturally onto a reducer-style state machine in a Dioxus app.
+
+use crate::{Coordinates, Error, PermissionStatus};
+use std::cell::{Cell, RefCell};
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+/// A location-related state transition.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LocationEvent {
+    /// Location permission was granted.
+    ///
+    /// Emitted when [`permission_status`](crate::permission_status) or
+    /// [`request_location_permission`](crate::request_location_permission)
+    /// sees access granted after it wasn't.
+    PermissionGranted,
+    /// Location permission was denied or revoked.
+    ///
+    /// Emitted when a status check or request sees access denied or
+    /// restricted after it wasn't. Revoking access mid-session is also
+    /// noticed: on iOS and macOS right away, on Android when a
+    /// foreground-only watch resumes, and on web once a watch was started
+    /// or the permission queried.
+    PermissionDenied,
+    /// A location fix was obtained.
+    FixObtained(Coordinates),
+    /// Fetching the location failed.
+    Error(Error),
+    /// Location updates started.
+    ServiceStarted,
+    /// Location updates stopped.
+    ServiceStopped,
+}
+
+impl std::fmt::Display for LocationEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LocationEvent::PermissionGranted => write!(f, "Location permission granted"),
+            LocationEvent::PermissionDenied => write!(f, "Location permission denied"),
+            LocationEvent::FixObtained(coords) => {
+                write!(f, "Location fix: {}, {}", coords.latitude, coords.longitude)
+            }
+            LocationEvent::Error(error) => write!(f, "Location error: {error}"),
+            LocationEvent::ServiceStarted => write!(f, "Location service started"),
+            LocationEvent::ServiceStopped => write!(f, "Location service stopped"),
+        }
+    }
+}
+
+type Subscriber = Rc<dyn Fn(LocationEvent)>;
+
+thread_local! {
+    static SUBSCRIBERS: RefCell<Vec<(u64, Subscriber)>> = const { RefCell::new(Vec::new()) };
+    static NEXT_ID: Cell<u64> = const { Cell::new(0) };
+    static LAST_PERMISSION: Cell<Option<PermissionStatus>> = const { Cell::new(None) };
+}
+
+/// Handle to an event subscription. The subscription ends when it is dropped.
+#[must_use = "the subscription is cancelled when the handle is dropped"]
+pub struct EventHandle {
+    id: u64,
+    // Subscriptions are per-thread, so the handle must stay on its thread.
+    _not_send: PhantomData<Rc<()>>,
+}
+
+impl Drop for EventHandle {
+    fn drop(&mut self) {
+        let _ = SUBSCRIBERS.try_with(|subscribers| {
+            subscribers.borrow_mut().retain(|(id, _)| *id != self.id);
+        });
+    }
+}
+
+/// Subscribe to all location events.
+///
+/// `cb` is called for every [`LocationEvent`] produced on the current thread
+/// until the returned [`EventHandle`] is dropped. Subscribe from the UI thread
+/// to observe the events produced by calls made from UI code.
+pub fn subscribe_events(cb: impl Fn(LocationEvent) + 'static) -> EventHandle {
+    let id = NEXT_ID.with(|next| {
+        let id = next.get();
+        next.set(id + 1);
+        id
+    });
+    SUBSCRIBERS.with(|subscribers| subscribers.borrow_mut().push((id, Rc::new(cb))));
+    EventHandle {
+        id,
+        _not_send: PhantomData,
+    }
+}
+
+/// Deliver `event` to every subscriber on the current thread.
+pub(crate) fn emit(event: LocationEvent) {
+    // Snapshot the list so callbacks may subscribe or unsubscribe.
+    let subscribers: Vec<Subscriber> = SUBSCRIBERS.with(|subscribers| {
+        subscribers
+            .borrow()
+            .iter()
+            .map(|(_, cb)| cb.clone())
+            .collect()
+    });
+    for cb in subscribers {
+        cb(event.clone());
+    }
+}
+
+/// Emit [`LocationEvent::PermissionGranted`] or
+/// [`LocationEvent::PermissionDenied`] if `status` changed since the last
+/// status seen on the current thread.
+pub(crate) fn permission_seen(status: PermissionStatus) {
+    let Some(previous) = LAST_PERMISSION.with(|last| last.replace(Some(status))) else {
+        return;
+    };
+    let denied = |status| {
+        matches!(
+            status,
+            PermissionStatus::Denied | PermissionStatus::Restricted
+        )
+    };
+    if status.is_granted() && !previous.is_granted() {
+        emit(LocationEvent::PermissionGranted);
+    } else if denied(status) && !denied(previous) {
+        emit(LocationEvent::PermissionDenied);
+    }
+}
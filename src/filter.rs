@@ -0,0 +1,358 @@
+//! Smoothing filters for noisy location fixes
+//!
+//! Raw fixes jitter around the true position, especially on mobile devices
+//! indoors or in urban canyons. The filters in this module take each new fix
+//! and return a smoothed estimate.
+
+use crate::geo_math::{normalize_longitude, EARTH_RADIUS_METERS};
+use crate::{Coordinates, Location};
+use std::collections::VecDeque;
+use std::time::SystemTime;
+
+/// A filter that smooths a sequence of location fixes.
+pub trait LocationFilter {
+    /// Feed a new fix into the filter and return the smoothed estimate.
+    fn update(&mut self, fix: Coordinates) -> Coordinates;
+
+    /// Discard all accumulated state.
+    fn reset(&mut self);
+}
+
+/// Weighted moving average over the last `window` fixes.
+///
+/// By default the weights increase linearly so recent fixes count more than
+/// older ones.
+#[derive(Debug, Clone)]
+pub struct SmoothingFilter {
+    window: usize,
+    weights: Vec<f64>,
+    history: VecDeque<Coordinates>,
+}
+
+impl SmoothingFilter {
+    /// Create a filter averaging the last `window` fixes with linearly
+    /// increasing weights (oldest `1`, newest `window`).
+    pub fn new(window: usize) -> Self {
+        let window = window.max(1);
+        Self::with_weights((1..=window).map(|w| w as f64).collect())
+    }
+
+    /// Create a filter with explicit weights, ordered from oldest to newest.
+    ///
+    /// The window size is the number of weights.
+    pub fn with_weights(weights: Vec<f64>) -> Self {
+        let weights = if weights.is_empty() {
+            vec![1.0]
+        } else {
+            weights
+        };
+        Self {
+            window: weights.len(),
+            history: VecDeque::with_capacity(weights.len()),
+            weights,
+        }
+    }
+
+    /// Number of fixes averaged.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+}
+
+impl LocationFilter for SmoothingFilter {
+    fn update(&mut self, fix: Coordinates) -> Coordinates {
+        if self.history.len() == self.window {
+            self.history.pop_front();
+        }
+        self.history.push_back(fix);
+
+        // With fewer fixes than the window, use the newest weights.
+        let weights = &self.weights[self.window - self.history.len()..];
+        let (mut latitude, mut longitude, mut total) = (0.0, 0.0, 0.0);
+        for (point, weight) in self.history.iter().zip(weights) {
+            latitude += point.latitude * weight;
+            longitude += longitude_offset(fix.longitude, point.longitude) * weight;
+            total += weight;
+        }
+
+        if total <= 0.0 {
+            return fix;
+        }
+
+        Coordinates::new(latitude / total, fix.longitude + longitude / total)
+    }
+
+    fn reset(&mut self) {
+        self.history.clear();
+    }
+}
+
+/// Exponential moving average of location fixes.
+///
+/// Needs no window size: each new fix is blended into the running estimate
+/// with a constant smoothing factor.
+#[derive(Debug, Clone)]
+pub struct ExponentialMovingAverage {
+    alpha: f64,
+    estimate: Option<Coordinates>,
+}
+
+impl ExponentialMovingAverage {
+    /// Default weight given to each new fix.
+    pub const DEFAULT_ALPHA: f64 = 0.3;
+
+    /// Create a filter with [`Self::DEFAULT_ALPHA`].
+    pub fn new() -> Self {
+        Self::with_alpha(Self::DEFAULT_ALPHA)
+    }
+
+    /// Create a filter with a custom smoothing factor in `(0, 1]`.
+    ///
+    /// Higher values follow new fixes more closely.
+    pub fn with_alpha(alpha: f64) -> Self {
+        Self {
+            alpha: alpha.clamp(f64::EPSILON, 1.0),
+            estimate: None,
+        }
+    }
+}
+
+impl Default for ExponentialMovingAverage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocationFilter for ExponentialMovingAverage {
+    fn update(&mut self, fix: Coordinates) -> Coordinates {
+        let estimate = match self.estimate {
+            Some(previous) => Coordinates::new(
+                previous.latitude + self.alpha * (fix.latitude - previous.latitude),
+                previous.longitude
+                    + self.alpha * longitude_offset(previous.longitude, fix.longitude),
+            ),
+            None => fix,
+        };
+        self.estimate = Some(estimate);
+        estimate
+    }
+
+    fn reset(&mut self) {
+        self.estimate = None;
+    }
+}
+
+/// Constant-velocity Kalman filter for location fixes.
+///
+/// Tracks the position and velocity east and north in meters, so unlike the
+/// averaging filters it follows a moving device without lagging behind.
+/// Each fix is weighted by its `horizontal_accuracy`, and the time between
+/// fixes comes from their timestamps.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::{Coordinates, KalmanFilter, Location};
+/// use std::time::{Duration, SystemTime};
+///
+/// let start = SystemTime::now();
+/// let mut filter = KalmanFilter::new();
+/// for (i, latitude) in [52.52, 52.52012, 52.51995, 52.52004].into_iter().enumerate() {
+///     let fix = Location {
+///         horizontal_accuracy: Some(15.0),
+///         timestamp: Some(start + Duration::from_secs(i as u64)),
+///         ..Location::from(Coordinates::new(latitude, 13.405))
+///     };
+///     let smoothed = filter.update_location(&fix);
+///     assert!((smoothed.latitude - 52.52).abs() < 0.0002);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct KalmanFilter {
+    process_noise: f64,
+    state: Option<KalmanState>,
+}
+
+impl KalmanFilter {
+    /// Default process noise in m²/s³.
+    ///
+    /// Suits walking and driving at a steady speed.
+    pub const DEFAULT_PROCESS_NOISE: f64 = 1.0;
+
+    /// Accuracy in meters assumed for fixes without a `horizontal_accuracy`.
+    pub const DEFAULT_ACCURACY_METERS: f64 = 10.0;
+
+    /// Seconds assumed between fixes without timestamps.
+    const DEFAULT_INTERVAL_SECONDS: f64 = 1.0;
+
+    /// Variance of the unknown initial velocity, in (m/s)²
+    const INITIAL_VELOCITY_VARIANCE: f64 = 100.0;
+
+    /// Create a filter with [`Self::DEFAULT_PROCESS_NOISE`].
+    pub fn new() -> Self {
+        Self::with_process_noise(Self::DEFAULT_PROCESS_NOISE)
+    }
+
+    /// Create a filter with a custom process noise `q` in m²/s³, the
+    /// spectral density of the device's random acceleration.
+    ///
+    /// Higher values follow turns and speed changes more closely, lower
+    /// values smooth more.
+    pub fn with_process_noise(q: f64) -> Self {
+        Self {
+            process_noise: q.max(0.0),
+            state: None,
+        }
+    }
+
+    /// Feed a new fix into the filter and return it with the smoothed
+    /// position.
+    ///
+    /// `horizontal_accuracy` is set to the filter's own estimate; the other
+    /// fields are kept. A fix older than the previous one is treated as
+    /// simultaneous with it.
+    pub fn update_location(&mut self, fix: &Location) -> Location {
+        let variance = match fix.horizontal_accuracy {
+            Some(accuracy) if accuracy > 0.0 => accuracy * accuracy,
+            _ => Self::DEFAULT_ACCURACY_METERS * Self::DEFAULT_ACCURACY_METERS,
+        };
+
+        let Some(state) = &mut self.state else {
+            self.state = Some(KalmanState {
+                origin: fix.coordinates(),
+                east: Axis::new(variance),
+                north: Axis::new(variance),
+                timestamp: fix.timestamp,
+            });
+            return Location {
+                horizontal_accuracy: Some(variance.sqrt()),
+                ..*fix
+            };
+        };
+
+        let seconds = match (state.timestamp, fix.timestamp) {
+            (Some(previous), Some(timestamp)) => timestamp
+                .duration_since(previous)
+                .map_or(0.0, |elapsed| elapsed.as_secs_f64()),
+            _ => Self::DEFAULT_INTERVAL_SECONDS,
+        };
+        state.timestamp = state.timestamp.max(fix.timestamp);
+
+        let (east, north) = state.offset_to(fix.coordinates());
+        state.east.predict(seconds, self.process_noise);
+        state.east.correct(east, variance);
+        state.north.predict(seconds, self.process_noise);
+        state.north.correct(north, variance);
+        state.recenter();
+
+        Location {
+            latitude: state.origin.latitude,
+            longitude: state.origin.longitude,
+            horizontal_accuracy: Some(
+                ((state.east.covariance[0][0] + state.north.covariance[0][0]) / 2.0).sqrt(),
+            ),
+            ..*fix
+        }
+    }
+}
+
+impl Default for KalmanFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocationFilter for KalmanFilter {
+    /// Smooth a fix without accuracy or timestamp, using
+    /// [`KalmanFilter::DEFAULT_ACCURACY_METERS`] and one second between
+    /// fixes.
+    fn update(&mut self, fix: Coordinates) -> Coordinates {
+        self.update_location(&Location::from(fix)).coordinates()
+    }
+
+    fn reset(&mut self) {
+        self.state = None;
+    }
+}
+
+/// Estimate on a plane tangent to the earth at `origin`, which follows the
+/// estimate so the flat-earth approximation stays local
+#[derive(Debug, Clone)]
+struct KalmanState {
+    origin: Coordinates,
+    east: Axis,
+    north: Axis,
+    timestamp: Option<SystemTime>,
+}
+
+impl KalmanState {
+    /// East and north offsets of `point` from the origin in meters
+    fn offset_to(&self, point: Coordinates) -> (f64, f64) {
+        let east = longitude_offset(self.origin.longitude, point.longitude).to_radians()
+            * self.origin.latitude.to_radians().cos()
+            * EARTH_RADIUS_METERS;
+        let north = (point.latitude - self.origin.latitude).to_radians() * EARTH_RADIUS_METERS;
+        (east, north)
+    }
+
+    /// Move the origin to the estimated position
+    fn recenter(&mut self) {
+        let latitude =
+            self.origin.latitude + (self.north.position / EARTH_RADIUS_METERS).to_degrees();
+        let longitude = self.origin.longitude
+            + (self.east.position
+                / (EARTH_RADIUS_METERS * self.origin.latitude.to_radians().cos()))
+            .to_degrees();
+        self.origin = Coordinates::new(latitude.clamp(-90.0, 90.0), longitude);
+        self.east.position = 0.0;
+        self.north.position = 0.0;
+    }
+}
+
+/// Position and velocity along one axis, with their covariance
+#[derive(Debug, Clone, Copy)]
+struct Axis {
+    position: f64,
+    velocity: f64,
+    covariance: [[f64; 2]; 2],
+}
+
+impl Axis {
+    fn new(variance: f64) -> Self {
+        Self {
+            position: 0.0,
+            velocity: 0.0,
+            covariance: [
+                [variance, 0.0],
+                [0.0, KalmanFilter::INITIAL_VELOCITY_VARIANCE],
+            ],
+        }
+    }
+
+    /// Advance by `dt` seconds under random acceleration of density `q`
+    fn predict(&mut self, dt: f64, q: f64) {
+        let [[p00, p01], [_, p11]] = self.covariance;
+        self.position += self.velocity * dt;
+        let p00 = p00 + 2.0 * dt * p01 + dt * dt * p11 + q * dt.powi(3) / 3.0;
+        let p01 = p01 + dt * p11 + q * dt * dt / 2.0;
+        let p11 = p11 + q * dt;
+        self.covariance = [[p00, p01], [p01, p11]];
+    }
+
+    /// Blend in a measured position with the given variance
+    fn correct(&mut self, measurement: f64, variance: f64) {
+        let [[p00, p01], [_, p11]] = self.covariance;
+        let gain = (p00 / (p00 + variance), p01 / (p00 + variance));
+        let residual = measurement - self.position;
+        self.position += gain.0 * residual;
+        self.velocity += gain.1 * residual;
+        let p00 = (1.0 - gain.0) * p00;
+        let p11 = p11 - gain.1 * p01;
+        let p01 = (1.0 - gain.0) * p01;
+        self.covariance = [[p00, p01], [p01, p11]];
+    }
+}
+
+/// Signed longitude difference `to - from`, taking the short way around the
+/// antimeridian.
+fn longitude_offset(from: f64, to: f64) -> f64 {
+    normalize_longitude(to - from)
+}
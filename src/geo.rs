@@ -0,0 +1,25 @@
+//! Distance and bearing helpers under their conventional names
+//!
+//! These are the most commonly needed functions from [`geo_math`](crate::geo_math),
+//! which has the full set of route and bounding box helpers. [`BoundingBox`]
+//! is [`CoordinateBounds`](crate::geo_math::CoordinateBounds).
+//!
+//! ```rust
+//! use dioxus_mobile_geolocation::geo::{destination_point, haversine_distance, initial_bearing};
+//! use dioxus_mobile_geolocation::Coordinates;
+//!
+//! let paris = Coordinates::new(48.8566, 2.3522);
+//! let london = Coordinates::new(51.5074, -0.1278);
+//!
+//! let distance = haversine_distance(paris, london);
+//! let bearing = initial_bearing(paris, london);
+//! let arrived = destination_point(paris, bearing, distance);
+//! assert!(haversine_distance(arrived, london) < 1.0);
+//! ```
+
+pub use crate::geo_math::distance_meters as haversine_distance;
+/// [`CoordinateBounds`](crate::geo_math::CoordinateBounds) under its
+/// conventional name; see there for the constructors and queries, e.g.
+/// `from_center`, `expand`, `diagonal_m`, `contains` and `intersects`.
+pub use crate::geo_math::CoordinateBounds as BoundingBox;
+pub use crate::geo_math::{destination_point, initial_bearing, midpoint, vincenty_distance};
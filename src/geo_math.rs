@@ -0,0 +1,1664 @@
+//! Geodesic math on [`Coordinates`]
+//!
+//! Pure-Rust helpers for distances, routes and travel time. All distances are
+//! in meters and computed on a spherical Earth model, which is accurate to
+//! about 0.5% and more than enough for UI purposes.
+
+use crate::{Coordinates, Error, Result};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+use std::time::{Duration, SystemTime};
+
+/// Mean Earth radius in meters (IUGG).
+pub const EARTH_RADIUS_METERS: f64 = 6_371_008.8;
+
+/// WGS-84 ellipsoid semi-major axis in meters.
+const WGS84_A: f64 = 6_378_137.0;
+
+/// WGS-84 ellipsoid flattening.
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+/// Wrap a longitude in degrees into `[-180, 180)`.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::geo_math::normalize_longitude;
+///
+/// assert_eq!(normalize_longitude(190.0), -170.0);
+/// assert_eq!(normalize_longitude(-540.0), -180.0);
+/// ```
+pub fn normalize_longitude(lon: f64) -> f64 {
+    ((lon % 360.0) + 540.0) % 360.0 - 180.0
+}
+
+/// Clamp a latitude in degrees to `[-90, 90]`.
+pub fn normalize_latitude(lat: f64) -> f64 {
+    lat.clamp(-90.0, 90.0)
+}
+
+/// Great-circle distance between two coordinates in meters (Haversine).
+pub fn distance_meters(a: Coordinates, b: Coordinates) -> f64 {
+    let (lat_a, lat_b) = (a.latitude.to_radians(), b.latitude.to_radians());
+    let d_lat = lat_b - lat_a;
+    let d_lon = (b.longitude - a.longitude).to_radians();
+
+    let h = (d_lat / 2.0).sin().powi(2) + lat_a.cos() * lat_b.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_METERS * h.sqrt().min(1.0).asin()
+}
+
+/// Distance between two coordinates in meters on the WGS-84 ellipsoid
+/// (Vincenty's inverse formula).
+///
+/// Accurate to within a millimeter, unlike the spherical
+/// [`distance_meters`]. Returns `None` if the iteration doesn't converge,
+/// which happens for nearly antipodal points.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::geo_math::vincenty_distance;
+/// use dioxus_mobile_geolocation::Coordinates;
+///
+/// let flinders_peak = Coordinates::new(-37.951_033_42, 144.424_867_89);
+/// let buninyong = Coordinates::new(-37.652_821_14, 143.926_495_53);
+/// let distance = vincenty_distance(flinders_peak, buninyong).unwrap();
+/// assert!((distance - 54_972.271).abs() < 0.01);
+/// ```
+pub fn vincenty_distance(a: Coordinates, b: Coordinates) -> Option<f64> {
+    const MAX_ITERATIONS: usize = 200;
+    let semi_minor = (1.0 - WGS84_F) * WGS84_A;
+
+    let d_lon = normalize_longitude(b.longitude - a.longitude).to_radians();
+    let u1 = ((1.0 - WGS84_F) * a.latitude.to_radians().tan()).atan();
+    let u2 = ((1.0 - WGS84_F) * b.latitude.to_radians().tan()).atan();
+    let (sin_u1, cos_u1) = u1.sin_cos();
+    let (sin_u2, cos_u2) = u2.sin_cos();
+
+    let mut lambda = d_lon;
+    for _ in 0..MAX_ITERATIONS {
+        let (sin_lambda, cos_lambda) = lambda.sin_cos();
+        let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+            + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2))
+        .sqrt();
+        if sin_sigma == 0.0 {
+            // Coincident points
+            return Some(0.0);
+        }
+        let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+        let sigma = sin_sigma.atan2(cos_sigma);
+        let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+        let cos_sq_alpha = 1.0 - sin_alpha * sin_alpha;
+        // Both points on the equator
+        let cos_2_sigma_m = if cos_sq_alpha == 0.0 {
+            0.0
+        } else {
+            cos_sigma - 2.0 * sin_u1 * sin_u2 / cos_sq_alpha
+        };
+        let c = WGS84_F / 16.0 * cos_sq_alpha * (4.0 + WGS84_F * (4.0 - 3.0 * cos_sq_alpha));
+
+        let previous = lambda;
+        lambda = d_lon
+            + (1.0 - c)
+                * WGS84_F
+                * sin_alpha
+                * (sigma
+                    + c * sin_sigma
+                        * (cos_2_sigma_m + c * cos_sigma * (-1.0 + 2.0 * cos_2_sigma_m.powi(2))));
+
+        if (lambda - previous).abs() < 1e-12 {
+            let u_sq = cos_sq_alpha * (WGS84_A.powi(2) - semi_minor.powi(2)) / semi_minor.powi(2);
+            let big_a =
+                1.0 + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+            let big_b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+            let delta_sigma = big_b
+                * sin_sigma
+                * (cos_2_sigma_m
+                    + big_b / 4.0
+                        * (cos_sigma * (-1.0 + 2.0 * cos_2_sigma_m.powi(2))
+                            - big_b / 6.0
+                                * cos_2_sigma_m
+                                * (-3.0 + 4.0 * sin_sigma.powi(2))
+                                * (-3.0 + 4.0 * cos_2_sigma_m.powi(2))));
+            return Some(semi_minor * big_a * (sigma - delta_sigma));
+        }
+    }
+
+    None
+}
+
+/// Initial great-circle bearing from `from` to `to` in degrees clockwise
+/// from north, in `[0, 360)`.
+///
+/// The bearing changes along the way on a great circle; this is the
+/// direction to set off in.
+pub fn initial_bearing(from: Coordinates, to: Coordinates) -> f64 {
+    let (lat_a, lat_b) = (from.latitude.to_radians(), to.latitude.to_radians());
+    let d_lon = (to.longitude - from.longitude).to_radians();
+
+    let y = d_lon.sin() * lat_b.cos();
+    let x = lat_a.cos() * lat_b.sin() - lat_a.sin() * lat_b.cos() * d_lon.cos();
+    y.atan2(x).to_degrees().rem_euclid(360.0)
+}
+
+/// Point halfway between `a` and `b` along the great circle joining them.
+pub fn midpoint(a: Coordinates, b: Coordinates) -> Coordinates {
+    let (lat_a, lat_b) = (a.latitude.to_radians(), b.latitude.to_radians());
+    let lon_a = a.longitude.to_radians();
+    let d_lon = (b.longitude - a.longitude).to_radians();
+
+    let bx = lat_b.cos() * d_lon.cos();
+    let by = lat_b.cos() * d_lon.sin();
+    let lat = (lat_a.sin() + lat_b.sin()).atan2(((lat_a.cos() + bx).powi(2) + by * by).sqrt());
+    let lon = lon_a + by.atan2(lat_a.cos() + bx);
+
+    Coordinates::new(lat.to_degrees(), lon.to_degrees())
+}
+
+/// Point reached by travelling `distance_m` meters from `origin` along the
+/// great circle with initial `bearing_degrees` (clockwise from north).
+pub fn destination_point(
+    origin: Coordinates,
+    bearing_degrees: f64,
+    distance_m: f64,
+) -> Coordinates {
+    let angular = distance_m / EARTH_RADIUS_METERS;
+    let bearing = bearing_degrees.to_radians();
+    let lat = origin.latitude.to_radians();
+    let lon = origin.longitude.to_radians();
+
+    let dest_lat = (lat.sin() * angular.cos() + lat.cos() * angular.sin() * bearing.cos()).asin();
+    let dest_lon = lon
+        + (bearing.sin() * angular.sin() * lat.cos())
+            .atan2(angular.cos() - lat.sin() * dest_lat.sin());
+
+    Coordinates::new(dest_lat.to_degrees(), dest_lon.to_degrees())
+}
+
+/// Estimated current position when satellite fixes are lost, e.g. in a
+/// tunnel.
+///
+/// Assumes the device kept moving from `last_fix` at `speed_ms` meters per
+/// second along `heading_degrees` (clockwise from north) since the fix was
+/// taken. The estimate drifts quickly, so only use it for short gaps.
+pub fn dead_reckoning_position(
+    last_fix: (Coordinates, SystemTime),
+    heading_degrees: f64,
+    speed_ms: f64,
+) -> Coordinates {
+    let (origin, fix_time) = last_fix;
+    let elapsed = crate::now()
+        .duration_since(fix_time)
+        .unwrap_or_default()
+        .as_secs_f64();
+    let distance_m = elapsed * speed_ms;
+    if !distance_m.is_finite() || distance_m <= 0.0 || !heading_degrees.is_finite() {
+        return origin;
+    }
+    destination_point(origin, heading_degrees, distance_m)
+}
+
+/// Point where the great-circle segments `a1`-`a2` and `b1`-`b2` cross.
+///
+/// Returns `None` if the segments do not intersect, or if they lie on the
+/// same great circle (overlapping segments have no single crossing point).
+pub fn track_segment_intersection(
+    a1: Coordinates,
+    a2: Coordinates,
+    b1: Coordinates,
+    b2: Coordinates,
+) -> Option<Coordinates> {
+    let (a1, a2) = (to_unit_vector(a1), to_unit_vector(a2));
+    let (b1, b2) = (to_unit_vector(b1), to_unit_vector(b2));
+    let normal_a = cross(a1, a2);
+    let normal_b = cross(b1, b2);
+
+    let line = cross(normal_a, normal_b);
+    let length = dot(line, line).sqrt();
+    if length < 1e-12 {
+        return None;
+    }
+    let candidate = scale(line, 1.0 / length);
+
+    // The great circles meet at two antipodal points; keep the one lying on
+    // both minor arcs, if any.
+    [candidate, scale(candidate, -1.0)]
+        .into_iter()
+        .find(|&point| on_arc(point, a1, a2, normal_a) && on_arc(point, b1, b2, normal_b))
+        .map(from_unit_vector)
+}
+
+/// Position along a timestamped `track` at time `t`.
+///
+/// Finds the two fixes bracketing `t` and interpolates along the great
+/// circle between them (spherical linear interpolation), which gives smooth
+/// playback animation instead of jumping between raw fixes. `track` must be
+/// ordered by time.
+///
+/// Returns `None` if `t` is outside the time range of the track.
+pub fn interpolate_at(track: &[(Coordinates, SystemTime)], t: SystemTime) -> Option<Coordinates> {
+    let (first, last) = (track.first()?, track.last()?);
+    if t < first.1 || t > last.1 {
+        return None;
+    }
+
+    let end = track.partition_point(|(_, time)| *time < t);
+    let (to, to_time) = track[end];
+    if end == 0 || to_time == t {
+        return Some(to);
+    }
+    let (from, from_time) = track[end - 1];
+
+    let span = to_time.duration_since(from_time).ok()?.as_secs_f64();
+    let elapsed = t.duration_since(from_time).ok()?.as_secs_f64();
+    Some(slerp(from, to, elapsed / span))
+}
+
+/// Spherical linear interpolation between `from` (`fraction = 0`) and `to`
+/// (`fraction = 1`).
+fn slerp(from: Coordinates, to: Coordinates, fraction: f64) -> Coordinates {
+    let (a, b) = (to_unit_vector(from), to_unit_vector(to));
+    let angle = dot(a, b).clamp(-1.0, 1.0).acos();
+    if angle < 1e-12 {
+        return from;
+    }
+
+    let sin_angle = angle.sin();
+    let weight_a = ((1.0 - fraction) * angle).sin() / sin_angle;
+    let weight_b = (fraction * angle).sin() / sin_angle;
+    from_unit_vector((
+        weight_a * a.0 + weight_b * b.0,
+        weight_a * a.1 + weight_b * b.1,
+        weight_a * a.2 + weight_b * b.2,
+    ))
+}
+
+type Vector3 = (f64, f64, f64);
+
+pub(crate) fn to_unit_vector(point: Coordinates) -> Vector3 {
+    let (lat, lon) = (point.latitude.to_radians(), point.longitude.to_radians());
+    (lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin())
+}
+
+pub(crate) fn from_unit_vector((x, y, z): Vector3) -> Coordinates {
+    Coordinates {
+        latitude: z.atan2((x * x + y * y).sqrt()).to_degrees(),
+        longitude: y.atan2(x).to_degrees(),
+    }
+}
+
+fn cross(a: Vector3, b: Vector3) -> Vector3 {
+    (
+        a.1 * b.2 - a.2 * b.1,
+        a.2 * b.0 - a.0 * b.2,
+        a.0 * b.1 - a.1 * b.0,
+    )
+}
+
+fn dot(a: Vector3, b: Vector3) -> f64 {
+    a.0 * b.0 + a.1 * b.1 + a.2 * b.2
+}
+
+fn scale(a: Vector3, factor: f64) -> Vector3 {
+    (a.0 * factor, a.1 * factor, a.2 * factor)
+}
+
+/// Whether `point` (on the great circle through `start` and `end`) lies on
+/// the minor arc between them.
+fn on_arc(point: Vector3, start: Vector3, end: Vector3, normal: Vector3) -> bool {
+    const EPSILON: f64 = -1e-12;
+    dot(cross(start, point), normal) >= EPSILON && dot(cross(point, end), normal) >= EPSILON
+}
+
+/// Total ascent and descent along an elevation profile.
+///
+/// `profile` holds `(distance_m, elevation_m)` samples ordered by distance.
+/// GPS altitude is noisy, so changes smaller than `threshold_m` are ignored:
+/// elevation is only counted once it has moved at least `threshold_m` away
+/// from the last counted point. A threshold of a few meters works well for
+/// GPS data; use `0.0` for clean (e.g. barometric or DEM) data.
+///
+/// Returns `(total_gain_m, total_loss_m)`, both non-negative.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::geo_math::route_elevation_gain_loss;
+///
+/// let profile = [(0.0, 100.0), (50.0, 101.0), (100.0, 110.0), (150.0, 104.0)];
+/// assert_eq!(route_elevation_gain_loss(&profile, 3.0), (10.0, 6.0));
+/// ```
+pub fn route_elevation_gain_loss(profile: &[(f64, f64)], threshold_m: f64) -> (f64, f64) {
+    let threshold_m = threshold_m.max(0.0);
+    let mut samples = profile.iter().map(|(_, elevation)| *elevation);
+    let Some(mut reference) = samples.next() else {
+        return (0.0, 0.0);
+    };
+
+    let (mut gain, mut loss) = (0.0, 0.0);
+    for elevation in samples {
+        let change = elevation - reference;
+        if change.abs() >= threshold_m && change != 0.0 {
+            if change > 0.0 {
+                gain += change;
+            } else {
+                loss -= change;
+            }
+            reference = elevation;
+        }
+    }
+
+    (gain, loss)
+}
+
+/// Indices of the points in `route` that are more than `max_jump_meters`
+/// away from the point before them.
+///
+/// A single bad fix in a GPS trace shows up as two jumps: onto the outlier
+/// and back off it. Use [`remove_outliers`] to drop the outliers themselves.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::geo_math::validate_route_continuity;
+/// use dioxus_mobile_geolocation::Coordinates;
+///
+/// let route = [
+///     Coordinates::new(52.5200, 13.4050),
+///     Coordinates::new(52.5201, 13.4051),
+///     Coordinates::new(52.6000, 13.4051),
+///     Coordinates::new(52.5202, 13.4052),
+/// ];
+/// assert_eq!(validate_route_continuity(&route, 100.0), vec![2, 3]);
+/// ```
+#[cfg(feature = "geo-math")]
+pub fn validate_route_continuity(route: &[Coordinates], max_jump_meters: f64) -> Vec<usize> {
+    route
+        .windows(2)
+        .enumerate()
+        .filter(|(_, pair)| distance_meters(pair[0], pair[1]) > max_jump_meters)
+        .map(|(index, _)| index + 1)
+        .collect()
+}
+
+/// `route` without the points that are more than `max_jump_meters` away
+/// from the last point kept.
+///
+/// The first point is always kept, so it should be a trusted fix. A real
+/// gap longer than `max_jump_meters`, such as after a tunnel, drops every
+/// point after it; pick the threshold from the fix interval and the
+/// fastest plausible speed.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::geo_math::remove_outliers;
+/// use dioxus_mobile_geolocation::Coordinates;
+///
+/// let route = [
+///     Coordinates::new(52.5200, 13.4050),
+///     Coordinates::new(52.6000, 13.4051),
+///     Coordinates::new(52.5202, 13.4052),
+/// ];
+/// assert_eq!(remove_outliers(&route, 100.0).len(), 2);
+/// ```
+#[cfg(feature = "geo-math")]
+pub fn remove_outliers(route: &[Coordinates], max_jump_meters: f64) -> Vec<Coordinates> {
+    let mut kept: Vec<Coordinates> = Vec::with_capacity(route.len());
+    for &point in route {
+        let continuous = kept
+            .last()
+            .is_none_or(|&last| distance_meters(last, point) <= max_jump_meters);
+        if continuous {
+            kept.push(point);
+        }
+    }
+    kept
+}
+
+/// Split a timestamped `route` into segments wherever more than
+/// `gap_seconds` pass between two consecutive points.
+///
+/// Traces recorded across several journeys have gaps where the device was
+/// off or the user parked; each segment can be exported as its own GPX track
+/// or drawn as a separate polyline. `route` should be ordered by time; a
+/// point older than the one before it never starts a segment.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::geo_math::route_split_by_gap;
+/// use dioxus_mobile_geolocation::Coordinates;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let at = |seconds| UNIX_EPOCH + Duration::from_secs(seconds);
+/// let route = [
+///     (Coordinates::new(52.5200, 13.4050), at(0)),
+///     (Coordinates::new(52.5201, 13.4051), at(10)),
+///     (Coordinates::new(52.5300, 13.4100), at(3600)),
+/// ];
+/// let segments = route_split_by_gap(&route, 300.0);
+/// assert_eq!(segments.len(), 2);
+/// assert_eq!(segments[0].len(), 2);
+/// ```
+#[cfg(feature = "geo-math")]
+pub fn route_split_by_gap(
+    route: &[(Coordinates, SystemTime)],
+    gap_seconds: f64,
+) -> Vec<Vec<(Coordinates, SystemTime)>> {
+    let mut segments: Vec<Vec<(Coordinates, SystemTime)>> = Vec::new();
+    for &(point, time) in route {
+        let current = segments.last_mut().filter(|segment| {
+            let (_, previous) = segment[segment.len() - 1];
+            let gap = time.duration_since(previous).unwrap_or_default();
+            gap.as_secs_f64() <= gap_seconds
+        });
+        match current {
+            Some(segment) => segment.push((point, time)),
+            None => segments.push(vec![(point, time)]),
+        }
+    }
+    segments
+}
+
+/// Average of compass headings in degrees, handling the wrap at 360°.
+///
+/// Averaging 350° and 10° arithmetically gives 180°; this treats each
+/// heading as a unit vector and returns the direction of their sum instead
+/// (the circular mean). The result is in `[0, 360)`. Returns `NaN` if
+/// `headings` is empty or the headings cancel out (e.g. 0° and 180°).
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::geo_math::heading_circular_mean;
+///
+/// let mean = heading_circular_mean(&[350.0, 10.0]);
+/// assert!(mean < 1e-9 || 360.0 - mean < 1e-9);
+/// assert!(mean < 360.0);
+/// assert!((heading_circular_mean(&[80.0, 100.0]) - 90.0).abs() < 1e-9);
+/// ```
+pub fn heading_circular_mean(headings: &[f64]) -> f64 {
+    let (sin_sum, cos_sum) = headings
+        .iter()
+        .map(|heading| heading.to_radians().sin_cos())
+        .fold((0.0, 0.0), |(sin_sum, cos_sum), (sin, cos)| {
+            (sin_sum + sin, cos_sum + cos)
+        });
+
+    if headings.is_empty() || sin_sum.hypot(cos_sum) < 1e-9 * headings.len() as f64 {
+        return f64::NAN;
+    }
+
+    // `rem_euclid` rounds tiny negative angles up to exactly 360.
+    let mean = sin_sum.atan2(cos_sum).to_degrees().rem_euclid(360.0);
+    if mean >= 360.0 {
+        0.0
+    } else {
+        mean
+    }
+}
+
+/// A unit for displaying speeds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SpeedUnit {
+    MetersPerSecond,
+    KilometersPerHour,
+    MilesPerHour,
+    Knots,
+}
+
+impl SpeedUnit {
+    /// Meters per second in one of this unit.
+    fn meters_per_second(self) -> f64 {
+        match self {
+            SpeedUnit::MetersPerSecond => 1.0,
+            SpeedUnit::KilometersPerHour => 1000.0 / 3600.0,
+            SpeedUnit::MilesPerHour => 1609.344 / 3600.0,
+            SpeedUnit::Knots => 1852.0 / 3600.0,
+        }
+    }
+}
+
+/// Convert a speed in meters per second (as reported by location fixes) to
+/// `unit`.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::geo_math::{speed_unit_convert, SpeedUnit};
+///
+/// assert!((speed_unit_convert(10.0, SpeedUnit::KilometersPerHour) - 36.0).abs() < 1e-9);
+/// ```
+pub fn speed_unit_convert(speed_ms: f64, unit: SpeedUnit) -> f64 {
+    speed_ms / unit.meters_per_second()
+}
+
+/// Convert a speed in `from` units to meters per second; the inverse of
+/// [`speed_unit_convert`].
+pub fn to_meters_per_second(value: f64, from: SpeedUnit) -> f64 {
+    value * from.meters_per_second()
+}
+
+/// A unit for displaying altitudes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AltitudeUnit {
+    Meters,
+    /// International feet, as used in aviation.
+    Feet,
+    Fathoms,
+}
+
+/// Convert an altitude in meters (as reported by location fixes) to `unit`.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::geo_math::{altitude_unit_convert, AltitudeUnit};
+///
+/// assert!((altitude_unit_convert(304.8, AltitudeUnit::Feet) - 1000.0).abs() < 1e-9);
+/// ```
+pub fn altitude_unit_convert(alt_m: f64, unit: AltitudeUnit) -> f64 {
+    match unit {
+        AltitudeUnit::Meters => alt_m,
+        AltitudeUnit::Feet => alt_m / 0.3048,
+        AltitudeUnit::Fathoms => alt_m / 1.8288,
+    }
+}
+
+/// An offset in degrees of latitude and longitude.
+///
+/// Adding a delta to [`Coordinates`] normalizes the result, so offsets that
+/// cross a pole or the antimeridian still produce valid coordinates.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CoordinateDelta {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl CoordinateDelta {
+    /// Create a delta from offsets in degrees.
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+        }
+    }
+}
+
+impl Neg for CoordinateDelta {
+    type Output = CoordinateDelta;
+
+    fn neg(self) -> CoordinateDelta {
+        CoordinateDelta::new(-self.latitude, -self.longitude)
+    }
+}
+
+impl Add<CoordinateDelta> for Coordinates {
+    type Output = Coordinates;
+
+    fn add(self, delta: CoordinateDelta) -> Coordinates {
+        Coordinates::new(
+            self.latitude + delta.latitude,
+            self.longitude + delta.longitude,
+        )
+    }
+}
+
+impl AddAssign<CoordinateDelta> for Coordinates {
+    fn add_assign(&mut self, delta: CoordinateDelta) {
+        *self = *self + delta;
+    }
+}
+
+impl Sub<CoordinateDelta> for Coordinates {
+    type Output = Coordinates;
+
+    fn sub(self, delta: CoordinateDelta) -> Coordinates {
+        self + -delta
+    }
+}
+
+impl SubAssign<CoordinateDelta> for Coordinates {
+    fn sub_assign(&mut self, delta: CoordinateDelta) {
+        *self = *self - delta;
+    }
+}
+
+/// An axis-aligned latitude/longitude bounding box.
+///
+/// If the box crosses the antimeridian, `east` is smaller than `west`.
+///
+/// Displays as its south-west and north-east corners. A precision given in
+/// the format string applies to every coordinate:
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::geo_math::CoordinateBounds;
+///
+/// let bounds = CoordinateBounds {
+///     north: 48.8712,
+///     south: 48.8498,
+///     east: 2.3101,
+///     west: 2.2894,
+/// };
+/// assert_eq!(format!("{bounds:.2}"), "SW(48.85, 2.29) – NE(48.87, 2.31)");
+/// ```
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CoordinateBounds {
+    pub north: f64,
+    pub south: f64,
+    pub east: f64,
+    pub west: f64,
+}
+
+impl CoordinateBounds {
+    /// Smallest bounding box containing all `points`.
+    ///
+    /// If the points are closer together across the antimeridian than
+    /// around it, the box crosses the antimeridian. Returns `None` if
+    /// `points` is empty.
+    pub fn from_coordinates(points: &[Coordinates]) -> Option<CoordinateBounds> {
+        let first = points.first()?;
+        let (mut north, mut south) = (first.latitude, first.latitude);
+        let mut longitudes = Vec::with_capacity(points.len());
+        for point in points {
+            north = north.max(point.latitude);
+            south = south.min(point.latitude);
+            longitudes.push(normalize_longitude(point.longitude));
+        }
+        longitudes.sort_by(f64::total_cmp);
+
+        // The box spans everything except the widest empty longitude gap.
+        let (min, max) = (longitudes[0], longitudes[longitudes.len() - 1]);
+        let (mut west, mut east, mut widest_gap) = (min, max, min + 360.0 - max);
+        for pair in longitudes.windows(2) {
+            let gap = pair[1] - pair[0];
+            if gap > widest_gap {
+                widest_gap = gap;
+                west = pair[1];
+                east = pair[0];
+            }
+        }
+
+        Some(CoordinateBounds {
+            north,
+            south,
+            east,
+            west,
+        })
+    }
+
+    /// Smallest bounding box containing the circle of `radius_meters` around
+    /// `center`.
+    ///
+    /// If the circle covers a pole, the box spans all longitudes.
+    pub fn from_center_radius(center: Coordinates, radius_meters: f64) -> CoordinateBounds {
+        let angular = radius_meters.max(0.0) / EARTH_RADIUS_METERS;
+        let lat = center.latitude.to_radians();
+        let north = (lat + angular).to_degrees();
+        let south = (lat - angular).to_degrees();
+
+        if north >= 90.0 || south <= -90.0 {
+            return CoordinateBounds {
+                north: north.min(90.0),
+                south: south.max(-90.0),
+                east: 180.0,
+                west: -180.0,
+            };
+        }
+
+        // Widest longitude offset of the circle, reached at the tangent points
+        // rather than due east/west of the center.
+        let d_lon = (angular.sin() / lat.cos()).asin().to_degrees();
+
+        CoordinateBounds {
+            north,
+            south,
+            east: normalize_longitude(center.longitude + d_lon),
+            west: normalize_longitude(center.longitude - d_lon),
+        }
+    }
+
+    /// Same as [`from_center_radius`](Self::from_center_radius).
+    pub fn from_center(center: Coordinates, radius_m: f64) -> CoordinateBounds {
+        Self::from_center_radius(center, radius_m)
+    }
+
+    /// Center of the box, taking antimeridian crossing into account.
+    pub fn center(&self) -> Coordinates {
+        Coordinates::new(
+            (self.north + self.south) / 2.0,
+            self.west + self.longitude_span() / 2.0,
+        )
+    }
+
+    /// Distance between the south-west and north-east corners in meters.
+    pub fn diagonal_meters(&self) -> f64 {
+        distance_meters(self.south_west(), self.north_east())
+    }
+
+    /// Same as [`diagonal_meters`](Self::diagonal_meters).
+    pub fn diagonal_m(&self) -> f64 {
+        self.diagonal_meters()
+    }
+
+    /// South-west corner of the box.
+    pub fn south_west(&self) -> Coordinates {
+        Coordinates {
+            latitude: self.south,
+            longitude: self.west,
+        }
+    }
+
+    /// North-east corner of the box.
+    pub fn north_east(&self) -> Coordinates {
+        Coordinates {
+            latitude: self.north,
+            longitude: self.east,
+        }
+    }
+
+    /// Expand the box by `meters` on all sides.
+    ///
+    /// The longitude padding is computed at the box's most poleward latitude,
+    /// so every edge moves out by at least `meters`. If the padded box would
+    /// wrap all the way around the globe it spans all longitudes.
+    pub fn pad(&self, meters: f64) -> CoordinateBounds {
+        let meters = meters.max(0.0);
+        let d_lat = (meters / EARTH_RADIUS_METERS).to_degrees();
+        let north = (self.north + d_lat).min(90.0);
+        let south = (self.south - d_lat).max(-90.0);
+
+        let latitude = north.abs().max(south.abs());
+        match longitude_degrees(meters, latitude) {
+            Some(d_lon) if self.longitude_span() + 2.0 * d_lon < 360.0 => CoordinateBounds {
+                north,
+                south,
+                east: normalize_longitude(self.east + d_lon),
+                west: normalize_longitude(self.west - d_lon),
+            },
+            _ => CoordinateBounds {
+                north,
+                south,
+                east: 180.0,
+                west: -180.0,
+            },
+        }
+    }
+
+    /// Same as [`pad`](Self::pad).
+    pub fn expand(&self, margin_m: f64) -> CoordinateBounds {
+        self.pad(margin_m)
+    }
+
+    /// Shrink the box by `meters` on all sides.
+    ///
+    /// Returns `None` if the box is too small to shrink that far, i.e. the
+    /// result would be inverted.
+    pub fn shrink(&self, meters: f64) -> Option<CoordinateBounds> {
+        let meters = meters.max(0.0);
+        let d_lat = (meters / EARTH_RADIUS_METERS).to_degrees();
+        let north = self.north - d_lat;
+        let south = self.south + d_lat;
+        if south > north {
+            return None;
+        }
+
+        let latitude = self.north.abs().max(self.south.abs());
+        let d_lon = longitude_degrees(meters, latitude)?;
+        if 2.0 * d_lon > self.longitude_span() {
+            return None;
+        }
+
+        Some(CoordinateBounds {
+            north,
+            south,
+            east: normalize_longitude(self.east - d_lon),
+            west: normalize_longitude(self.west + d_lon),
+        })
+    }
+
+    /// Smallest box containing both `self` and `other`.
+    ///
+    /// The longitude range is the shorter way around the globe that covers
+    /// both boxes, so the result may cross the antimeridian.
+    pub fn union(&self, other: &CoordinateBounds) -> CoordinateBounds {
+        let (span_a, span_b) = (self.longitude_span(), other.longitude_span());
+        let (west, span) = if longitude_offset(self.west, other.west) + span_b <= span_a {
+            (self.west, span_a)
+        } else if longitude_offset(other.west, self.west) + span_a <= span_b {
+            (other.west, span_b)
+        } else {
+            // Either extend `self` east to cover `other` or the other way round
+            let from_self = longitude_offset(self.west, other.west) + span_b;
+            let from_other = longitude_offset(other.west, self.west) + span_a;
+            if from_self <= from_other {
+                (self.west, from_self)
+            } else {
+                (other.west, from_other)
+            }
+        };
+
+        let (west, east) = if span >= 360.0 {
+            (-180.0, 180.0)
+        } else {
+            (west, east_of(west, span))
+        };
+        CoordinateBounds {
+            north: self.north.max(other.north),
+            south: self.south.min(other.south),
+            east,
+            west,
+        }
+    }
+
+    /// Area covered by both `self` and `other`, or `None` if they don't
+    /// overlap.
+    ///
+    /// Two boxes that both nearly circle the globe can overlap in two
+    /// separate longitude ranges; only the one starting at `other.west` is
+    /// returned then.
+    pub fn intersection(&self, other: &CoordinateBounds) -> Option<CoordinateBounds> {
+        let north = self.north.min(other.north);
+        let south = self.south.max(other.south);
+        if south > north {
+            return None;
+        }
+
+        let (span_a, span_b) = (self.longitude_span(), other.longitude_span());
+        let (offset_b, offset_a) = (
+            longitude_offset(self.west, other.west),
+            longitude_offset(other.west, self.west),
+        );
+        let (west, span) = if offset_b <= span_a {
+            (other.west, (span_a - offset_b).min(span_b))
+        } else if offset_a <= span_b {
+            (self.west, (span_b - offset_a).min(span_a))
+        } else {
+            return None;
+        };
+
+        let (west, east) = if span >= 360.0 {
+            (-180.0, 180.0)
+        } else {
+            (west, east_of(west, span))
+        };
+        Some(CoordinateBounds {
+            north,
+            south,
+            east,
+            west,
+        })
+    }
+
+    /// Whether `point` lies inside the box or on its edge.
+    ///
+    /// Longitudes are compared going east from `west`, so boxes crossing the
+    /// antimeridian work, and `180` and `-180` are the same meridian.
+    ///
+    /// ```rust
+    /// use dioxus_mobile_geolocation::geo_math::CoordinateBounds;
+    /// use dioxus_mobile_geolocation::Coordinates;
+    ///
+    /// let fiji = CoordinateBounds {
+    ///     north: -12.0,
+    ///     south: -21.0,
+    ///     east: -178.0,
+    ///     west: 177.0,
+    /// };
+    /// assert!(fiji.contains(Coordinates::new(-17.7, 178.1)));
+    /// assert!(fiji.contains(Coordinates::new(-16.8, -179.9)));
+    /// assert!(!fiji.contains(Coordinates::new(-17.7, 170.0)));
+    /// ```
+    pub fn contains(&self, point: Coordinates) -> bool {
+        (self.south..=self.north).contains(&point.latitude)
+            && longitude_offset(self.west, point.longitude) <= self.longitude_span()
+    }
+
+    /// Whether `self` and `other` share any point, including touching
+    /// edges.
+    pub fn intersects(&self, other: &CoordinateBounds) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Approximate surface area of the box in square kilometers.
+    ///
+    /// Treats the box as a trapezoid: the Haversine distance between the
+    /// southern and northern edges times the average length of the two
+    /// edges along their parallels. Accurate for boxes up to a few hundred
+    /// kilometers across.
+    pub fn area_km2(&self) -> f64 {
+        let height = distance_meters(
+            Coordinates {
+                latitude: self.south,
+                longitude: self.west,
+            },
+            Coordinates {
+                latitude: self.north,
+                longitude: self.west,
+            },
+        );
+        let parallel_length = |latitude: f64| {
+            EARTH_RADIUS_METERS * latitude.to_radians().cos() * self.longitude_span().to_radians()
+        };
+        let width = (parallel_length(self.south) + parallel_length(self.north)) / 2.0;
+        height * width / 1_000_000.0
+    }
+
+    /// Width of the box in degrees of longitude.
+    fn longitude_span(&self) -> f64 {
+        if self.east >= self.west {
+            self.east - self.west
+        } else {
+            self.east - self.west + 360.0
+        }
+    }
+}
+
+impl fmt::Display for CoordinateBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(
+                f,
+                "SW({:.p$}, {:.p$}) – NE({:.p$}, {:.p$})",
+                self.south,
+                self.west,
+                self.north,
+                self.east,
+                p = precision
+            ),
+            None => write!(
+                f,
+                "SW({}, {}) – NE({}, {})",
+                self.south, self.west, self.north, self.east
+            ),
+        }
+    }
+}
+
+impl fmt::Debug for CoordinateBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CoordinateBounds")
+            .field("north", &self.north)
+            .field("south", &self.south)
+            .field("east", &self.east)
+            .field("west", &self.west)
+            .field("crosses_antimeridian", &(self.east < self.west))
+            .field("center", &self.center())
+            .field("diagonal_meters", &self.diagonal_meters())
+            .finish()
+    }
+}
+
+/// Degrees east from longitude `from` to longitude `to`, in `[0, 360)`.
+fn longitude_offset(from: f64, to: f64) -> f64 {
+    (to - from).rem_euclid(360.0)
+}
+
+/// Longitude `span` degrees east of `west`, keeping `180.0` as is so that a
+/// box ending at the antimeridian doesn't appear to cross it.
+fn east_of(west: f64, span: f64) -> f64 {
+    let east = west + span;
+    if east > 180.0 {
+        east - 360.0
+    } else {
+        east
+    }
+}
+
+/// Degrees of longitude spanning `meters` along the parallel at `latitude`.
+///
+/// Returns `None` at the poles, where a parallel has no length.
+fn longitude_degrees(meters: f64, latitude: f64) -> Option<f64> {
+    let circumference = EARTH_RADIUS_METERS * latitude.to_radians().cos();
+    (circumference > 1e-6).then(|| (meters / circumference).to_degrees())
+}
+
+/// A circular sector around `center`: the points within `radius_meters`
+/// whose bearing from `center` lies between `bearing_start` and
+/// `bearing_end`.
+///
+/// Bearings are in degrees clockwise from north and the sector runs
+/// clockwise from `bearing_start` to `bearing_end`, so a sector from `330.0`
+/// to `30.0` faces north. Useful for "what's ahead of me" queries.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::geo_math::{destination_point, SectorQuery};
+/// use dioxus_mobile_geolocation::Coordinates;
+///
+/// let here = Coordinates::new(52.52, 13.405);
+/// let ahead = SectorQuery {
+///     center: here,
+///     radius_meters: 2_000.0,
+///     bearing_start: 30.0,
+///     bearing_end: 90.0,
+/// };
+/// assert!(ahead.contains(destination_point(here, 60.0, 1_000.0)));
+/// assert!(!ahead.contains(destination_point(here, 120.0, 1_000.0)));
+/// assert!(!ahead.contains(destination_point(here, 60.0, 3_000.0)));
+/// ```
+#[cfg(feature = "geo-math")]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SectorQuery {
+    pub center: Coordinates,
+    pub radius_meters: f64,
+    pub bearing_start: f64,
+    pub bearing_end: f64,
+}
+
+#[cfg(feature = "geo-math")]
+impl SectorQuery {
+    /// Whether `c` lies inside the sector.
+    ///
+    /// The center itself has no bearing and counts as inside.
+    pub fn contains(&self, c: Coordinates) -> bool {
+        let distance = distance_meters(self.center, c);
+        if distance > self.radius_meters {
+            return false;
+        }
+        if distance == 0.0 {
+            return true;
+        }
+
+        let width = (self.bearing_end - self.bearing_start).rem_euclid(360.0);
+        let offset = (initial_bearing(self.center, c) - self.bearing_start).rem_euclid(360.0);
+        offset <= width
+    }
+}
+
+/// An ordered sequence of coordinates describing a route.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Polyline {
+    pub points: Vec<Coordinates>,
+}
+
+impl Polyline {
+    /// Create a polyline from its vertices.
+    pub fn new(points: Vec<Coordinates>) -> Self {
+        Self { points }
+    }
+
+    /// Smallest bounding box containing the whole route, or `None` if the
+    /// polyline is empty.
+    pub fn bounding_box(&self) -> Option<CoordinateBounds> {
+        CoordinateBounds::from_coordinates(&self.points)
+    }
+
+    /// Length of the route in meters, summing the Haversine distances
+    /// between successive vertices.
+    pub fn total_length_meters(&self) -> f64 {
+        self.remaining_length_meters(0)
+    }
+
+    /// Time needed to travel the whole route at `speed_ms` meters per second.
+    ///
+    /// Returns [`Duration::MAX`] if the speed is zero, negative or not finite.
+    pub fn duration_at_speed(&self, speed_ms: f64) -> Duration {
+        eta_for_distance(self.total_length_meters(), speed_ms)
+    }
+
+    /// Decode a route in Google's Encoded Polyline Algorithm Format.
+    ///
+    /// `precision` is the number of decimal places the coordinates were
+    /// encoded with: `5` for Google Maps, `6` for OSRM and Valhalla.
+    ///
+    /// ```rust
+    /// use dioxus_mobile_geolocation::geo_math::Polyline;
+    ///
+    /// let route = Polyline::from_encoded_polyline("_p~iF~ps|U_ulLnnqC_mqNvxq`@", 5).unwrap();
+    /// assert_eq!(route.points.len(), 3);
+    /// assert_eq!(route.points[0].latitude, 38.5);
+    /// assert_eq!(route.points[2].longitude, -126.453);
+    /// assert_eq!(route.to_encoded_polyline(5), "_p~iF~ps|U_ulLnnqC_mqNvxq`@");
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// [`Error::InvalidCoordinates`] if `encoded` is malformed or decodes to
+    /// coordinates out of range, which usually means the wrong `precision`.
+    pub fn from_encoded_polyline(encoded: &str, precision: u8) -> Result<Polyline> {
+        let factor = 10f64.powi(i32::from(precision));
+        let mut bytes = encoded.bytes();
+        let (mut latitude, mut longitude) = (0i64, 0i64);
+        let mut points = Vec::new();
+
+        while let Some(d_lat) = decode_polyline_value(&mut bytes)? {
+            let d_lon = decode_polyline_value(&mut bytes)?.ok_or(Error::InvalidCoordinates)?;
+            latitude = latitude
+                .checked_add(d_lat)
+                .ok_or(Error::InvalidCoordinates)?;
+            longitude = longitude
+                .checked_add(d_lon)
+                .ok_or(Error::InvalidCoordinates)?;
+
+            let point = Coordinates {
+                latitude: latitude as f64 / factor,
+                longitude: longitude as f64 / factor,
+            };
+            if point.latitude.abs() > 90.0 || point.longitude.abs() > 180.0 {
+                return Err(Error::InvalidCoordinates);
+            }
+            points.push(point);
+        }
+
+        Ok(Polyline { points })
+    }
+
+    /// Encode the route in Google's Encoded Polyline Algorithm Format with
+    /// `precision` decimal places (`5` for Google Maps, `6` for OSRM).
+    pub fn to_encoded_polyline(&self, precision: u8) -> String {
+        let factor = 10f64.powi(i32::from(precision));
+        let mut encoded = String::new();
+        let (mut previous_lat, mut previous_lon) = (0i64, 0i64);
+
+        for point in &self.points {
+            let latitude = (point.latitude * factor).round() as i64;
+            let longitude = (point.longitude * factor).round() as i64;
+            encode_polyline_value(latitude - previous_lat, &mut encoded);
+            encode_polyline_value(longitude - previous_lon, &mut encoded);
+            (previous_lat, previous_lon) = (latitude, longitude);
+        }
+
+        encoded
+    }
+
+    /// Length of the route from vertex `start` to the end, in meters.
+    fn remaining_length_meters(&self, start: usize) -> f64 {
+        self.points
+            .get(start..)
+            .unwrap_or_default()
+            .windows(2)
+            .map(|segment| distance_meters(segment[0], segment[1]))
+            .sum()
+    }
+}
+
+impl From<Vec<Coordinates>> for Polyline {
+    fn from(points: Vec<Coordinates>) -> Self {
+        Self::new(points)
+    }
+}
+
+/// Read one zigzag-encoded value of an encoded polyline, or `None` at the
+/// end of the input.
+fn decode_polyline_value(bytes: &mut impl Iterator<Item = u8>) -> Result<Option<i64>> {
+    let mut value = 0u64;
+    let mut shift = 0;
+    loop {
+        let Some(byte) = bytes.next() else {
+            // Running out of input is only fine between values
+            return if shift == 0 {
+                Ok(None)
+            } else {
+                Err(Error::InvalidCoordinates)
+            };
+        };
+        let chunk = u64::from(byte)
+            .checked_sub(63)
+            .filter(|chunk| *chunk < 0x40 && shift < 64)
+            .ok_or(Error::InvalidCoordinates)?;
+        value |= (chunk & 0x1f) << shift;
+        shift += 5;
+        if chunk < 0x20 {
+            let magnitude = (value >> 1) as i64;
+            return Ok(Some(if value & 1 == 1 {
+                !magnitude
+            } else {
+                magnitude
+            }));
+        }
+    }
+}
+
+/// Append one value to an encoded polyline as 5-bit chunks, least
+/// significant first.
+fn encode_polyline_value(value: i64, encoded: &mut String) {
+    let mut value = if value < 0 {
+        !((value as u64) << 1)
+    } else {
+        (value as u64) << 1
+    };
+    while value >= 0x20 {
+        encoded.push(char::from((0x20 | (value & 0x1f)) as u8 + 63));
+        value >>= 5;
+    }
+    encoded.push(char::from(value as u8 + 63));
+}
+
+/// Smallest circle enclosing all `points`, as its center and radius in
+/// meters, or `None` if `points` is empty.
+///
+/// Uses Welzl's algorithm on an equirectangular projection around the first
+/// point, so it is only accurate for areas up to a few hundred kilometers
+/// across. Handy for fitting a map viewport to a set of points of interest.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::geo_math::{distance_meters, minimum_enclosing_circle};
+/// use dioxus_mobile_geolocation::Coordinates;
+///
+/// let west = Coordinates::new(0.0, -0.01);
+/// let east = Coordinates::new(0.0, 0.01);
+/// let (center, radius) = minimum_enclosing_circle(&[west, east, Coordinates::new(0.001, 0.0)]).unwrap();
+/// assert!(distance_meters(center, Coordinates::new(0.0, 0.0)) < 1.0);
+/// assert!((radius - distance_meters(west, east) / 2.0).abs() < 1.0);
+/// ```
+#[cfg(feature = "geo-math")]
+pub fn minimum_enclosing_circle(points: &[Coordinates]) -> Option<(Coordinates, f64)> {
+    let origin = *points.first()?;
+    let mut projected: Vec<(f64, f64)> = points
+        .iter()
+        .map(|point| project_local(origin, *point))
+        .collect();
+
+    // Welzl's expected linear running time relies on a random point order.
+    // A fixed-seed xorshift shuffle is enough and keeps results reproducible.
+    let mut state = 0x9E37_79B9_7F4A_7C15_u64;
+    for i in (1..projected.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        projected.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+
+    let mut circle = Circle::new(projected[0], 0.0);
+    for i in 1..projected.len() {
+        if circle.contains(projected[i]) {
+            continue;
+        }
+        circle = Circle::new(projected[i], 0.0);
+        for j in 0..i {
+            if circle.contains(projected[j]) {
+                continue;
+            }
+            circle = Circle::through_two(projected[i], projected[j]);
+            for k in 0..j {
+                if !circle.contains(projected[k]) {
+                    circle = Circle::through_three(projected[i], projected[j], projected[k]);
+                }
+            }
+        }
+    }
+
+    Some((unproject_local(origin, circle.center), circle.radius))
+}
+
+/// A circle in projected (east, north) meters.
+#[cfg(feature = "geo-math")]
+struct Circle {
+    center: (f64, f64),
+    radius: f64,
+}
+
+#[cfg(feature = "geo-math")]
+impl Circle {
+    fn new(center: (f64, f64), radius: f64) -> Self {
+        Self { center, radius }
+    }
+
+    /// Circle with `a` and `b` on opposite sides.
+    fn through_two(a: (f64, f64), b: (f64, f64)) -> Self {
+        let center = ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+        Self::new(center, planar_distance(a, b) / 2.0)
+    }
+
+    /// Circumcircle of `a`, `b` and `c`, or the circle around the farthest
+    /// pair if they are collinear.
+    fn through_three(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> Self {
+        let (bx, by) = (b.0 - a.0, b.1 - a.1);
+        let (cx, cy) = (c.0 - a.0, c.1 - a.1);
+        let d = 2.0 * (bx * cy - by * cx);
+        if d.abs() < 1e-12 {
+            return [(a, b), (a, c), (b, c)]
+                .into_iter()
+                .map(|(p, q)| Self::through_two(p, q))
+                .max_by(|p, q| p.radius.total_cmp(&q.radius))
+                .unwrap_or_else(|| Self::new(a, 0.0));
+        }
+
+        let (b_sq, c_sq) = (bx * bx + by * by, cx * cx + cy * cy);
+        let ux = (cy * b_sq - by * c_sq) / d;
+        let uy = (bx * c_sq - cx * b_sq) / d;
+        Self::new((a.0 + ux, a.1 + uy), ux.hypot(uy))
+    }
+
+    /// Whether `point` is inside, allowing for rounding errors.
+    fn contains(&self, point: (f64, f64)) -> bool {
+        planar_distance(self.center, point) <= self.radius * (1.0 + 1e-9) + 1e-6
+    }
+}
+
+#[cfg(feature = "geo-math")]
+fn planar_distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+#[cfg(feature = "geo-math")]
+impl Coordinates {
+    /// Radius in meters of the smallest circle enclosing all `points`, or
+    /// `0.0` if `points` is empty.
+    ///
+    /// See [`minimum_enclosing_circle`] for the center and accuracy.
+    ///
+    /// ```rust
+    /// use dioxus_mobile_geolocation::geo_math::distance_meters;
+    /// use dioxus_mobile_geolocation::Coordinates;
+    ///
+    /// let west = Coordinates::new(0.0, -0.01);
+    /// let east = Coordinates::new(0.0, 0.01);
+    /// let radius = Coordinates::bounding_circle_radius(&[west, east]);
+    /// assert!((radius - distance_meters(west, east) / 2.0).abs() < 1.0);
+    /// assert_eq!(Coordinates::bounding_circle_radius(&[]), 0.0);
+    /// ```
+    pub fn bounding_circle_radius(points: &[Coordinates]) -> f64 {
+        minimum_enclosing_circle(points).map_or(0.0, |(_, radius)| radius)
+    }
+}
+
+/// Estimated time to travel in a straight line from `current` to
+/// `destination` at `speed_ms` meters per second.
+///
+/// Returns [`Duration::ZERO`] if `current` is already at `destination`, and
+/// [`Duration::MAX`] if the speed is zero, negative or not finite.
+pub fn compute_eta(current: Coordinates, destination: Coordinates, speed_ms: f64) -> Duration {
+    eta_for_distance(distance_meters(current, destination), speed_ms)
+}
+
+/// Estimated time to finish `route` from vertex `progress_index` at
+/// `speed_ms` meters per second.
+///
+/// Returns [`Duration::ZERO`] once `progress_index` reaches the last vertex,
+/// and [`Duration::MAX`] if the speed is zero, negative or not finite.
+pub fn compute_eta_with_route(route: &Polyline, progress_index: usize, speed_ms: f64) -> Duration {
+    eta_for_distance(route.remaining_length_meters(progress_index), speed_ms)
+}
+
+fn eta_for_distance(distance_m: f64, speed_ms: f64) -> Duration {
+    if distance_m <= 0.0 {
+        return Duration::ZERO;
+    }
+    if !speed_ms.is_finite() || speed_ms <= 0.0 {
+        return Duration::MAX;
+    }
+    Duration::try_from_secs_f64(distance_m / speed_ms).unwrap_or(Duration::MAX)
+}
+
+/// A recorded trace of location fixes, e.g. a GPS track.
+///
+/// Two simplification algorithms are provided:
+///
+/// - [`LocationTrail::simplify`] (Ramer-Douglas-Peucker) removes every point
+///   closer than a distance tolerance to the simplified line. It is fast and
+///   the right choice when you know how much error you can accept.
+/// - [`LocationTrail::simplify_visvalingam`] (Visvalingam-Whyatt) repeatedly
+///   drops the point forming the smallest triangle with its neighbors. It
+///   keeps the overall shape better and is the right choice when you have a
+///   fixed point budget, e.g. for rendering a trace on a map.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocationTrail {
+    pub points: Vec<Coordinates>,
+}
+
+impl LocationTrail {
+    /// Create a trail from its fixes, oldest first.
+    pub fn new(points: Vec<Coordinates>) -> Self {
+        Self { points }
+    }
+
+    /// Simplify the trail with Ramer-Douglas-Peucker, removing points that
+    /// deviate less than `tolerance_meters` from the simplified line.
+    pub fn simplify(&self, tolerance_meters: f64) -> LocationTrail {
+        if self.points.len() < 3 {
+            return self.clone();
+        }
+
+        let projected = self.project();
+        let mut keep = vec![false; projected.len()];
+        keep[0] = true;
+        keep[projected.len() - 1] = true;
+
+        let mut stack = vec![(0, projected.len() - 1)];
+        while let Some((start, end)) = stack.pop() {
+            let (mut farthest, mut max_distance) = (start, 0.0);
+            for index in start + 1..end {
+                let distance =
+                    point_segment_distance(projected[index], projected[start], projected[end]);
+                if distance > max_distance {
+                    farthest = index;
+                    max_distance = distance;
+                }
+            }
+            if max_distance > tolerance_meters {
+                keep[farthest] = true;
+                stack.push((start, farthest));
+                stack.push((farthest, end));
+            }
+        }
+
+        self.retain(&keep)
+    }
+
+    /// Simplify the trail with Visvalingam-Whyatt down to at most
+    /// `target_points` points. The first and last points are always kept.
+    pub fn simplify_visvalingam(&self, target_points: usize) -> LocationTrail {
+        let target_points = target_points.max(2);
+        if self.points.len() <= target_points {
+            return self.clone();
+        }
+
+        let projected = self.project();
+        let len = projected.len();
+        let mut prev: Vec<usize> = (0..len).map(|i| i.saturating_sub(1)).collect();
+        let mut next: Vec<usize> = (1..=len).collect();
+        let mut keep = vec![true; len];
+        let area = |prev: usize, index: usize, next: usize| {
+            triangle_area(projected[prev], projected[index], projected[next])
+        };
+        let mut areas: Vec<f64> = (0..len)
+            .map(|i| {
+                if i == 0 || i == len - 1 {
+                    f64::INFINITY
+                } else {
+                    area(i - 1, i, i + 1)
+                }
+            })
+            .collect();
+
+        let mut heap: BinaryHeap<Candidate> = (1..len - 1)
+            .map(|index| Candidate::new(areas[index], index))
+            .collect();
+
+        // Points with the smallest effective area are removed first. A
+        // neighbor's area never drops below the area just removed, so the
+        // removal order reflects visual significance. Updated areas are
+        // pushed again, and stale heap entries skipped when popped.
+        let mut remaining = len;
+        while remaining > target_points {
+            let Some(candidate) = heap.pop() else {
+                break;
+            };
+            let (removed_area, index) = (candidate.area, candidate.index);
+            if !keep[index] || removed_area.total_cmp(&areas[index]).is_ne() {
+                continue;
+            }
+            keep[index] = false;
+            remaining -= 1;
+
+            let (before, after) = (prev[index], next[index]);
+            next[before] = after;
+            prev[after] = before;
+            if before != 0 {
+                areas[before] = area(prev[before], before, after).max(removed_area);
+                heap.push(Candidate::new(areas[before], before));
+            }
+            if after != len - 1 {
+                areas[after] = area(before, after, next[after]).max(removed_area);
+                heap.push(Candidate::new(areas[after], after));
+            }
+        }
+
+        self.retain(&keep)
+    }
+
+    fn retain(&self, keep: &[bool]) -> LocationTrail {
+        LocationTrail::new(
+            self.points
+                .iter()
+                .zip(keep)
+                .filter_map(|(point, keep)| keep.then_some(*point))
+                .collect(),
+        )
+    }
+
+    /// Project the trail onto a local plane (meters) around its first point.
+    fn project(&self) -> Vec<(f64, f64)> {
+        let origin = self.points[0];
+        self.points
+            .iter()
+            .map(|point| project_local(origin, *point))
+            .collect()
+    }
+}
+
+/// A point that [`LocationTrail::simplify_visvalingam`] may remove, ordered
+/// so the smallest area (then the lowest index) is the heap's maximum.
+struct Candidate {
+    area: f64,
+    index: usize,
+}
+
+impl Candidate {
+    fn new(area: f64, index: usize) -> Self {
+        Self { area, index }
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .area
+            .total_cmp(&self.area)
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for Candidate {}
+
+impl From<Vec<Coordinates>> for LocationTrail {
+    fn from(points: Vec<Coordinates>) -> Self {
+        Self::new(points)
+    }
+}
+
+/// Points evenly spaced along the great circle from one coordinate to
+/// another, e.g. to draw a flight route as a curve on a map.
+///
+/// The first point is `from` and each following point is `step_meters`
+/// further along the arc, so the last point is within `step_meters` of `to`.
+/// `to` itself is only included if the distance is a whole number of steps.
+/// A step that isn't positive yields only `from`. Between antipodal points
+/// every great circle is equally short, so the path is arbitrary.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::geo_math::{distance_meters, GreatCirclePath};
+/// use dioxus_mobile_geolocation::Coordinates;
+///
+/// let london = Coordinates::new(51.4700, -0.4543);
+/// let new_york = Coordinates::new(40.6413, -73.7781);
+/// let path: Vec<Coordinates> = GreatCirclePath::new(london, new_york, 100_000.0).collect();
+/// assert_eq!(path.len(), 56);
+/// assert!(distance_meters(path[55], new_york) < 100_000.0);
+/// ```
+#[cfg(feature = "geo-math")]
+#[derive(Debug, Clone)]
+pub struct GreatCirclePath {
+    from: Coordinates,
+    to: Coordinates,
+    step_meters: f64,
+    distance_m: f64,
+    /// Number of points, including `from`
+    len: u64,
+    next: u64,
+}
+
+#[cfg(feature = "geo-math")]
+impl GreatCirclePath {
+    /// Create a path from `from` towards `to` with a point every
+    /// `step_meters`.
+    pub fn new(from: Coordinates, to: Coordinates, step_meters: f64) -> Self {
+        let distance_m = distance_meters(from, to);
+        // Saturates for tiny steps; the path is consumed lazily anyway
+        let steps = if step_meters > 0.0 {
+            (distance_m / step_meters).floor() as u64
+        } else {
+            0
+        };
+        Self {
+            from,
+            to,
+            step_meters,
+            distance_m,
+            len: steps.saturating_add(1),
+            next: 0,
+        }
+    }
+}
+
+#[cfg(feature = "geo-math")]
+impl Iterator for GreatCirclePath {
+    type Item = Coordinates;
+
+    fn next(&mut self) -> Option<Coordinates> {
+        if self.next >= self.len {
+            return None;
+        }
+        let point = if self.next == 0 {
+            self.from
+        } else {
+            let fraction = self.next as f64 * self.step_meters / self.distance_m;
+            slerp(self.from, self.to, fraction)
+        };
+        self.next += 1;
+        Some(point)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = usize::try_from(self.len - self.next).ok();
+        (remaining.unwrap_or(usize::MAX), remaining)
+    }
+}
+
+/// Equirectangular projection of `point` to (east, north) meters from
+/// `origin`. Accurate for the short distances within a single trail.
+fn project_local(origin: Coordinates, point: Coordinates) -> (f64, f64) {
+    let d_lon = normalize_longitude(point.longitude - origin.longitude);
+    let x = d_lon.to_radians() * origin.latitude.to_radians().cos() * EARTH_RADIUS_METERS;
+    let y = (point.latitude - origin.latitude).to_radians() * EARTH_RADIUS_METERS;
+    (x, y)
+}
+
+/// Inverse of [`project_local`].
+#[cfg(feature = "geo-math")]
+fn unproject_local(origin: Coordinates, (x, y): (f64, f64)) -> Coordinates {
+    let latitude = origin.latitude + (y / EARTH_RADIUS_METERS).to_degrees();
+    let longitude = origin.longitude
+        + (x / (EARTH_RADIUS_METERS * origin.latitude.to_radians().cos())).to_degrees();
+    Coordinates::new(latitude, longitude)
+}
+
+fn point_segment_distance(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length_squared).clamp(0.0, 1.0)
+    };
+    ((p.0 - a.0 - t * dx).powi(2) + (p.1 - a.1 - t * dy).powi(2)).sqrt()
+}
+
+fn triangle_area(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+    ((b.0 - a.0) * (c.1 - a.1) - (c.0 - a.0) * (b.1 - a.1)).abs() / 2.0
+}
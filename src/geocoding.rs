@@ -0,0 +1,52 @@
+//! Reverse geocoding
+//!
+//! Turns coordinates into a human-readable address using a Nominatim (or
+//! Nominatim-compatible, e.g. Photon) server. Pointing this at a self-hosted
+//! instance keeps location data off third-party services.
+
+use crate::{Coordinates, Error, Result};
+use serde_json::Value;
+
+/// Look up the address closest to `coords` on the Nominatim server at
+/// `nominatim_url`.
+///
+/// Calls `{nominatim_url}/reverse?lat={lat}&lon={lon}&format=json` and
+/// returns the `display_name` field of the response.
+///
+/// ## Errors
+///
+/// - [`Error::Network`] if the server can't be reached, responds with an
+///   error status, or returns something other than JSON
+/// - [`Error::Unknown`] if the server has no address for the coordinates
+pub async fn approximate_address_from_coordinates(
+    coords: Coordinates,
+    nominatim_url: &str,
+) -> Result<String> {
+    let url = format!(
+        "{}/reverse?lat={}&lon={}&format=json",
+        nominatim_url.trim_end_matches('/'),
+        coords.latitude,
+        coords.longitude
+    );
+
+    // Nominatim's usage policy requires an identifying user agent. Browsers
+    // ignore this header and send their own.
+    let response: Value = reqwest::Client::new()
+        .get(&url)
+        .header(reqwest::header::USER_AGENT, USER_AGENT)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|_| Error::Network)?
+        .json()
+        .await
+        .map_err(|_| Error::Network)?;
+
+    response
+        .get("display_name")
+        .and_then(Value::as_str)
+        .map(str::to_owned)
+        .ok_or(Error::Unknown)
+}
+
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
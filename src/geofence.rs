@@ -0,0 +1,397 @@
+//! Geofence monitoring
+//!
+//! iOS and macOS hand the regions to CoreLocation's region monitoring. Every
+//! other platform watches the location and checks each fix against the
+//! regions itself; Android does this too, as its Geofencing API needs Google
+//! Play services.
+
+use crate::{Coordinates, LocationOptions, Result};
+use futures_channel::mpsc;
+use futures_core::Stream;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+use crate::{geo_math, now, watch::LocationCallback, Location, WatchHandle};
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+use std::sync::{Mutex, PoisonError};
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+use std::time::{Duration, SystemTime};
+
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+type Monitor = crate::darwin::GeofenceMonitor;
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+type Monitor = WatchHandle;
+
+/// Most regions CoreLocation monitors at once for an app on iOS and macOS.
+/// The other platforms check each fix against the regions themselves, so
+/// they have no limit.
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+pub(crate) const MAX_GEOFENCES: usize = 20;
+
+/// How long the device must stay inside a region before
+/// [`GeofenceEventKind::Dwell`] is reported
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+const DWELL_TIME: Duration = Duration::from_secs(5 * 60);
+
+/// Callback invoked by a geofence monitor for each event or error.
+pub(crate) type GeofenceCallback = Arc<dyn Fn(Result<GeofenceEvent>) + Send + Sync>;
+
+/// A circular region to monitor with [`watch_geofence`](crate::watch_geofence).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GeofenceRegion {
+    /// Identifier reported back in [`GeofenceEvent::region_id`].
+    pub id: String,
+    /// Center of the region.
+    pub center: Coordinates,
+    /// Radius of the region in meters.
+    pub radius_m: f64,
+}
+
+/// What happened at a region's boundary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GeofenceEventKind {
+    /// The device moved into the region.
+    Enter,
+    /// The device moved out of the region.
+    Exit,
+    /// The device stayed inside the region for five minutes.
+    ///
+    /// CoreLocation has no dwell transition, so this is never reported on
+    /// iOS and macOS.
+    Dwell,
+}
+
+/// A transition of one monitored region.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GeofenceEvent {
+    /// The [`GeofenceRegion::id`] of the region.
+    pub region_id: String,
+    /// The kind of transition.
+    pub kind: GeofenceEventKind,
+}
+
+/// Stream of geofence events returned by
+/// [`watch_geofence`](crate::watch_geofence).
+///
+/// Yields a `Result<GeofenceEvent>` for every transition or error. If
+/// monitoring can't be started at all, the stream yields that error and
+/// ends. Dropping the stream removes all of its regions.
+#[must_use = "geofence monitoring stops when the stream is dropped"]
+pub struct GeofenceStream {
+    receiver: mpsc::UnboundedReceiver<Result<GeofenceEvent>>,
+    _monitor: Option<Monitor>,
+}
+
+impl GeofenceStream {
+    pub(crate) fn new(options: &LocationOptions, regions: Vec<GeofenceRegion>) -> Self {
+        let (sender, receiver) = mpsc::unbounded();
+        let callback: GeofenceCallback = Arc::new(move |result| {
+            let _ = sender.unbounded_send(result);
+        });
+
+        let monitor = match start(options, regions, callback.clone()) {
+            Ok(monitor) => Some(monitor),
+            Err(error) => {
+                callback(Err(error));
+                None
+            }
+        };
+
+        // Only the monitor's callback keeps the channel open, so the stream
+        // ends right after the error if monitoring couldn't start.
+        Self {
+            receiver,
+            _monitor: monitor,
+        }
+    }
+}
+
+impl Stream for GeofenceStream {
+    type Item = Result<GeofenceEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+/// Geofences registered together with a single platform call.
+///
+/// ```rust,no_run
+/// use dioxus_mobile_geolocation::{Coordinates, GeofenceCollection, GeofenceRegion};
+///
+/// let stores = GeofenceCollection(vec![
+///     GeofenceRegion {
+///         id: "mitte".to_string(),
+///         center: Coordinates::new(52.52, 13.405),
+///         radius_m: 150.0,
+///     },
+///     GeofenceRegion {
+///         id: "kreuzberg".to_string(),
+///         center: Coordinates::new(52.499, 13.403),
+///         radius_m: 150.0,
+///     },
+/// ]);
+/// let handle = stores
+///     .register(|event| println!("{:?} {}", event.kind, event.region_id))
+///     .expect("geofences should be registered");
+/// # drop(handle);
+/// ```
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GeofenceCollection(pub Vec<GeofenceRegion>);
+
+impl GeofenceCollection {
+    /// Start monitoring every region, calling `on_event` for each transition.
+    ///
+    /// Fails with [`Error::TooManyGeofences`](crate::Error::TooManyGeofences)
+    /// if iOS or macOS would monitor more than 20 regions for the app,
+    /// counting those of other collections, and with the platform's error if
+    /// monitoring can't start. Errors after that are skipped; use
+    /// [`watch_geofence`](crate::watch_geofence) to receive them. The
+    /// callback must be `Send + Sync` because Android and desktop platforms
+    /// deliver events on other threads.
+    pub fn register(
+        self,
+        on_event: impl Fn(GeofenceEvent) + Send + Sync + 'static,
+    ) -> Result<GeofenceCollectionHandle> {
+        self.register_with_options(&LocationOptions::default(), on_event)
+    }
+
+    /// Like [`register`](Self::register), but configured by `options`
+    /// instead of [`LocationOptions::default`].
+    ///
+    /// The options apply to the location watch checked against the regions,
+    /// so they are ignored on iOS and macOS, which monitor the regions
+    /// themselves.
+    pub fn register_with_options(
+        self,
+        options: &LocationOptions,
+        on_event: impl Fn(GeofenceEvent) + Send + Sync + 'static,
+    ) -> Result<GeofenceCollectionHandle> {
+        let callback: GeofenceCallback = Arc::new(move |result| {
+            if let Ok(event) = result {
+                on_event(event);
+            }
+        });
+        Ok(GeofenceCollectionHandle {
+            _monitor: start(options, self.0, callback)?,
+        })
+    }
+}
+
+impl From<Vec<GeofenceRegion>> for GeofenceCollection {
+    fn from(regions: Vec<GeofenceRegion>) -> Self {
+        Self(regions)
+    }
+}
+
+/// Handle to a registered [`GeofenceCollection`]. Dropping it removes all of
+/// its regions.
+#[must_use = "geofence monitoring stops when the handle is dropped"]
+pub struct GeofenceCollectionHandle {
+    _monitor: Monitor,
+}
+
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+fn start(
+    _options: &LocationOptions,
+    regions: Vec<GeofenceRegion>,
+    callback: GeofenceCallback,
+) -> Result<Monitor> {
+    crate::darwin::monitor_geofences(&regions, callback)
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+fn start(
+    options: &LocationOptions,
+    regions: Vec<GeofenceRegion>,
+    callback: GeofenceCallback,
+) -> Result<Monitor> {
+    let tracker = Mutex::new(GeofenceTracker::new(regions));
+    let on_location: LocationCallback = Arc::new(move |result: Result<Location>| match result {
+        Ok(location) => {
+            let events = tracker
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .update(&location);
+            for event in events {
+                callback(Ok(event));
+            }
+        }
+        Err(error) => callback(Err(error)),
+    });
+
+    WatchHandle::try_new(options, (), on_location)
+}
+
+/// Inside/outside state of each region, updated from location fixes
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+struct GeofenceTracker {
+    regions: Vec<TrackedRegion>,
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+struct TrackedRegion {
+    region: GeofenceRegion,
+    /// When the device entered the region, if it is inside
+    entered_at: Option<SystemTime>,
+    /// Whether `Dwell` was reported since entering
+    dwelled: bool,
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+impl GeofenceTracker {
+    fn new(regions: Vec<GeofenceRegion>) -> Self {
+        let regions = regions
+            .into_iter()
+            .map(|region| TrackedRegion {
+                region,
+                entered_at: None,
+                dwelled: false,
+            })
+            .collect();
+        Self { regions }
+    }
+
+    /// Events caused by a new fix
+    ///
+    /// Regions start out as outside, so a first fix inside a region reports
+    /// `Enter`.
+    fn update(&mut self, location: &Location) -> Vec<GeofenceEvent> {
+        let position = location.coordinates();
+        let time = location.timestamp.unwrap_or_else(now);
+        let mut events = Vec::new();
+
+        for tracked in &mut self.regions {
+            let inside = geo_math::distance_meters(position, tracked.region.center)
+                <= tracked.region.radius_m;
+            let kind = match (inside, tracked.entered_at) {
+                (true, None) => {
+                    tracked.entered_at = Some(time);
+                    tracked.dwelled = false;
+                    GeofenceEventKind::Enter
+                }
+                (true, Some(entered_at)) => {
+                    let stayed = time.duration_since(entered_at).unwrap_or_default();
+                    if tracked.dwelled || stayed < DWELL_TIME {
+                        continue;
+                    }
+                    tracked.dwelled = true;
+                    GeofenceEventKind::Dwell
+                }
+                (false, Some(_)) => {
+                    tracked.entered_at = None;
+                    GeofenceEventKind::Exit
+                }
+                (false, None) => continue,
+            };
+            events.push(GeofenceEvent {
+                region_id: tracked.region.id.clone(),
+                kind,
+            });
+        }
+
+        events
+    }
+}
+
+#[cfg(all(test, not(any(target_os = "ios", target_os = "macos"))))]
+mod tests {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+
+    const DEPOT: Coordinates = Coordinates {
+        latitude: 52.52,
+        longitude: 13.405,
+    };
+
+    fn tracker() -> GeofenceTracker {
+        GeofenceTracker::new(vec![GeofenceRegion {
+            id: "depot".to_string(),
+            center: DEPOT,
+            radius_m: 200.0,
+        }])
+    }
+
+    /// A fix `meters_north` of the depot, `seconds` after the epoch
+    fn fix(meters_north: f64, seconds: u64) -> Location {
+        Location {
+            timestamp: Some(UNIX_EPOCH + Duration::from_secs(seconds)),
+            ..Location::from(geo_math::destination_point(DEPOT, 0.0, meters_north))
+        }
+    }
+
+    fn kinds(events: Vec<GeofenceEvent>) -> Vec<GeofenceEventKind> {
+        events
+            .into_iter()
+            .map(|event| {
+                assert_eq!(event.region_id, "depot");
+                event.kind
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reports_enter_and_exit_once() {
+        let mut tracker = tracker();
+        assert_eq!(kinds(tracker.update(&fix(500.0, 0))), []);
+        assert_eq!(
+            kinds(tracker.update(&fix(100.0, 10))),
+            [GeofenceEventKind::Enter]
+        );
+        assert_eq!(kinds(tracker.update(&fix(50.0, 20))), []);
+        assert_eq!(
+            kinds(tracker.update(&fix(300.0, 30))),
+            [GeofenceEventKind::Exit]
+        );
+        assert_eq!(kinds(tracker.update(&fix(400.0, 40))), []);
+    }
+
+    #[test]
+    fn first_fix_inside_reports_enter() {
+        let mut tracker = tracker();
+        assert_eq!(
+            kinds(tracker.update(&fix(0.0, 0))),
+            [GeofenceEventKind::Enter]
+        );
+    }
+
+    #[test]
+    fn reports_dwell_once_after_the_dwell_time() {
+        let mut tracker = tracker();
+        let dwell = DWELL_TIME.as_secs();
+        tracker.update(&fix(0.0, 0));
+        assert_eq!(kinds(tracker.update(&fix(10.0, dwell - 1))), []);
+        assert_eq!(
+            kinds(tracker.update(&fix(10.0, dwell))),
+            [GeofenceEventKind::Dwell]
+        );
+        assert_eq!(kinds(tracker.update(&fix(10.0, 2 * dwell))), []);
+    }
+
+    #[test]
+    fn re_entry_restarts_the_dwell_time() {
+        let mut tracker = tracker();
+        let dwell = DWELL_TIME.as_secs();
+        tracker.update(&fix(0.0, 0));
+        tracker.update(&fix(0.0, dwell));
+        assert_eq!(
+            kinds(tracker.update(&fix(500.0, dwell + 10))),
+            [GeofenceEventKind::Exit]
+        );
+        assert_eq!(
+            kinds(tracker.update(&fix(0.0, dwell + 20))),
+            [GeofenceEventKind::Enter]
+        );
+        assert_eq!(kinds(tracker.update(&fix(0.0, 2 * dwell))), []);
+        assert_eq!(
+            kinds(tracker.update(&fix(0.0, 2 * dwell + 20))),
+            [GeofenceEventKind::Dwell]
+        );
+    }
+}
@@ -0,0 +1,148 @@
+//! Geohash encoding
+//!
+//! Geohashes interleave the bits of longitude and latitude bisections and
+//! write them in a base-32 alphabet, so every extra character narrows the
+//! cell. Nearby points usually share a prefix, which databases such as
+//! Elasticsearch, MongoDB and PostGIS use for proximity queries.
+
+use crate::geo_math::distance_meters;
+use crate::Coordinates;
+use std::fmt;
+
+/// Longest supported geohash, with cells of roughly 4cm × 2cm.
+const MAX_PRECISION: usize = 12;
+
+/// The standard geohash alphabet, which leaves out `a`, `i`, `l` and `o`
+const BASE32: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// Why a string couldn't be decoded by
+/// [`Coordinates::from_geohash`](crate::Coordinates::from_geohash).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeohashError {
+    /// The geohash is empty.
+    EmptyString,
+    /// The geohash has more than 12 characters.
+    TooLong,
+    /// The geohash contains a character outside the base-32 alphabet.
+    InvalidCharacter(char),
+}
+
+impl fmt::Display for GeohashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeohashError::EmptyString => write!(f, "Empty geohash"),
+            GeohashError::TooLong => {
+                write!(f, "Geohash is longer than {MAX_PRECISION} characters")
+            }
+            GeohashError::InvalidCharacter(c) => write!(f, "Invalid geohash character {c:?}"),
+        }
+    }
+}
+
+impl std::error::Error for GeohashError {}
+
+impl Coordinates {
+    /// Encode these coordinates as a geohash of `precision` characters.
+    ///
+    /// `precision` is clamped to 1-12; five characters give cells of about
+    /// 5km × 5km, nine characters about 5m × 5m.
+    ///
+    /// ```rust
+    /// use dioxus_mobile_geolocation::Coordinates;
+    ///
+    /// let aalborg = Coordinates::new(57.64911, 10.40744);
+    /// assert_eq!(aalborg.to_geohash(11), "u4pruydqqvj");
+    /// ```
+    pub fn to_geohash(&self, precision: u8) -> String {
+        let precision = usize::from(precision).clamp(1, MAX_PRECISION);
+        let mut latitude = (-90.0, 90.0);
+        let mut longitude = (-180.0, 180.0);
+        let mut is_longitude = true;
+
+        let mut hash = String::with_capacity(precision);
+        for _ in 0..precision {
+            let mut index = 0;
+            for _ in 0..5 {
+                let (range, value) = if is_longitude {
+                    (&mut longitude, self.longitude)
+                } else {
+                    (&mut latitude, self.latitude)
+                };
+                let mid = (range.0 + range.1) / 2.0;
+                index <<= 1;
+                if value >= mid {
+                    index |= 1;
+                    range.0 = mid;
+                } else {
+                    range.1 = mid;
+                }
+                is_longitude = !is_longitude;
+            }
+            hash.push(char::from(BASE32[index]));
+        }
+        hash
+    }
+
+    /// Decode a geohash, returning the center of its cell and the distance
+    /// in meters from the center to the cell's corners.
+    ///
+    /// Every point inside the cell is within that distance of the returned
+    /// coordinates. Upper-case geohashes are accepted.
+    ///
+    /// ```rust
+    /// use dioxus_mobile_geolocation::Coordinates;
+    ///
+    /// let (center, error_m) = Coordinates::from_geohash("ezs42").unwrap();
+    /// assert!((center.latitude - 42.605).abs() < 0.001);
+    /// assert!((center.longitude + 5.603).abs() < 0.001);
+    /// assert!(error_m < 5_000.0);
+    /// ```
+    pub fn from_geohash(hash: &str) -> Result<(Coordinates, f64), GeohashError> {
+        if hash.is_empty() {
+            return Err(GeohashError::EmptyString);
+        }
+        if hash.chars().count() > MAX_PRECISION {
+            return Err(GeohashError::TooLong);
+        }
+
+        let mut latitude = (-90.0, 90.0);
+        let mut longitude = (-180.0, 180.0);
+        let mut is_longitude = true;
+
+        for c in hash.chars() {
+            let index = BASE32
+                .iter()
+                .position(|&digit| char::from(digit) == c.to_ascii_lowercase())
+                .ok_or(GeohashError::InvalidCharacter(c))?;
+            for bit in (0..5).rev() {
+                let range = if is_longitude {
+                    &mut longitude
+                } else {
+                    &mut latitude
+                };
+                let mid = (range.0 + range.1) / 2.0;
+                if index & (1 << bit) != 0 {
+                    range.0 = mid;
+                } else {
+                    range.1 = mid;
+                }
+                is_longitude = !is_longitude;
+            }
+        }
+
+        let center = Coordinates {
+            latitude: (latitude.0 + latitude.1) / 2.0,
+            longitude: (longitude.0 + longitude.1) / 2.0,
+        };
+        // The corner nearer the equator is the farthest from the center
+        let corner = Coordinates {
+            latitude: if center.latitude >= 0.0 {
+                latitude.0
+            } else {
+                latitude.1
+            },
+            longitude: longitude.1,
+        };
+        Ok((center, distance_meters(center, corner)))
+    }
+}
@@ -0,0 +1,116 @@
+//! GeoJSON features for map libraries
+//!
+//! Mapbox, Leaflet, deck.gl and most other web map libraries take GeoJSON.
+//! A fix becomes a `Point` feature whose position is `[longitude, latitude]`
+//! or `[longitude, latitude, altitude]`, the reverse of the latitude-first
+//! order used elsewhere in this crate.
+
+use crate::timestamp::{from_millis, to_millis};
+use crate::{Error, Location, LocationHistory, Result};
+use serde_json::{json, Map, Value};
+
+impl Location {
+    /// This fix as a GeoJSON `Point` feature.
+    ///
+    /// The properties hold the fields other than the position, named as in
+    /// the browser's `GeolocationCoordinates`: `accuracy`,
+    /// `altitudeAccuracy`, `speed` and `heading`, plus `timestamp` in
+    /// milliseconds since the Unix epoch. Fields without a value are left
+    /// out.
+    ///
+    /// ```rust
+    /// use dioxus_mobile_geolocation::{Coordinates, Location};
+    ///
+    /// let fix = Location {
+    ///     horizontal_accuracy: Some(5.0),
+    ///     ..Location::from(Coordinates::new(52.52, 13.405))
+    /// };
+    /// let feature = fix.to_geojson_feature();
+    /// assert_eq!(feature["geometry"]["coordinates"], serde_json::json!([13.405, 52.52]));
+    /// assert_eq!(feature["properties"]["accuracy"], 5.0);
+    /// ```
+    pub fn to_geojson_feature(&self) -> Value {
+        let mut position = vec![self.longitude, self.latitude];
+        position.extend(self.altitude);
+
+        let mut properties = Map::new();
+        let fields = [
+            ("accuracy", self.horizontal_accuracy),
+            ("altitudeAccuracy", self.vertical_accuracy),
+            ("speed", self.speed),
+            ("heading", self.course),
+        ];
+        for (name, value) in fields {
+            if let Some(value) = value {
+                properties.insert(name.into(), value.into());
+            }
+        }
+        if let Some(timestamp) = self.timestamp {
+            properties.insert("timestamp".into(), to_millis(timestamp).into());
+        }
+
+        json!({
+            "type": "Feature",
+            "geometry": { "type": "Point", "coordinates": position },
+            "properties": properties,
+        })
+    }
+
+    /// Read a fix from a GeoJSON `Point` feature, the inverse of
+    /// [`to_geojson_feature`](Self::to_geojson_feature).
+    ///
+    /// Properties that are missing, `null` or of the wrong type are left
+    /// as `None`; other properties are ignored.
+    ///
+    /// ## Errors
+    ///
+    /// [`Error::InvalidCoordinates`] if `v` isn't a feature with a `Point`
+    /// geometry, or the position is incomplete or out of range.
+    pub fn from_geojson_feature(v: &Value) -> Result<Location> {
+        if v["type"] != "Feature" || v["geometry"]["type"] != "Point" {
+            return Err(Error::InvalidCoordinates);
+        }
+        let position: Vec<f64> = v["geometry"]["coordinates"]
+            .as_array()
+            .ok_or(Error::InvalidCoordinates)?
+            .iter()
+            .map(Value::as_f64)
+            .collect::<Option<_>>()
+            .ok_or(Error::InvalidCoordinates)?;
+        let (longitude, latitude, altitude) = match position[..] {
+            [longitude, latitude] => (longitude, latitude, None),
+            [longitude, latitude, altitude, ..] => (longitude, latitude, Some(altitude)),
+            _ => return Err(Error::InvalidCoordinates),
+        };
+        if !(-180.0..=180.0).contains(&longitude) || !(-90.0..=90.0).contains(&latitude) {
+            return Err(Error::InvalidCoordinates);
+        }
+
+        let properties = &v["properties"];
+        let property = |name: &str| properties[name].as_f64();
+        Ok(Location {
+            latitude,
+            longitude,
+            altitude,
+            horizontal_accuracy: property("accuracy"),
+            vertical_accuracy: property("altitudeAccuracy"),
+            speed: property("speed"),
+            course: property("heading"),
+            // A timestamp out of range counts as missing
+            timestamp: properties["timestamp"].as_i64().and_then(from_millis),
+        })
+    }
+}
+
+impl LocationHistory {
+    /// The track as a GeoJSON `FeatureCollection` with one `Point` feature
+    /// per fix, oldest first.
+    ///
+    /// See [`Location::to_geojson_feature`] for the layout of each feature.
+    pub fn to_geojson_feature_collection(&self) -> Value {
+        json!({
+            "type": "FeatureCollection",
+            "features": self.iter().map(Location::to_geojson_feature).collect::<Vec<_>>(),
+        })
+    }
+}
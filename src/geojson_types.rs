@@ -0,0 +1,36 @@
+//! GeoJSON interop
+//!
+//! GeoJSON positions are `[longitude, latitude]`, the reverse of the
+//! latitude-first order used by [`Coordinates::new`].
+
+use crate::{Coordinates, Error};
+
+impl From<Coordinates> for geojson::Value {
+    /// Convert to a GeoJSON `Point`.
+    fn from(coords: Coordinates) -> Self {
+        geojson::Value::Point(vec![coords.longitude, coords.latitude])
+    }
+}
+
+impl TryFrom<geojson::Value> for Coordinates {
+    type Error = Error;
+
+    /// Convert a GeoJSON `Point` to coordinates, ignoring any altitude.
+    ///
+    /// Fails with [`Error::InvalidCoordinates`] for other geometry types and
+    /// for positions that are missing a component, aren't finite or have a
+    /// latitude outside `[-90, 90]`.
+    fn try_from(value: geojson::Value) -> Result<Self, Self::Error> {
+        let geojson::Value::Point(position) = value else {
+            return Err(Error::InvalidCoordinates);
+        };
+        match position[..] {
+            [longitude, latitude, ..]
+                if longitude.is_finite() && latitude.is_finite() && latitude.abs() <= 90.0 =>
+            {
+                Ok(Coordinates::new(latitude, longitude))
+            }
+            _ => Err(Error::InvalidCoordinates),
+        }
+    }
+}
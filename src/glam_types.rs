@@ -0,0 +1,30 @@
+//! glam interop
+//!
+//! Vectors use the GeoJSON axis order: `x` is longitude and `y` is latitude,
+//! the reverse of the latitude-first order used by [`Coordinates::new`].
+//! Mixing the two orders is the most common source of points that end up in
+//! the wrong hemisphere or off the map.
+
+use crate::Coordinates;
+use glam::DVec2;
+
+impl From<Coordinates> for DVec2 {
+    /// Convert to a vector with `x = longitude` and `y = latitude`.
+    ///
+    /// Note the order: `DVec2::from(Coordinates::new(lat, lon))` is
+    /// `DVec2::new(lon, lat)`.
+    fn from(coords: Coordinates) -> Self {
+        DVec2::new(coords.longitude, coords.latitude)
+    }
+}
+
+impl From<DVec2> for Coordinates {
+    /// Convert a vector with `x = longitude` and `y = latitude`, normalizing
+    /// as [`Coordinates::new`] does.
+    ///
+    /// Note the order: `Coordinates::from(DVec2::new(lon, lat))` is
+    /// `Coordinates::new(lat, lon)`.
+    fn from(vector: DVec2) -> Self {
+        Coordinates::new(vector.y, vector.x)
+    }
+}
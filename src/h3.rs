@@ -0,0 +1,60 @@
+//! Uber H3 hexagonal indexing
+//!
+//! Thin wrapper around the pure-Rust [`h3o`] implementation so that
+//! [`Coordinates`] can be aggregated into H3 cells. Indexes are plain `u64`
+//! values, identical to the ones produced by the reference C library.
+
+use crate::Coordinates;
+use h3o::{CellIndex, LatLng, Resolution};
+
+/// The H3 null index, returned when an index cannot be computed.
+pub const H3_NULL: u64 = 0;
+
+impl Coordinates {
+    /// Get the H3 index of the cell containing these coordinates at
+    /// `resolution` (0-15).
+    ///
+    /// Returns [`H3_NULL`] if the coordinates are not finite or the
+    /// resolution is out of range.
+    pub fn to_h3_index(&self, resolution: u8) -> u64 {
+        let Ok(resolution) = Resolution::try_from(resolution) else {
+            return H3_NULL;
+        };
+        match LatLng::new(self.latitude, self.longitude) {
+            Ok(point) => point.to_cell(resolution).into(),
+            Err(_) => H3_NULL,
+        }
+    }
+}
+
+/// Get the centre of the H3 cell identified by `index`.
+///
+/// Returns `None` if `index` is not a valid H3 cell index.
+pub fn h3_index_to_coordinates(index: u64) -> Option<Coordinates> {
+    let cell = CellIndex::try_from(index).ok()?;
+    let center = LatLng::from(cell);
+    Some(Coordinates {
+        latitude: center.lat(),
+        longitude: center.lng(),
+    })
+}
+
+/// Get the cells directly adjacent to the H3 cell identified by `index`.
+///
+/// Pentagon cells only have five neighbors, in which case the last entry is
+/// [`H3_NULL`]. An invalid `index` yields all [`H3_NULL`].
+pub fn h3_neighbors(index: u64) -> [u64; 6] {
+    let mut neighbors = [H3_NULL; 6];
+    let Ok(cell) = CellIndex::try_from(index) else {
+        return neighbors;
+    };
+
+    let ring = cell
+        .grid_disk::<Vec<_>>(1)
+        .into_iter()
+        .filter(|neighbor| *neighbor != cell);
+    for (slot, neighbor) in neighbors.iter_mut().zip(ring) {
+        *slot = neighbor.into();
+    }
+    neighbors
+}
@@ -0,0 +1,146 @@
+//! Compass heading updates
+//!
+//! Like location watches, every platform delivers headings through
+//! callbacks (`platform::watch_heading`) that this module turns into a
+//! stream.
+
+use crate::Result;
+use futures_channel::mpsc;
+use futures_core::Stream;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::SystemTime;
+
+#[cfg(target_os = "android")]
+use crate::android as platform;
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+use crate::darwin as platform;
+#[cfg(target_arch = "wasm32")]
+use crate::web as platform;
+
+/// Callback invoked by a platform heading watch for each reading or error.
+pub(crate) type HeadingCallback = Arc<dyn Fn(Result<Heading>) + Send + Sync>;
+
+/// A running platform heading watch. Updates stop when it is dropped.
+pub(crate) type HeadingWatch = platform::HeadingWatch;
+
+/// Start heading updates, calling `callback` for each reading or error.
+pub(crate) fn watch(callback: HeadingCallback) -> Result<HeadingWatch> {
+    platform::watch_heading(callback)
+}
+
+/// A compass reading.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Heading {
+    /// Direction the device points in, in degrees clockwise from magnetic
+    /// north.
+    pub magnetic_heading: f64,
+    /// Direction the device points in, in degrees clockwise from true north.
+    ///
+    /// Only iOS and macOS report it, and only while the location is known.
+    pub true_heading: Option<f64>,
+    /// Maximum deviation of the heading in degrees, or a negative value if
+    /// the platform doesn't know it.
+    pub accuracy: f64,
+    /// When the reading was taken.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_millis"))]
+    pub timestamp: SystemTime,
+}
+
+impl Heading {
+    /// Whether the headings are finite and non-negative and the accuracy is
+    /// finite.
+    ///
+    /// CoreLocation reports `-1` for headings it couldn't determine. A
+    /// negative [`accuracy`](Self::accuracy) only means the accuracy is
+    /// unknown, so such readings are still valid.
+    ///
+    /// ```rust
+    /// use dioxus_mobile_geolocation::Heading;
+    /// use std::time::SystemTime;
+    ///
+    /// let heading = Heading {
+    ///     magnetic_heading: 90.0,
+    ///     true_heading: None,
+    ///     accuracy: -1.0,
+    ///     timestamp: SystemTime::now(),
+    /// };
+    /// assert!(heading.is_valid());
+    /// assert!(!Heading { magnetic_heading: -1.0, ..heading }.is_valid());
+    /// ```
+    pub fn is_valid(&self) -> bool {
+        let valid = |value: f64| value.is_finite() && value >= 0.0;
+        valid(self.magnetic_heading)
+            && self.true_heading.is_none_or(valid)
+            && (self.accuracy.is_finite() || self.accuracy < 0.0)
+    }
+}
+
+/// Stream of compass headings returned by
+/// [`watch_heading`](crate::watch_heading).
+///
+/// Yields a `Result<Heading>` for every reading or error. If updates can't
+/// be started at all, the stream yields that error and ends. Dropping the
+/// stream stops the updates.
+#[must_use = "heading updates stop when the stream is dropped"]
+pub struct HeadingStream {
+    receiver: mpsc::UnboundedReceiver<Result<Heading>>,
+    _watch: Option<HeadingWatch>,
+}
+
+impl HeadingStream {
+    pub(crate) fn new() -> Self {
+        let (sender, receiver) = mpsc::unbounded();
+        let callback: HeadingCallback = {
+            let sender = sender.clone();
+            Arc::new(move |result| {
+                let _ = sender.unbounded_send(result);
+            })
+        };
+
+        let watch = match watch(callback) {
+            Ok(watch) => Some(watch),
+            Err(error) => {
+                let _ = sender.unbounded_send(Err(error));
+                None
+            }
+        };
+
+        // Only the watch's callback keeps the channel open, so the stream
+        // ends right after the error if the watch couldn't start.
+        drop(sender);
+
+        Self {
+            receiver,
+            _watch: watch,
+        }
+    }
+}
+
+impl Stream for HeadingStream {
+    type Item = Result<Heading>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+/// Platforms without a compass API
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "ios",
+    target_os = "macos",
+    target_arch = "wasm32"
+)))]
+mod platform {
+    use super::HeadingCallback;
+    use crate::{Error, Result};
+
+    pub struct HeadingWatch;
+
+    pub fn watch_heading(_callback: HeadingCallback) -> Result<HeadingWatch> {
+        Err(Error::PermanentlyUnavailable)
+    }
+}
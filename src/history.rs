@@ -0,0 +1,151 @@
+//! Recorded location history
+//!
+//! A bounded buffer of fixes with statistics for the recorded track. It
+//! doesn't use any platform API, so it works the same on every target.
+
+use crate::geo_math::{distance_meters, CoordinateBounds};
+use crate::timestamp::format_timestamp;
+use crate::{Coordinates, Location};
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// The most recent `capacity` fixes of a track, oldest first.
+///
+/// Once full, every [`push`](Self::push) drops the oldest fix.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::{Location, LocationHistory};
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let fix = |latitude: f64, seconds: u64| Location {
+///     latitude,
+///     longitude: 13.405,
+///     altitude: None,
+///     horizontal_accuracy: None,
+///     vertical_accuracy: None,
+///     speed: None,
+///     course: None,
+///     timestamp: Some(UNIX_EPOCH + Duration::from_secs(seconds)),
+/// };
+///
+/// let mut history = LocationHistory::new(100);
+/// history.push(fix(52.520, 0));
+/// history.push(fix(52.521, 60));
+/// assert_eq!(history.duration(), Some(Duration::from_secs(60)));
+/// assert!((history.total_distance_m() - 111.2).abs() < 0.1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LocationHistory {
+    capacity: usize,
+    fixes: VecDeque<Location>,
+}
+
+impl LocationHistory {
+    /// Create an empty history keeping at most `capacity` fixes.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            fixes: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Append `loc`, dropping the oldest fix if the history is full.
+    ///
+    /// A history with a capacity of zero stays empty.
+    pub fn push(&mut self, loc: Location) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.fixes.len() == self.capacity {
+            self.fixes.pop_front();
+        }
+        self.fixes.push_back(loc);
+    }
+
+    /// Maximum number of fixes kept.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of fixes currently kept.
+    pub fn len(&self) -> usize {
+        self.fixes.len()
+    }
+
+    /// Whether no fix has been kept.
+    pub fn is_empty(&self) -> bool {
+        self.fixes.is_empty()
+    }
+
+    /// The kept fixes, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &Location> {
+        self.fixes.iter()
+    }
+
+    /// Length of the track in meters, summing the Haversine distances
+    /// between successive fixes.
+    pub fn total_distance_m(&self) -> f64 {
+        self.fixes
+            .iter()
+            .zip(self.fixes.iter().skip(1))
+            .map(|(a, b)| distance_meters(a.coordinates(), b.coordinates()))
+            .sum()
+    }
+
+    /// Time between the first and the last fix.
+    ///
+    /// Returns `None` if the history is empty, either fix has no timestamp,
+    /// or the last fix is older than the first.
+    pub fn duration(&self) -> Option<Duration> {
+        let first = self.fixes.front()?.timestamp?;
+        let last = self.fixes.back()?.timestamp?;
+        last.duration_since(first).ok()
+    }
+
+    /// Average speed over the track in meters per second.
+    ///
+    /// Returns `None` if [`duration`](Self::duration) is unknown or zero.
+    pub fn average_speed_mps(&self) -> Option<f64> {
+        let seconds = self.duration()?.as_secs_f64();
+        (seconds > 0.0).then(|| self.total_distance_m() / seconds)
+    }
+
+    /// Smallest bounding box containing every fix, or `None` if the history
+    /// is empty.
+    pub fn bounding_box(&self) -> Option<CoordinateBounds> {
+        let points: Vec<Coordinates> = self.fixes.iter().map(Location::coordinates).collect();
+        CoordinateBounds::from_coordinates(&points)
+    }
+
+    /// The track as a GPX 1.1 document with a single track segment.
+    ///
+    /// Each fix becomes a `<trkpt>`, with `<ele>` and `<time>` when the fix
+    /// has an altitude and a timestamp.
+    pub fn export_gpx(&self) -> String {
+        let mut gpx = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<gpx version=\"1.1\" creator=\"",
+            env!("CARGO_PKG_NAME"),
+            "\" xmlns=\"http://www.topografix.com/GPX/1/1\">\n",
+            "  <trk>\n",
+            "    <trkseg>\n",
+        ));
+        for fix in &self.fixes {
+            let _ = write!(
+                gpx,
+                "      <trkpt lat=\"{}\" lon=\"{}\">",
+                fix.latitude, fix.longitude
+            );
+            if let Some(altitude) = fix.altitude {
+                let _ = write!(gpx, "<ele>{altitude}</ele>");
+            }
+            if let Some(timestamp) = fix.timestamp {
+                let _ = write!(gpx, "<time>{}</time>", format_timestamp(timestamp));
+            }
+            gpx.push_str("</trkpt>\n");
+        }
+        gpx.push_str("    </trkseg>\n  </trk>\n</gpx>\n");
+        gpx
+    }
+}
@@ -0,0 +1,61 @@
+//! Building-level indoor positioning
+//!
+//! Indoor positioning systems report a floor and X/Y offsets in meters from
+//! a reference point of the building rather than latitude and longitude.
+
+use crate::geo_math::destination_point;
+use crate::Coordinates;
+
+/// Position inside a building in its local coordinate system.
+///
+/// `x_meters` points along the building's X axis and `y_meters` along its
+/// Y axis, both measured from the building origin passed to [`to_wgs84`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IndoorCoordinate {
+    /// Floor number, with `0` as the ground floor.
+    pub floor: i32,
+    /// Offset along the building's X axis in meters.
+    pub x_meters: f64,
+    /// Offset along the building's Y axis in meters.
+    pub y_meters: f64,
+    /// Identifier of the building the coordinate belongs to.
+    pub building_id: String,
+}
+
+/// Transform a building-local coordinate to WGS-84.
+///
+/// `building_origin` is where the building's X/Y axes start and
+/// `building_bearing` is the direction of its Y axis in degrees clockwise
+/// from north; the X axis points 90° clockwise from it. A bearing of `0.0`
+/// means X points east and Y points north. The floor doesn't affect the
+/// result.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::{to_wgs84, Coordinates, IndoorCoordinate};
+///
+/// let origin = Coordinates::new(51.5, -0.12);
+/// let indoor = IndoorCoordinate {
+///     floor: 2,
+///     x_meters: 0.0,
+///     y_meters: 100.0,
+///     building_id: "hq".to_string(),
+/// };
+///
+/// let position = to_wgs84(indoor, origin, 0.0);
+/// assert!(position.latitude > origin.latitude);
+/// assert!((position.longitude - origin.longitude).abs() < 1e-9);
+/// ```
+pub fn to_wgs84(
+    indoor: IndoorCoordinate,
+    building_origin: Coordinates,
+    building_bearing: f64,
+) -> Coordinates {
+    let distance = indoor.x_meters.hypot(indoor.y_meters);
+    if distance == 0.0 {
+        return building_origin;
+    }
+
+    let bearing = building_bearing + indoor.x_meters.atan2(indoor.y_meters).to_degrees();
+    destination_point(building_origin, bearing, distance)
+}
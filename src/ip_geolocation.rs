@@ -0,0 +1,84 @@
+//! Location estimates from the device's public IP address
+//!
+//! A last resort when neither satellite nor network positioning is
+//! available, e.g. on desktops without a location service. The estimate is
+//! usually the location of the internet provider's nearest point of
+//! presence, so it can be off by tens of kilometers or more.
+
+use crate::{Coordinates, Error, Result};
+use serde_json::Value;
+
+/// Typical accuracy in meters of an IP-based location estimate.
+///
+/// `Coordinates` carry no accuracy, so treat results of
+/// [`estimate_location_from_ip`] as at best this accurate.
+pub const IP_LOCATION_ACCURACY_METERS: f64 = 50_000.0;
+
+/// Estimate the device's location from its public IP address.
+///
+/// Sends a GET request to `api_url` and reads the coordinates from the JSON
+/// response. The field names of common services are understood:
+///
+/// - `lat`/`lon`, as returned by `http://ip-api.com/json`
+/// - `latitude`/`longitude`, as returned by `https://ipapi.co/json`
+/// - `loc` as a `"lat,lon"` string, as returned by `https://ipinfo.io/json`
+///
+/// The result is only accurate to around [`IP_LOCATION_ACCURACY_METERS`].
+///
+/// ## Privacy
+///
+/// This sends the device's IP address to the service at `api_url`, which
+/// can log it together with the estimated location, without the user
+/// granting location permission. Ask for consent first, prefer a service
+/// you operate, and only use this when the platform location is
+/// unavailable.
+///
+/// ## Errors
+///
+/// - [`Error::Network`] if the service can't be reached, responds with an
+///   error status, or returns something other than JSON
+/// - [`Error::Unknown`] if the response has no coordinates, e.g. for a
+///   private address
+/// - [`Error::InvalidCoordinates`] if the coordinates are out of range
+pub async fn estimate_location_from_ip(api_url: &str) -> Result<Coordinates> {
+    // Browsers ignore this header and send their own
+    let response: Value = reqwest::Client::new()
+        .get(api_url)
+        .header(reqwest::header::USER_AGENT, USER_AGENT)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|_| Error::Network)?
+        .json()
+        .await
+        .map_err(|_| Error::Network)?;
+
+    let (latitude, longitude) = coordinates_from_response(&response).ok_or(Error::Unknown)?;
+    if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+        return Err(Error::InvalidCoordinates);
+    }
+    Ok(Coordinates::new(latitude, longitude))
+}
+
+/// Read the latitude and longitude from any of the supported field layouts
+fn coordinates_from_response(response: &Value) -> Option<(f64, f64)> {
+    let pair = |latitude: &str, longitude: &str| {
+        Some((
+            response.get(latitude)?.as_f64()?,
+            response.get(longitude)?.as_f64()?,
+        ))
+    };
+    let loc = || {
+        let (latitude, longitude) = response.get("loc")?.as_str()?.split_once(',')?;
+        Some((
+            latitude.trim().parse().ok()?,
+            longitude.trim().parse().ok()?,
+        ))
+    };
+
+    pair("lat", "lon")
+        .or_else(|| pair("latitude", "longitude"))
+        .or_else(loc)
+}
+
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
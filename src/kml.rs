@@ -0,0 +1,66 @@
+//! KML export
+//!
+//! Produces KML snippets for Google Earth and other GIS tools. The output is
+//! a `<Placemark>` element; wrap it in a `<kml><Document>` to get a complete
+//! file.
+
+use crate::timestamp::format_timestamp;
+use crate::Coordinates;
+use std::time::SystemTime;
+
+impl Coordinates {
+    /// KML `<Placemark>` with a `<Point>` at these coordinates.
+    ///
+    /// `name` and `description` are XML-escaped.
+    pub fn to_kml_placemark(&self, name: &str, description: &str) -> String {
+        format!(
+            "<Placemark><name>{}</name><description>{}</description><Point><coordinates>{},{}</coordinates></Point></Placemark>",
+            escape(name),
+            escape(description),
+            self.longitude,
+            self.latitude
+        )
+    }
+}
+
+/// KML `<Placemark>` with a `<LineString>` through the points of `track`.
+///
+/// If the track has timestamps, the placemark gets a `<TimeSpan>` from the
+/// first to the last fix so Google Earth's time slider can show it.
+pub fn route_to_kml(track: &[(Coordinates, SystemTime)], name: &str) -> String {
+    let coordinates = track
+        .iter()
+        .map(|(point, _)| format!("{},{}", point.longitude, point.latitude))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let time_span = match (track.first(), track.last()) {
+        (Some((_, begin)), Some((_, end))) => format!(
+            "<TimeSpan><begin>{}</begin><end>{}</end></TimeSpan>",
+            format_timestamp(*begin),
+            format_timestamp(*end)
+        ),
+        _ => String::new(),
+    };
+
+    format!(
+        "<Placemark><name>{}</name>{time_span}<LineString><tessellate>1</tessellate><coordinates>{coordinates}</coordinates></LineString></Placemark>",
+        escape(name)
+    )
+}
+
+/// Escape the characters that are special in XML text.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
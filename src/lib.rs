@@ -12,6 +12,35 @@
 //! - `location-coarse`: Request coarse location permission (default)
 //! - `location-fine`: Request fine/precise location permission
 //! - `background-location`: Request background location access
+//! - `s2`: S2 geometry cell ID encoding and decoding
+//! - `h3`: Uber H3 hexagonal cell indexing
+//! - `geohash`: Geohash encoding and decoding
+//! - `solar`: Sun azimuth and elevation for a coordinate and time
+//! - `tile-math`: XYZ map tile helpers for prefetching tiles
+//! - `geocoding`: Reverse geocoding through a Nominatim server
+//! - `ip-geolocation`: Rough location estimates from the public IP address
+//! - `reporter`: Periodic location reports to an HTTP endpoint
+//! - `serde`: `Serialize`/`Deserialize` implementations for public types
+//! - `secure-storage`: Save the last location in the iOS/macOS Keychain
+//! - `geojson-types`: Conversions between `Coordinates` and `geojson::Value`
+//! - `geojson`: GeoJSON `Feature` export and import for `Location` and `LocationHistory`
+//! - `kml`: KML placemark export for Google Earth
+//! - `nalgebra`: Conversions between `Coordinates` and `nalgebra::Point2<f64>`
+//! - `glam`: Conversions between `Coordinates` and `glam::DVec2`
+//! - `activity-recognition`: Motion activity updates on Android and iOS
+//! - `barometer`: Relative altitude from the barometer on Android and iOS
+//! - `pedometer`: Step counting on Android and iOS, and walking speed estimates from step cadence
+//! - `indoor`: Building-local indoor coordinates and WGS-84 conversion, and
+//!   `LocationOptions::with_indoor_mapping` for indoor positioning
+//! - `clustering`: Centroids of point clusters
+//! - `base64-coords`: Base64-encoded binary coordinate arrays
+//! - `nmea`: NMEA 0183 sentence parsing for external GPS receivers
+//! - `geo-math`: Extra geometry in [`geo_math`] such as minimum enclosing circles
+//! - `dioxus`: `use_location` and `use_location_once` hooks for components
+//! - `windows`: Windows support through the WinRT Geolocation API
+//! - `linux`: Linux support through GeoClue2 over D-Bus
+//! - `mock`: `MockLocationProvider` for scripting locations in tests, and
+//!   `NoiseSimulator` for adding GPS-like noise to them
 //!
 //! ## Usage
 //!
@@ -75,20 +104,237 @@ mod darwin;
 #[cfg(target_arch = "wasm32")]
 mod web;
 
+// Windows uses the WinRT Geolocation API
+#[cfg(all(target_os = "windows", feature = "windows"))]
+mod windows;
+
+// Linux uses GeoClue2 over D-Bus
+#[cfg(all(target_os = "linux", feature = "linux"))]
+mod linux;
+
 // Re-export web-specific async API for proper usage on web
 #[cfg(target_arch = "wasm32")]
-pub use web::{get_current_position, get_current_position_sync};
+pub use web::{
+    get_current_position, get_current_position_sync, last_position_error, load_persisted_location,
+    persist_location, PositionOptionsExt,
+};
+
+// Keychain persistence of the last fix on Darwin platforms
+#[cfg(all(
+    feature = "secure-storage",
+    any(target_os = "ios", target_os = "macos")
+))]
+pub use darwin::{load_location_from_keychain, save_last_location_to_keychain};
 
 #[cfg(not(any(
     target_os = "android",
     target_os = "ios",
     target_os = "macos",
-    target_arch = "wasm32"
+    target_arch = "wasm32",
+    all(target_os = "windows", feature = "windows"),
+    all(target_os = "linux", feature = "linux")
 )))]
 mod unsupported;
 
+// Millisecond timestamps for serde
+#[cfg(feature = "serde")]
+mod serde_millis;
+
+// Futures and streams on top of the platform location callbacks
+mod watch;
+pub use watch::{LocationCoalescer, LocationDiff, LocationStream, WatchHandle};
+
+// Timeouts for single location requests
+#[cfg(not(target_arch = "wasm32"))]
+mod timeout;
+
+// Region enter/exit monitoring
+mod geofence;
+pub use geofence::{
+    GeofenceCollection, GeofenceCollectionHandle, GeofenceEvent, GeofenceEventKind, GeofenceRegion,
+    GeofenceStream,
+};
+
+// Compass heading updates
+mod heading;
+pub use heading::{Heading, HeadingStream};
+
+// GNSS satellite status
+mod satellites;
+pub use satellites::GpsSatellite;
+
+// Unified location event stream
+mod events;
+pub use events::{subscribe_events, EventHandle, LocationEvent};
+
+// Comparable snapshot of the latest location state
+mod observable;
+pub use observable::{location_observable, LocationObservable};
+
+// Permission, fix age and accuracy in one place
+mod monitor;
+pub use monitor::{global_monitor, LocationMonitor};
+
+// Distance, route and travel time math
+pub mod geo_math;
+
+// Conventional names for the common geodesic helpers
+pub mod geo;
+
+// Smoothing filters for noisy fixes
+mod filter;
+pub use filter::{ExponentialMovingAverage, KalmanFilter, LocationFilter, SmoothingFilter};
+
+// Activity guesses from speed
+mod activity;
+pub use activity::{detect_activity_from_speed, ActivityType};
+
+// Recorded tracks and their statistics
+mod history;
+pub use history::LocationHistory;
+
+// Degrees-minutes-seconds notation
+mod dms;
+pub use dms::ParseError;
+
+// Timestamp formats for the exporters
+mod timestamp;
+
+// S2 geometry cell IDs
+#[cfg(feature = "s2")]
+pub mod s2;
+
+#[cfg(feature = "s2")]
+pub use s2::s2_cell_to_coordinates;
+
+// Uber H3 hexagonal indexes
+#[cfg(feature = "h3")]
+pub mod h3;
+
+#[cfg(feature = "h3")]
+pub use h3::{h3_index_to_coordinates, h3_neighbors};
+
+// Geohash strings
+#[cfg(feature = "geohash")]
+mod geohash;
+
+#[cfg(feature = "geohash")]
+pub use geohash::GeohashError;
+
+// Solar position (NOAA algorithm)
+#[cfg(feature = "solar")]
+mod solar;
+
+// XYZ map tiles
+#[cfg(feature = "tile-math")]
+mod tiles;
+
+#[cfg(feature = "tile-math")]
+pub use tiles::{tile_coverage_for_polyline, tile_urls_for_bounds};
+
+// Reverse geocoding
+#[cfg(feature = "geocoding")]
+mod geocoding;
+
+#[cfg(feature = "geocoding")]
+pub use geocoding::approximate_address_from_coordinates;
+
+// IP-based location estimates
+#[cfg(feature = "ip-geolocation")]
+mod ip_geolocation;
+
+#[cfg(feature = "ip-geolocation")]
+pub use ip_geolocation::{estimate_location_from_ip, IP_LOCATION_ACCURACY_METERS};
+
+// Periodic location reports over HTTP
+#[cfg(feature = "reporter")]
+mod reporter;
+
+#[cfg(feature = "reporter")]
+pub use reporter::{LocationReporter, MAX_PENDING_REPORTS};
+
+// GeoJSON interop
+#[cfg(feature = "geojson-types")]
+mod geojson_types;
+
+// GeoJSON features for map libraries
+#[cfg(feature = "geojson")]
+mod geojson_feature;
+
+// KML export
+#[cfg(feature = "kml")]
+mod kml;
+
+#[cfg(feature = "kml")]
+pub use kml::route_to_kml;
+
+// nalgebra interop
+#[cfg(feature = "nalgebra")]
+mod nalgebra_types;
+
+// glam interop
+#[cfg(feature = "glam")]
+mod glam_types;
+
+// Step cadence speed estimates
+#[cfg(feature = "pedometer")]
+mod pedometer;
+
+#[cfg(feature = "pedometer")]
+pub use pedometer::{default_stride_length, walking_speed_estimate, PedometerUpdate};
+
+// Building-local indoor coordinates
+#[cfg(feature = "barometer")]
+mod barometer;
+
+#[cfg(feature = "indoor")]
+mod indoor;
+
+#[cfg(feature = "indoor")]
+pub use indoor::{to_wgs84, IndoorCoordinate};
+
+// Clusters of nearby points
+#[cfg(feature = "clustering")]
+mod clustering;
+
+#[cfg(feature = "clustering")]
+pub use clustering::cluster_centroids;
+
+// Base64 binary coordinate arrays
+#[cfg(feature = "base64-coords")]
+mod base64_coords;
+
+#[cfg(feature = "base64-coords")]
+pub use base64_coords::{decode_coordinates_from_base64, encode_coordinates_as_base64};
+
+// NMEA 0183 sentences from external receivers
+#[cfg(feature = "nmea")]
+pub mod nmea;
+
+// Dioxus hooks
+#[cfg(feature = "dioxus")]
+pub mod dioxus;
+
+// Scripted locations for tests
+#[cfg(feature = "mock")]
+mod mock;
+
+#[cfg(feature = "mock")]
+pub use mock::MockLocationProvider;
+
+// Simulated GPS noise for tests
+#[cfg(feature = "mock")]
+mod noise;
+
+#[cfg(feature = "mock")]
+pub use noise::NoiseSimulator;
+
 use permissions::{static_permission, Permission};
 use permissions_core::{LocationPrecision, PermissionBuilder};
+use std::sync::Arc;
+#[cfg(target_arch = "wasm32")]
+use std::time::UNIX_EPOCH;
+use std::time::{Duration, SystemTime};
 
 // Declare Java sources for Android using the macro system
 // This embeds absolute paths and generates linker symbols automatically
@@ -96,17 +342,35 @@ use permissions_core::{LocationPrecision, PermissionBuilder};
 dioxus_platform_bridge::android_plugin!(
     package = "dioxus.mobile.geolocation",
     plugin = "geolocation",
-    files = ["src/android/PermissionsHelper.java"]
+    files = [
+        "src/android/PermissionsHelper.java",
+        "src/android/LocationUpdates.java",
+        "src/android/HeadingUpdates.java",
+        "src/android/LifecycleUpdates.java",
+        "src/android/SatelliteUpdates.java",
+        "src/android/AltitudeUpdates.java",
+        "src/android/StepUpdates.java",
+        "src/android/ActivityUpdates.java"
+    ]
 );
 // Error types
 /// Result type for geolocation operations
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// An error that can occur when fetching the location.
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
 pub enum Error {
     /// An error occurred with the Android Java environment.
-    AndroidEnvironment,
+    ///
+    /// `source` is the JNI error, if there was one; it is also returned by
+    /// [`source()`](std::error::Error::source). It is shared so `Error`
+    /// stays `Clone`, and skipped when serializing.
+    AndroidEnvironment {
+        #[cfg_attr(feature = "serde", serde(skip))]
+        source: Option<Arc<dyn std::error::Error + Send + Sync + 'static>>,
+    },
     /// The user denied authorization.
     AuthorizationDenied,
     /// A network error occurred.
@@ -119,38 +383,443 @@ pub enum Error {
     PermanentlyUnavailable,
     /// An unknown error occurred.
     Unknown,
+    /// A value could not be converted to valid coordinates.
+    InvalidCoordinates,
+    /// More geofences were registered than the platform can monitor.
+    TooManyGeofences { limit: usize },
+    /// The browser's Geolocation API reported an error.
+    ///
+    /// `code` is the `GeolocationPositionError.code` value and `message` the
+    /// browser's description of the failure. Only returned on the web, but
+    /// available everywhere so matches on `Error` are the same on every
+    /// platform.
+    WebError { code: u16, message: String },
 }
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Error::AndroidEnvironment => write!(f, "Android Java environment error"),
+            Error::AndroidEnvironment { source: None } => {
+                write!(f, "Android Java environment error")
+            }
+            Error::AndroidEnvironment {
+                source: Some(source),
+            } => write!(f, "Android Java environment error: {source}"),
             Error::AuthorizationDenied => write!(f, "Location authorization denied"),
             Error::Network => write!(f, "Network error"),
             Error::NotMainThread => write!(f, "Function must be called from main thread"),
             Error::TemporarilyUnavailable => write!(f, "Location temporarily unavailable"),
             Error::PermanentlyUnavailable => write!(f, "Location not supported on this device"),
             Error::Unknown => write!(f, "Unknown error"),
+            Error::InvalidCoordinates => write!(f, "Invalid coordinates"),
+            Error::TooManyGeofences { limit } => {
+                write!(f, "Too many geofences, at most {limit} are supported")
+            }
+            Error::WebError { code, message } => {
+                write!(f, "Web geolocation error {code}: {message}")
+            }
         }
     }
 }
 
-impl std::error::Error for Error {}
+impl Error {
+    /// The JNI error behind an [`Error::AndroidEnvironment`], if any.
+    pub fn android_source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::AndroidEnvironment {
+                source: Some(source),
+            } => Some(&**source),
+            _ => None,
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.android_source()
+    }
+}
 
 #[cfg(target_os = "android")]
 impl From<jni::errors::Error> for Error {
-    fn from(_: jni::errors::Error) -> Self {
-        Error::AndroidEnvironment
+    fn from(error: jni::errors::Error) -> Self {
+        Error::AndroidEnvironment {
+            source: Some(Arc::new(error)),
+        }
     }
 }
 
 /// Represents a geographic coordinate
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Coordinates {
     pub latitude: f64,
     pub longitude: f64,
 }
 
+impl Coordinates {
+    /// Create coordinates, wrapping the longitude into `[-180, 180)` and
+    /// clamping the latitude to `[-90, 90]`.
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        Self {
+            latitude: geo_math::normalize_latitude(latitude),
+            longitude: geo_math::normalize_longitude(longitude),
+        }
+    }
+
+    /// Start a [`CoordinatesBuilder`], which names each argument and
+    /// validates them instead of normalizing.
+    pub fn builder() -> CoordinatesBuilder {
+        CoordinatesBuilder::default()
+    }
+
+    /// Link to these coordinates on openstreetmap.org, with a marker, at the
+    /// given map `zoom` level.
+    pub fn to_osm_deeplink(&self, zoom: u8) -> String {
+        let (lat, lon) = (self.latitude, self.longitude);
+        format!("https://www.openstreetmap.org/?mlat={lat}&mlon={lon}#map={zoom}/{lat}/{lon}")
+    }
+
+    /// Link to these coordinates in Google Maps (opens the app if installed).
+    pub fn to_google_maps_deeplink(&self) -> String {
+        format!(
+            "https://www.google.com/maps/search/?api=1&query={},{}",
+            self.latitude, self.longitude
+        )
+    }
+
+    /// Link to these coordinates in Apple Maps (opens the app on Apple devices).
+    pub fn to_apple_maps_deeplink(&self) -> String {
+        format!(
+            "https://maps.apple.com/?ll={lat},{lon}&q={lat},{lon}",
+            lat = self.latitude,
+            lon = self.longitude
+        )
+    }
+}
+
+/// Builds [`Coordinates`] from named latitude and longitude, so they can't
+/// be swapped by accident.
+///
+/// Unlike [`Coordinates::new`], which wraps and clamps, [`build`](Self::build)
+/// rejects out-of-range values, as they usually mean a mixed-up argument.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::Coordinates;
+///
+/// let berlin = Coordinates::builder()
+///     .latitude(52.52)
+///     .longitude(13.405)
+///     .build()
+///     .unwrap();
+/// assert_eq!(berlin.latitude, 52.52);
+///
+/// // Swapped by mistake: 139.69 is not a valid latitude
+/// assert!(Coordinates::builder().latitude(139.69).longitude(35.68).build().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+#[must_use]
+pub struct CoordinatesBuilder {
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+}
+
+impl CoordinatesBuilder {
+    /// Set the latitude in degrees, `[-90, 90]`.
+    pub fn latitude(mut self, lat: f64) -> Self {
+        self.latitude = Some(lat);
+        self
+    }
+
+    /// Set the longitude in degrees, `[-180, 180]`.
+    pub fn longitude(mut self, lon: f64) -> Self {
+        self.longitude = Some(lon);
+        self
+    }
+
+    /// Validate and build the coordinates.
+    ///
+    /// Fails with [`Error::InvalidCoordinates`] if either value is missing,
+    /// not finite, or out of range.
+    pub fn build(self) -> Result<Coordinates> {
+        match (self.latitude, self.longitude) {
+            (Some(latitude), Some(longitude))
+                if (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude) =>
+            {
+                Ok(Coordinates {
+                    latitude,
+                    longitude,
+                })
+            }
+            _ => Err(Error::InvalidCoordinates),
+        }
+    }
+}
+
+/// A location fix with every detail the platform reports.
+///
+/// Fields the platform doesn't provide for a given fix are `None`; for
+/// example, network-based fixes usually have no altitude, and browsers only
+/// report speed and course while moving.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Altitude above sea level in meters.
+    pub altitude: Option<f64>,
+    /// Radius of uncertainty of the position in meters (68% confidence).
+    pub horizontal_accuracy: Option<f64>,
+    /// Uncertainty of the altitude in meters.
+    pub vertical_accuracy: Option<f64>,
+    /// Ground speed in meters per second.
+    pub speed: Option<f64>,
+    /// Direction of travel in degrees clockwise from true north.
+    pub course: Option<f64>,
+    /// When the fix was obtained.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_millis::option"))]
+    pub timestamp: Option<SystemTime>,
+}
+
+impl Location {
+    /// The position of this fix.
+    pub fn coordinates(&self) -> Coordinates {
+        Coordinates {
+            latitude: self.latitude,
+            longitude: self.longitude,
+        }
+    }
+}
+
+impl From<Coordinates> for Location {
+    /// A fix with only a position and no other details.
+    fn from(coords: Coordinates) -> Self {
+        Location {
+            latitude: coords.latitude,
+            longitude: coords.longitude,
+            altitude: None,
+            horizontal_accuracy: None,
+            vertical_accuracy: None,
+            speed: None,
+            course: None,
+            timestamp: None,
+        }
+    }
+}
+
+impl From<Location> for Coordinates {
+    fn from(location: Location) -> Self {
+        location.coordinates()
+    }
+}
+
+/// Options for [`get_location`] and [`watch_position`].
+///
+/// The defaults match what `get_current_position_sync()` uses on web:
+/// network accuracy, a 10 second timeout, cached fixes up to a minute old and
+/// no distance filter.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::LocationOptions;
+/// use std::time::Duration;
+///
+/// let options = LocationOptions::new()
+///     .high_accuracy(true)
+///     .timeout(Duration::from_secs(15))
+///     .maximum_age(Duration::from_secs(60))
+///     .distance_filter(10.0);
+/// assert!(options.high_accuracy);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocationOptions {
+    /// How long to wait for a fix before failing with
+    /// [`Error::TemporarilyUnavailable`].
+    pub timeout: Duration,
+    /// Accept a cached fix if it is at most this old.
+    pub maximum_age: Duration,
+    /// Ask for the most accurate (satellite) positioning, at the cost of
+    /// battery life and a slower first fix. On iOS and macOS, a tier set
+    /// with [`set_desired_accuracy`] takes precedence.
+    pub high_accuracy: bool,
+    /// Minimum distance in meters the device must move before a watch
+    /// reports a new fix. `0.0` reports every fix.
+    pub distance_filter: f64,
+    /// Prefer indoor positioning where the platform offers it, see
+    /// [`with_indoor_mapping`](Self::with_indoor_mapping).
+    #[cfg(feature = "indoor")]
+    pub indoor_mapping: bool,
+}
+
+impl LocationOptions {
+    /// Options with the default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set [`high_accuracy`](Self::high_accuracy).
+    ///
+    /// Maps to `enableHighAccuracy` on web, `kCLLocationAccuracyBest` on
+    /// iOS/macOS and the GPS provider on Android.
+    #[must_use]
+    pub fn high_accuracy(mut self, high_accuracy: bool) -> Self {
+        self.high_accuracy = high_accuracy;
+        self
+    }
+
+    /// Set [`timeout`](Self::timeout). Maps to `timeout` on web.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set [`maximum_age`](Self::maximum_age). Maps to `maximumAge` on web.
+    #[must_use]
+    pub fn maximum_age(mut self, maximum_age: Duration) -> Self {
+        self.maximum_age = maximum_age;
+        self
+    }
+
+    /// Set [`distance_filter`](Self::distance_filter) in meters.
+    ///
+    /// Maps to `CLLocationManager.distanceFilter` on iOS/macOS and the
+    /// `minDistance` of `LocationManager.requestLocationUpdates()` on
+    /// Android. Browsers have no equivalent, so on web fixes closer than
+    /// this to the last reported one are dropped.
+    #[must_use]
+    pub fn distance_filter(mut self, meters: f64) -> Self {
+        self.distance_filter = meters;
+        self
+    }
+
+    /// Set [`indoor_mapping`](Self::indoor_mapping).
+    ///
+    /// On iOS and macOS this asks for the best accuracy, the only one at
+    /// which CoreLocation uses the indoor positioning of venues Apple has
+    /// surveyed. On Android it prefers the network provider over GPS, as
+    /// satellites can't be seen indoors and the network provider locates
+    /// the device from Wi-Fi, including Wi-Fi round-trip-time ranging on
+    /// Android 9+ devices that support it. Ignored on other platforms.
+    #[cfg(feature = "indoor")]
+    #[must_use]
+    pub fn with_indoor_mapping(mut self, indoor_mapping: bool) -> Self {
+        self.indoor_mapping = indoor_mapping;
+        self
+    }
+
+    /// Whether [`indoor_mapping`](Self::indoor_mapping) is set, `false`
+    /// without the `indoor` feature.
+    #[cfg(any(target_os = "android", target_os = "ios", target_os = "macos"))]
+    pub(crate) fn wants_indoor_mapping(&self) -> bool {
+        #[cfg(feature = "indoor")]
+        return self.indoor_mapping;
+        #[cfg(not(feature = "indoor"))]
+        return false;
+    }
+}
+
+impl Default for LocationOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            maximum_age: Duration::from_secs(60),
+            high_accuracy: false,
+            distance_filter: 0.0,
+            #[cfg(feature = "indoor")]
+            indoor_mapping: false,
+        }
+    }
+}
+
+/// A location provider available on the device.
+///
+/// Only Android exposes individual providers (`gps`, `network`, `fused`,
+/// `passive`); see [`list_available_location_providers`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocationProviderInfo {
+    /// Provider name, e.g. `"gps"`.
+    pub name: String,
+    /// Whether the user has the provider turned on.
+    pub enabled: bool,
+    /// Whether the provider needs a data network connection.
+    pub requires_network: bool,
+    /// Whether the provider needs a satellite positioning system.
+    pub requires_satellite: bool,
+    /// Whether the provider needs the cellular network.
+    pub requires_cell: bool,
+    /// Accuracy of the provider's last known fix in meters, if it has one.
+    pub accuracy_meters: Option<f32>,
+}
+
+/// Expected battery impact of location updates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PowerLevel {
+    /// Location is not in use, e.g. because permission is missing.
+    None,
+    /// Coarse, low-power positioning such as cell towers or Wi-Fi.
+    Low,
+    /// Medium power use, e.g. network location with occasional GPS.
+    Medium,
+    /// Satellite positioning, the most expensive option.
+    High,
+}
+
+/// Accuracy to ask the platform for with [`set_desired_accuracy`].
+///
+/// Coarser tiers let the platform use cheaper positioning, which matters a
+/// lot for battery life: a weather widget is fine with
+/// [`ThreeKilometers`](Self::ThreeKilometers), while turn-by-turn navigation
+/// needs [`Navigation`](Self::Navigation).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AccuracyTier {
+    /// The highest accuracy, using additional sensors; meant for navigation
+    /// while the device is plugged in.
+    Navigation,
+    /// The best accuracy the device can provide.
+    Best,
+    /// Accurate to about ten meters.
+    TenMeters,
+    /// Accurate to about a hundred meters.
+    HundredMeters,
+    /// Accurate to about a kilometer.
+    Kilometer,
+    /// Accurate to about three kilometers.
+    ThreeKilometers,
+}
+
+/// Location permission state reported by [`permission_status`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PermissionStatus {
+    /// The user hasn't been asked yet.
+    NotDetermined,
+    /// The user declined location access.
+    Denied,
+    /// Location access is blocked by the system, e.g. parental controls or
+    /// a platform without location support.
+    Restricted,
+    /// Only approximate location is allowed.
+    GrantedCoarse,
+    /// Precise location is allowed while the app is in use.
+    GrantedFine,
+    /// Location is allowed in the background too.
+    GrantedAlways,
+}
+
+impl PermissionStatus {
+    /// Whether any kind of location access has been granted.
+    pub fn is_granted(self) -> bool {
+        matches!(
+            self,
+            Self::GrantedCoarse | Self::GrantedFine | Self::GrantedAlways
+        )
+    }
+}
+
 // Embed location permissions as linker symbols when features are enabled
 // Use the builder pattern required by the updated permissions crate API
 #[cfg(feature = "location-fine")]
@@ -174,6 +843,19 @@ pub const BACKGROUND_LOCATION: Permission = static_permission!(PermissionBuilder
     .with_description("Background location access")
     .build());
 
+// Motion and fitness access for the CoreMotion sensors (iOS) and step and
+// activity detection (Android)
+#[cfg(any(
+    feature = "barometer",
+    feature = "pedometer",
+    feature = "activity-recognition"
+))]
+pub const MOTION: Permission = static_permission!(PermissionBuilder::custom()
+    .with_android("android.permission.ACTIVITY_RECOGNITION")
+    .with_ios("NSMotionUsageDescription")
+    .with_description("Motion and fitness activity")
+    .build());
+
 /// Request location permissions at runtime.
 ///
 /// This function triggers the system permission dialog for location access.
@@ -184,6 +866,10 @@ pub const BACKGROUND_LOCATION: Permission = static_permission!(PermissionBuilder
 /// - **Android**: Calls `ActivityCompat.requestPermissions()` via JNI
 /// - **iOS/macOS**: Calls `CLLocationManager.requestWhenInUseAuthorization()` via objc2
 /// - **Web**: Initiates geolocation request (triggers permission dialog and populates cache)
+/// - **Windows** (`windows` feature): Calls `Geolocator.RequestAccessAsync()`
+///   and blocks until the user answers
+/// - **Linux** (`linux` feature): Starts a GeoClue2 client and returns whether
+///   `Start()` succeeded
 /// - **Other platforms**: Always returns `false`
 ///
 /// ## Usage
@@ -193,18 +879,42 @@ pub const BACKGROUND_LOCATION: Permission = static_permission!(PermissionBuilder
 ///
 /// On web, this also starts fetching the location asynchronously. You can then
 /// call `last_known_location()` after a short delay to retrieve the cached result.
+///
+/// Emits [`LocationEvent::PermissionGranted`] or
+/// [`LocationEvent::PermissionDenied`] if the answer is known when this
+/// returns. Where the dialog answers later, the event is emitted by the next
+/// [`permission_status`] call that sees the change.
 pub fn request_location_permission() -> bool {
+    // Record the status before asking, so the answer is seen as a change
+    permission_status();
+    let requested = request_permission();
+    permission_status();
+    requested
+}
+
+fn request_permission() -> bool {
+    #[cfg(feature = "mock")]
+    if mock::request_permission() {
+        return true;
+    }
+
     #[cfg(target_os = "android")]
     return android::request_permission();
     #[cfg(any(target_os = "ios", target_os = "macos"))]
     return darwin::request_permission();
     #[cfg(target_arch = "wasm32")]
     return web::request_permission();
+    #[cfg(all(target_os = "windows", feature = "windows"))]
+    return windows::request_permission();
+    #[cfg(all(target_os = "linux", feature = "linux"))]
+    return linux::request_permission();
     #[cfg(not(any(
         target_os = "android",
         target_os = "ios",
         target_os = "macos",
-        target_arch = "wasm32"
+        target_arch = "wasm32",
+        all(target_os = "windows", feature = "windows"),
+        all(target_os = "linux", feature = "linux")
     )))]
     return unsupported::request_permission();
 }
@@ -216,9 +926,15 @@ pub fn request_location_permission() -> bool {
 ///
 /// ## Platform behavior
 ///
-/// - **Android**: Queries `LocationManager.getLastKnownLocation()` via JNI
+/// - **Android**: Queries `FusedLocationProviderClient.getLastLocation()`
+///   when Google Play services are available, falling back to
+///   `LocationManager.getLastKnownLocation()` via JNI
 /// - **iOS/macOS**: Queries `CLLocationManager.location` via objc2
 /// - **Web**: Returns cached position (must call `get_current_position_sync()` first to populate cache)
+/// - **Windows** (`windows` feature): Returns the last fix seen by this
+///   process, or asks `Geolocator` for a cached position for up to a second
+/// - **Linux** (`linux` feature): Returns the last fix seen by this process,
+///   or waits up to a second for a GeoClue2 client's first fix
 /// - **Other platforms**: Always returns `None`
 ///
 /// ## Web Usage
@@ -252,18 +968,1004 @@ pub fn request_location_permission() -> bool {
 /// On iOS/macOS, permissions are handled via Info.plist configuration.
 ///
 /// On Web, permissions are requested automatically when you call the Geolocation API.
+///
+/// Use [`last_known_location_full`] to also get the accuracy, altitude,
+/// speed and time of the fix.
 pub fn last_known_location() -> Option<(f64, f64)> {
+    last_known_location_full().map(|location| (location.latitude, location.longitude))
+}
+
+/// Get the last known location from the device, with all available details.
+///
+/// Like [`last_known_location`], but returns a [`Location`] so callers can
+/// check the fix's accuracy and age before trusting it.
+///
+/// This only reads a cached fix, so unlike the functions that obtain a new
+/// fix it doesn't emit [`LocationEvent::FixObtained`].
+///
+/// ## Platform behavior
+///
+/// - **Android**: Reads the location returned by
+///   `FusedLocationProviderClient.getLastLocation()` when Google Play
+///   services are available, falling back to
+///   `LocationManager.getLastKnownLocation()` via JNI
+/// - **iOS/macOS**: Reads `CLLocationManager.location` via objc2
+/// - **Web**: Returns the cached `GeolocationPosition`, falling back to the
+///   position persisted by a previous page load (which only has coordinates)
+/// - **Windows** (`windows` feature): Returns the last fix seen by this
+///   process, or asks `Geolocator` for a cached position for up to a second
+/// - **Linux** (`linux` feature): Returns the last fix seen by this process,
+///   or waits up to a second for a GeoClue2 client's first fix
+/// - **Other platforms**: Always returns `None`
+pub fn last_known_location_full() -> Option<Location> {
+    #[cfg(feature = "mock")]
+    if let Some(location) = mock::next_location() {
+        return location;
+    }
+
     #[cfg(target_os = "android")]
     return android::last_known();
     #[cfg(any(target_os = "ios", target_os = "macos"))]
     return darwin::last_known();
     #[cfg(target_arch = "wasm32")]
     return web::last_known();
+    #[cfg(all(target_os = "windows", feature = "windows"))]
+    return windows::last_known();
+    #[cfg(all(target_os = "linux", feature = "linux"))]
+    return linux::last_known();
     #[cfg(not(any(
         target_os = "android",
         target_os = "ios",
         target_os = "macos",
-        target_arch = "wasm32"
+        target_arch = "wasm32",
+        all(target_os = "windows", feature = "windows"),
+        all(target_os = "linux", feature = "linux")
     )))]
     return unsupported::last_known();
 }
+
+/// Get the current location.
+///
+/// Resolves with a cached fix if one is at most `options.maximum_age` old,
+/// and otherwise starts the platform location service and waits for the next
+/// fix, for at most `options.timeout`. Unlike [`last_known_location`], this
+/// never blocks the calling thread.
+///
+/// ## Platform behavior
+///
+/// - **Android**: Registers a `LocationListener` with
+///   `LocationManager.requestLocationUpdates()` on the main looper
+/// - **iOS/macOS**: Starts the `CLLocationManager` with a delegate; must be
+///   called (and polled) on the main thread, or it fails with
+///   [`Error::NotMainThread`]
+/// - **Web**: Wraps `navigator.geolocation.getCurrentPosition()`
+/// - **Windows** (`windows` feature): Subscribes to `Geolocator.PositionChanged`
+/// - **Linux** (`linux` feature): Starts a GeoClue2 client and waits for its
+///   `LocationUpdated` signal
+/// - **Other platforms**: Always fails with [`Error::PermanentlyUnavailable`]
+///
+/// ## Errors
+///
+/// - [`Error::AuthorizationDenied`] if location permission was denied
+/// - [`Error::TemporarilyUnavailable`] if no fix arrived within the timeout,
+///   or location services are switched off
+pub async fn get_location(options: &LocationOptions) -> Result<Location> {
+    #[cfg(any(
+        target_os = "android",
+        target_os = "ios",
+        target_os = "macos",
+        all(target_os = "windows", feature = "windows"),
+        all(target_os = "linux", feature = "linux")
+    ))]
+    let result = watch::get_location(options).await;
+    #[cfg(target_arch = "wasm32")]
+    let result = web::get_location(options).await;
+    #[cfg(not(any(
+        target_os = "android",
+        target_os = "ios",
+        target_os = "macos",
+        target_arch = "wasm32",
+        all(target_os = "windows", feature = "windows"),
+        all(target_os = "linux", feature = "linux")
+    )))]
+    let result: Result<Location> = {
+        let _ = options;
+        Err(Error::PermanentlyUnavailable)
+    };
+
+    match &result {
+        Ok(location) => events::emit(LocationEvent::FixObtained(location.coordinates())),
+        Err(error) => events::emit(LocationEvent::Error(error.clone())),
+    }
+    result
+}
+
+/// Wait for the first location update of a new watch, then stop the watch.
+///
+/// Unlike [`get_location`], this never resolves with a cached fix: it starts
+/// the platform location service like [`watch_location`] and resolves with
+/// the first fix or error it reports. Uses [`LocationOptions::default`] with
+/// `timeout` in place of the default timeout.
+///
+/// ```rust,no_run
+/// use dioxus_mobile_geolocation::watch_location_once;
+/// use std::time::Duration;
+///
+/// # async fn locate() {
+/// match watch_location_once(Duration::from_secs(10)).await {
+///     Ok(coords) => println!("{}, {}", coords.latitude, coords.longitude),
+///     Err(error) => eprintln!("{error}"),
+/// }
+/// # }
+/// ```
+///
+/// On iOS and macOS this must be called and polled on the main thread, as
+/// for [`get_location`]. See [`watch_position`] for platform behavior.
+///
+/// ## Errors
+///
+/// - [`Error::AuthorizationDenied`] if location permission was denied
+/// - [`Error::TemporarilyUnavailable`] if no fix arrived within `timeout`,
+///   or location services are switched off
+pub async fn watch_location_once(timeout: Duration) -> Result<Coordinates> {
+    let options = LocationOptions {
+        timeout,
+        ..LocationOptions::default()
+    };
+    let result = watch::first_update(&options).await;
+
+    match &result {
+        Ok(location) => events::emit(LocationEvent::FixObtained(location.coordinates())),
+        Err(error) => events::emit(LocationEvent::Error(error.clone())),
+    }
+    result.map(|location| location.coordinates())
+}
+
+/// Watch the location continuously.
+///
+/// Returns a [`LocationStream`] yielding every fix (or error) the platform
+/// reports until the stream is dropped. Emits
+/// [`LocationEvent::ServiceStarted`] when updates start and
+/// [`LocationEvent::ServiceStopped`] when the stream is dropped.
+///
+/// ```rust,no_run
+/// use dioxus_mobile_geolocation::{watch_position, LocationOptions};
+/// use futures_core::Stream;
+/// use std::future::poll_fn;
+/// use std::pin::Pin;
+///
+/// # async fn track() {
+/// let mut updates = watch_position(&LocationOptions::default());
+/// while let Some(update) = poll_fn(|cx| Pin::new(&mut updates).poll_next(cx)).await {
+///     match update {
+///         Ok(location) => println!("{}, {}", location.latitude, location.longitude),
+///         Err(error) => eprintln!("{error}"),
+///     }
+/// }
+/// # }
+/// ```
+///
+/// ## Platform behavior
+///
+/// - **Android**: `LocationManager.requestLocationUpdates()` with a listener
+///   on the main looper
+/// - **iOS/macOS**: `CLLocationManager.startUpdatingLocation()` with a
+///   delegate; must be called on the main thread, otherwise the stream
+///   yields [`Error::NotMainThread`]. The stream must also be dropped there.
+/// - **Web**: `navigator.geolocation.watchPosition()`, cleared on drop
+/// - **Windows** (`windows` feature): `Geolocator.PositionChanged`, removed on
+///   drop
+/// - **Linux** (`linux` feature): GeoClue2 `LocationUpdated` signals, with
+///   the client stopped on drop
+/// - **Other platforms**: Yields [`Error::PermanentlyUnavailable`] and ends
+pub fn watch_position(options: &LocationOptions) -> LocationStream {
+    LocationStream::new(options)
+}
+
+/// Call `callback` with every location update until the handle is dropped.
+///
+/// Uses [`LocationOptions::default`]. The callback must be `Send + Sync`
+/// because Android delivers updates on the main looper thread rather than
+/// the thread that started the watch. If updates can't be started, the
+/// callback is called once with the error.
+///
+/// While the signal is lost, e.g. in a tunnel, the platform reports
+/// [`Error::TemporarilyUnavailable`]. If the last fix had a speed and
+/// course, the callback gets a [`dead_reckoning_position`](geo_math::dead_reckoning_position)
+/// estimate instead, for up to a minute after that fix.
+///
+/// See [`watch_position`] for platform behavior.
+pub fn watch_location(
+    callback: impl Fn(Result<Coordinates>) + Send + Sync + 'static,
+) -> WatchHandle {
+    watch_location_with_options(&LocationOptions::default(), callback)
+}
+
+/// Like [`watch_location`], but configured by `options` instead of
+/// [`LocationOptions::default`].
+///
+/// ```rust,no_run
+/// use dioxus_mobile_geolocation::{watch_location_with_options, LocationOptions};
+///
+/// let options = LocationOptions::new().high_accuracy(true).distance_filter(10.0);
+/// let handle = watch_location_with_options(&options, |result| {
+///     if let Ok(coords) = result {
+///         println!("{}, {}", coords.latitude, coords.longitude);
+///     }
+/// });
+/// # drop(handle);
+/// ```
+pub fn watch_location_with_options(
+    options: &LocationOptions,
+    callback: impl Fn(Result<Coordinates>) + Send + Sync + 'static,
+) -> WatchHandle {
+    WatchHandle::new(options, (), watch::dead_reckoning_callback(callback))
+}
+
+/// Like [`watch_location`], but passes `context` to every call of `callback`.
+///
+/// The handle keeps its own clone of `context`, available through
+/// [`WatchHandle::context`], so several watches can share one callback and
+/// still tell their updates apart.
+///
+/// ```rust,no_run
+/// use dioxus_mobile_geolocation::{watch_location_with_context, Coordinates, Result};
+///
+/// let log = |result: Result<Coordinates>, name: &&str| match result {
+///     Ok(coords) => println!("{name}: {coords:?}"),
+///     Err(error) => eprintln!("{name}: {error}"),
+/// };
+/// let driver = watch_location_with_context("driver", log);
+/// let passenger = watch_location_with_context("passenger", log);
+/// assert_eq!(*driver.context(), "driver");
+/// # drop(passenger);
+/// ```
+pub fn watch_location_with_context<T>(
+    context: T,
+    callback: impl Fn(Result<Coordinates>, &T) + Send + Sync + 'static,
+) -> WatchHandle<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    watch_location_with_context_and_options(&LocationOptions::default(), context, callback)
+}
+
+/// Like [`watch_location_with_context`], but configured by `options`
+/// instead of [`LocationOptions::default`].
+pub fn watch_location_with_context_and_options<T>(
+    options: &LocationOptions,
+    context: T,
+    callback: impl Fn(Result<Coordinates>, &T) + Send + Sync + 'static,
+) -> WatchHandle<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    let callback_context = context.clone();
+    WatchHandle::new(
+        options,
+        context,
+        watch::coordinates_callback(move |result| callback(result, &callback_context)),
+    )
+}
+
+/// Monitor `regions` for the device entering, leaving or dwelling in them.
+///
+/// Returns a [`GeofenceStream`] yielding a [`GeofenceEvent`] for every
+/// transition until the stream is dropped, which removes all the regions.
+///
+/// ```rust,no_run
+/// use dioxus_mobile_geolocation::{watch_geofence, Coordinates, GeofenceRegion};
+/// use futures_core::Stream;
+/// use std::future::poll_fn;
+/// use std::pin::Pin;
+///
+/// # async fn track() {
+/// let depot = GeofenceRegion {
+///     id: "depot".to_string(),
+///     center: Coordinates::new(52.52, 13.405),
+///     radius_m: 200.0,
+/// };
+/// let mut events = watch_geofence(vec![depot]);
+/// while let Some(event) = poll_fn(|cx| Pin::new(&mut events).poll_next(cx)).await {
+///     match event {
+///         Ok(event) => println!("{:?} {}", event.kind, event.region_id),
+///         Err(error) => eprintln!("{error}"),
+///     }
+/// }
+/// # }
+/// ```
+///
+/// ## Platform behavior
+///
+/// - **iOS/macOS**: `CLLocationManager.startMonitoring(for:)` with a
+///   `CLCircularRegion` per region; must be called on the main thread,
+///   otherwise the stream yields [`Error::NotMainThread`]. Only boundary
+///   crossings are reported, so there is no `Enter` for a region the device
+///   is already in, and no [`GeofenceEventKind::Dwell`].
+/// - **Other platforms**: Each update of a location watch, as started by
+///   [`watch_position`] with [`LocationOptions::default`], is checked
+///   against the regions
+pub fn watch_geofence(regions: Vec<GeofenceRegion>) -> GeofenceStream {
+    watch_geofence_with_options(&LocationOptions::default(), regions)
+}
+
+/// Like [`watch_geofence`], but configured by `options` instead of
+/// [`LocationOptions::default`].
+///
+/// The options apply to the location watch checked against the regions, so
+/// they are ignored on iOS and macOS, which monitor the regions themselves.
+pub fn watch_geofence_with_options(
+    options: &LocationOptions,
+    regions: Vec<GeofenceRegion>,
+) -> GeofenceStream {
+    GeofenceStream::new(options, regions)
+}
+
+/// Watch the compass heading continuously.
+///
+/// Returns a [`HeadingStream`] yielding every [`Heading`] (or error) the
+/// platform reports until the stream is dropped.
+///
+/// ## Platform behavior
+///
+/// - **Android**: `SensorManager` rotation vector sensor
+///   (`TYPE_ROTATION_VECTOR`), with the listener on the main looper. Devices
+///   without the sensor yield [`Error::PermanentlyUnavailable`].
+/// - **iOS/macOS**: `CLLocationManager.startUpdatingHeading()` with a
+///   delegate; must be called on the main thread, otherwise the stream
+///   yields [`Error::NotMainThread`]. Macs without a magnetometer yield
+///   [`Error::PermanentlyUnavailable`].
+/// - **Web**: `DeviceOrientationEvent` listeners, removed on drop. Safari on
+///   iOS only delivers these events after the page called
+///   `DeviceOrientationEvent.requestPermission()` from a user gesture.
+/// - **Other platforms**: Yields [`Error::PermanentlyUnavailable`] and ends
+pub fn watch_heading() -> HeadingStream {
+    HeadingStream::new()
+}
+
+/// Call `callback` with every location update while the app is in the
+/// foreground, until the handle is dropped.
+///
+/// Location updates stop while the app is in the background, saving battery
+/// when no UI shows the location, and restart when it returns. If they
+/// can't be restarted, the callback is called with the error. Like
+/// [`watch_location`], this uses [`LocationOptions::default`] and the
+/// callback must be `Send + Sync`.
+///
+/// ```rust,no_run
+/// use dioxus_mobile_geolocation::watch_location_foreground_only;
+///
+/// let handle = watch_location_foreground_only(|result| {
+///     if let Ok(coords) = result {
+///         println!("{}, {}", coords.latitude, coords.longitude);
+///     }
+/// });
+/// # drop(handle);
+/// ```
+///
+/// ## Platform behavior
+///
+/// - **Android**: Stops the `LocationManager` updates when the activity is
+///   paused (`onPause`) and restarts them when it resumes, through
+///   `Application.ActivityLifecycleCallbacks`
+/// - **iOS**: Stops the updates on `UIApplicationDidEnterBackgroundNotification`
+///   and restarts them on `UIApplicationWillEnterForegroundNotification`;
+///   must be called on the main thread
+/// - **macOS**: Stops the updates while the app is hidden; must be called on
+///   the main thread
+/// - **Web**: Clears the `watchPosition()` watch while the page is hidden
+///   and starts a new one when the `visibilitychange` event reports it
+///   visible again
+/// - **Other platforms**: Behaves like [`watch_location`], as apps there have
+///   no background state
+///
+/// The app is taken to be in the foreground when this is called. See
+/// [`watch_position`] for how the updates are delivered.
+pub fn watch_location_foreground_only(
+    callback: impl Fn(Result<Coordinates>) + Send + Sync + 'static,
+) -> WatchHandle {
+    WatchHandle::foreground_only(
+        &LocationOptions::default(),
+        (),
+        watch::coordinates_callback(callback),
+    )
+}
+
+/// Call `callback` with the direction of travel in degrees clockwise from
+/// north, until the handle is dropped.
+///
+/// Combines [`watch_heading`] and [`watch_location`]: below
+/// `switch_speed_ms` the compass heading is passed on, preferring true
+/// north, as it is stable while standing still. Above it the GPS course is
+/// passed on instead, as a car's engine and body disturb the magnetometer.
+/// Compass readings are skipped while moving fast, and without a compass
+/// only the course is passed on. Errors aren't reported. The callback must
+/// be `Send + Sync`.
+///
+/// ```rust,no_run
+/// use dioxus_mobile_geolocation::watch_heading_compass;
+///
+/// // Follow the GPS course above walking speed
+/// let handle = watch_heading_compass(2.0, |heading| println!("{heading:.0}°"));
+/// # drop(handle);
+/// ```
+///
+/// See [`watch_heading`] and [`watch_position`] for platform behavior.
+pub fn watch_heading_compass(
+    switch_speed_ms: f64,
+    callback: impl Fn(f64) + Send + Sync + 'static,
+) -> WatchHandle {
+    watch::heading_compass(switch_speed_ms, callback)
+}
+
+/// Call `callback` with the change in altitude in meters since the first
+/// barometer reading, until the handle is dropped.
+///
+/// Air pressure reveals climbing or descending within a second and to
+/// about a meter, which GPS altitude can't, so this suits vertical speed
+/// displays. It doesn't measure the height above sea level. If updates
+/// can't be started, `callback` is called once with the error and the
+/// handle is inactive. The callback must be `Send + Sync`.
+///
+/// ```rust,no_run
+/// use dioxus_mobile_geolocation::watch_barometric_altitude;
+///
+/// let handle = watch_barometric_altitude(|altitude| match altitude {
+///     Ok(meters) => println!("{meters:+.1} m"),
+///     Err(error) => eprintln!("No barometer: {error}"),
+/// });
+/// # drop(handle);
+/// ```
+///
+/// ## Platform behavior
+///
+/// - **Android**: `SensorManager` pressure sensor (`TYPE_PRESSURE`),
+///   converted with `SensorManager.getAltitude()`, with the listener on the
+///   main looper
+/// - **iOS**: `CMAltimeter.startRelativeAltitudeUpdates()` on the main
+///   queue; needs the [`MOTION`] permission
+/// - **Other platforms**: [`Error::PermanentlyUnavailable`]
+///
+/// Devices without a barometer report [`Error::PermanentlyUnavailable`].
+#[cfg(feature = "barometer")]
+pub fn watch_barometric_altitude(
+    callback: impl Fn(Result<f64>) + Send + Sync + 'static,
+) -> WatchHandle {
+    let callback: barometer::AltitudeCallback = Arc::new(callback);
+    match barometer::watch(callback.clone()) {
+        Ok(watch) => WatchHandle::sensor((), Some(watch::SensorWatch::Altitude(watch))),
+        Err(error) => {
+            callback(Err(error));
+            WatchHandle::sensor((), None)
+        }
+    }
+}
+
+/// Call `callback` with the steps (and, on iOS, distance, floors and pace)
+/// counted since the watch started, until the handle is dropped.
+///
+/// Combined with the location fixes this allows dead reckoning indoors,
+/// e.g. with [`walking_speed_estimate`]. If updates can't be started,
+/// `callback` is called once with the error and the handle is inactive.
+/// The callback must be `Send + Sync`.
+///
+/// ```rust,no_run
+/// use dioxus_mobile_geolocation::watch_pedometer;
+///
+/// let handle = watch_pedometer(|update| match update {
+///     Ok(update) => println!("{} steps", update.steps),
+///     Err(error) => eprintln!("No pedometer: {error}"),
+/// });
+/// # drop(handle);
+/// ```
+///
+/// ## Platform behavior
+///
+/// - **Android**: `SensorManager` step counter (`TYPE_STEP_COUNTER`), with
+///   the listener on the main looper; only `steps` is reported. From API
+///   level 29 no steps arrive without the [`MOTION`] permission
+/// - **iOS**: `CMPedometer.startPedometerUpdates(from:)`, calling back on a
+///   CoreMotion queue; needs the [`MOTION`] permission
+/// - **Other platforms**: [`Error::PermanentlyUnavailable`]
+///
+/// Devices without a step counter report [`Error::PermanentlyUnavailable`].
+#[cfg(feature = "pedometer")]
+pub fn watch_pedometer(
+    callback: impl Fn(Result<PedometerUpdate>) + Send + Sync + 'static,
+) -> WatchHandle {
+    let callback: pedometer::PedometerCallback = Arc::new(callback);
+    match pedometer::watch(callback.clone()) {
+        Ok(watch) => WatchHandle::sensor((), Some(watch::SensorWatch::Pedometer(watch))),
+        Err(error) => {
+            callback(Err(error));
+            WatchHandle::sensor((), None)
+        }
+    }
+}
+
+/// Call `callback` with the user's activity each time the platform detects
+/// it, until the handle is dropped.
+///
+/// The platforms combine the motion sensors over several seconds, so this
+/// is more reliable than [`detect_activity_from_speed`], e.g. it tells a
+/// car waiting at a traffic light from someone standing still. Use it to
+/// stop a location watch while [`ActivityType::Still`] is reported, saving
+/// battery. If updates can't be started, `callback` is called once with
+/// [`ActivityType::Unknown`] and the handle is inactive. The callback must
+/// be `Send + Sync`.
+///
+/// ```rust,no_run
+/// use dioxus_mobile_geolocation::{watch_activity, ActivityType};
+///
+/// let handle = watch_activity(|activity| {
+///     if activity == ActivityType::Still {
+///         println!("Standing still, pausing location updates");
+///     }
+/// });
+/// # drop(handle);
+/// ```
+///
+/// ## Platform behavior
+///
+/// - **Android**: Google Play services Activity Recognition, every 10
+///   seconds; devices without Play services get [`ActivityType::Unknown`].
+///   From API level 29 no activities arrive without the [`MOTION`]
+///   permission. Cycling is reported as [`ActivityType::Vehicle`]
+/// - **iOS**: `CMMotionActivityManager.startActivityUpdates()` on the main
+///   queue; needs the [`MOTION`] permission. Cycling is reported as
+///   [`ActivityType::Vehicle`]
+/// - **Other platforms**: Only [`ActivityType::Unknown`], once
+///
+/// [`ActivityType::FastVehicle`] is never reported.
+#[cfg(feature = "activity-recognition")]
+pub fn watch_activity(callback: impl Fn(ActivityType) + Send + Sync + 'static) -> WatchHandle {
+    let callback: activity::ActivityCallback = Arc::new(callback);
+    match activity::watch(callback.clone()) {
+        Ok(watch) => WatchHandle::sensor((), Some(watch::SensorWatch::Activity(watch))),
+        Err(_) => {
+            callback(ActivityType::Unknown);
+            WatchHandle::sensor((), None)
+        }
+    }
+}
+
+/// Call `callback` with the GNSS satellites in view each time their status
+/// changes, until the handle is dropped.
+///
+/// Useful for showing signal strength bars. If satellite status can't be
+/// watched, `callback` is called once with an empty list and the handle is
+/// inactive. The callback must be `Send + Sync`.
+///
+/// ```rust,no_run
+/// use dioxus_mobile_geolocation::watch_gps_status;
+///
+/// let handle = watch_gps_status(|satellites| {
+///     let used = satellites.iter().filter(|satellite| satellite.used_in_fix).count();
+///     println!("{used} of {} satellites used", satellites.len());
+/// });
+/// # drop(handle);
+/// ```
+///
+/// ## Platform behavior
+///
+/// - **Android**: `LocationManager.registerGnssStatusCallback()` (API level
+///   24+) on the main looper; needs fine location permission
+/// - **Other platforms**: No satellite status; `callback` gets an empty list
+pub fn watch_gps_status(
+    callback: impl Fn(Vec<GpsSatellite>) + Send + Sync + 'static,
+) -> WatchHandle {
+    let callback: satellites::SatelliteCallback = Arc::new(callback);
+    match satellites::watch(callback.clone()) {
+        Ok(watch) => WatchHandle::sensor((), Some(watch::SensorWatch::Satellites(watch))),
+        Err(_) => {
+            callback(Vec::new());
+            WatchHandle::sensor((), None)
+        }
+    }
+}
+
+/// Call `callback` with the change between each fix and the one before it,
+/// until the handle is dropped.
+///
+/// The first fix only starts the pairing and produces no diff, and errors
+/// are skipped. Use this to derive speed or turn rate from the raw fixes.
+/// Like [`watch_location`], this uses [`LocationOptions::default`] and the
+/// callback must be `Send + Sync`.
+///
+/// ```rust,no_run
+/// use dioxus_mobile_geolocation::watch_location_diffs;
+///
+/// let handle = watch_location_diffs(|diff| {
+///     if let Some(speed) = diff.speed_mps() {
+///         println!("moved {:.1} m at {speed:.1} m/s", diff.distance_m);
+///     }
+/// });
+/// # drop(handle);
+/// ```
+///
+/// See [`watch_position`] for platform behavior.
+pub fn watch_location_diffs(
+    callback: impl Fn(LocationDiff) + Send + Sync + 'static,
+) -> WatchHandle {
+    watch_location_diffs_with_options(&LocationOptions::default(), callback)
+}
+
+/// Like [`watch_location_diffs`], but configured by `options` instead of
+/// [`LocationOptions::default`].
+pub fn watch_location_diffs_with_options(
+    options: &LocationOptions,
+    callback: impl Fn(LocationDiff) + Send + Sync + 'static,
+) -> WatchHandle {
+    WatchHandle::new(options, (), watch::diff_callback(callback))
+}
+
+/// Call `callback` with at most one fix per `interval`, until the handle is
+/// dropped.
+///
+/// Some platforms deliver fixes far more often than an app needs. As there
+/// is no timer, fixes arriving within `interval` of the last one passed on
+/// are skipped, and the first fix after the interval ends, which is the
+/// most recent one at that point, is passed on right away and starts the
+/// next interval. Intervals are measured with the fixes' timestamps. The
+/// first fix is passed on right away and errors are never held back. Like
+/// [`watch_location`], this uses [`LocationOptions::default`] and the
+/// callback must be `Send + Sync`.
+///
+/// ```rust,no_run
+/// use dioxus_mobile_geolocation::watch_location_min_interval;
+/// use std::time::Duration;
+///
+/// let handle = watch_location_min_interval(Duration::from_secs(5), |result| {
+///     if let Ok(coords) = result {
+///         println!("{}, {}", coords.latitude, coords.longitude);
+///     }
+/// });
+/// # drop(handle);
+/// ```
+///
+/// See [`watch_position`] for platform behavior.
+pub fn watch_location_min_interval(
+    interval: Duration,
+    callback: impl Fn(Result<Coordinates>) + Send + Sync + 'static,
+) -> WatchHandle {
+    WatchHandle::new(
+        &LocationOptions::default(),
+        (),
+        watch::min_interval_callback(interval, callback),
+    )
+}
+
+/// Call `callback` with every location update, lowering the accuracy while
+/// the battery is below `battery_threshold_percent`, until the handle is
+/// dropped.
+///
+/// Starts with [`AccuracyTier::Best`] and switches to
+/// [`AccuracyTier::HundredMeters`] through [`set_desired_accuracy`] while the
+/// battery is low, switching back once it's charged again. The battery is
+/// checked with each update. As [`set_desired_accuracy`] is global, this
+/// affects other watches as well, and its last tier stays in effect after
+/// the handle is dropped. The callback must be `Send + Sync`.
+///
+/// ```rust,no_run
+/// use dioxus_mobile_geolocation::watch_location_adaptive;
+///
+/// let handle = watch_location_adaptive(20, |result| {
+///     if let Ok(coords) = result {
+///         println!("{}, {}", coords.latitude, coords.longitude);
+///     }
+/// });
+/// # drop(handle);
+/// ```
+///
+/// ## Platform behavior
+///
+/// - **iOS**: Reads `UIDevice.batteryLevel`, enabling battery monitoring,
+///   on the main thread where the updates arrive
+/// - **Other platforms**: Behaves like [`watch_location`] with high accuracy,
+///   as they either don't report the battery level or can't change the
+///   accuracy of a running watch
+///
+/// See [`watch_position`] for how the updates are delivered.
+pub fn watch_location_adaptive(
+    battery_threshold_percent: u8,
+    callback: impl Fn(Result<Coordinates>) + Send + Sync + 'static,
+) -> WatchHandle {
+    set_desired_accuracy(AccuracyTier::Best);
+    let options = LocationOptions {
+        high_accuracy: true,
+        ..LocationOptions::default()
+    };
+    WatchHandle::new(
+        &options,
+        (),
+        watch::adaptive_callback(battery_threshold_percent, callback),
+    )
+}
+
+/// Call `callback` only with fixes more accurate than every fix before
+/// them, until the handle is dropped.
+///
+/// Indoors or between tall buildings the reported accuracy can swing from
+/// fix to fix; this passes on a fix only when its `horizontal_accuracy` is
+/// lower than the best seen so far, so the position shown never gets worse.
+/// Fixes without an accuracy are skipped. Errors are passed on and reset the
+/// best accuracy, since updates were interrupted, as does starting a new
+/// watch after dropping the handle. Like [`watch_location`], this uses
+/// [`LocationOptions::default`] and the callback must be `Send + Sync`.
+///
+/// ```rust,no_run
+/// use dioxus_mobile_geolocation::watch_location_accuracy_improvement;
+///
+/// let handle = watch_location_accuracy_improvement(|result| {
+///     if let Ok(coords) = result {
+///         println!("better fix: {}, {}", coords.latitude, coords.longitude);
+///     }
+/// });
+/// # drop(handle);
+/// ```
+///
+/// See [`watch_position`] for platform behavior.
+pub fn watch_location_accuracy_improvement(
+    callback: impl Fn(Result<Coordinates>) + Send + Sync + 'static,
+) -> WatchHandle {
+    watch_location_accuracy_improvement_with_options(&LocationOptions::default(), callback)
+}
+
+/// Like [`watch_location_accuracy_improvement`], but configured by `options`
+/// instead of [`LocationOptions::default`].
+pub fn watch_location_accuracy_improvement_with_options(
+    options: &LocationOptions,
+    callback: impl Fn(Result<Coordinates>) + Send + Sync + 'static,
+) -> WatchHandle {
+    WatchHandle::new(options, (), watch::accuracy_improvement_callback(callback))
+}
+
+/// Get the last known location, or `default` if none is available.
+///
+/// Handy for UI that must always show a position, such as centering a map.
+/// See [`last_known_location`] for platform behavior.
+pub fn last_known_location_or_default(default: Coordinates) -> Coordinates {
+    last_known_location_or_else(|| default)
+}
+
+/// Get the last known location, or compute a fallback with `f` if none is
+/// available.
+///
+/// `f` is only called when there is no known location. See
+/// [`last_known_location`] for platform behavior.
+pub fn last_known_location_or_else(f: impl FnOnce() -> Coordinates) -> Coordinates {
+    match last_known_location() {
+        Some((latitude, longitude)) => Coordinates {
+            latitude,
+            longitude,
+        },
+        None => f(),
+    }
+}
+
+/// Get how long ago the last known location fix was obtained.
+///
+/// Returns `None` if no fix has ever been obtained (or permissions are
+/// denied). The age is measured from the fix's own timestamp, not from when
+/// it was last read, so a cached fix from an hour ago reports an hour.
+///
+/// ## Platform behavior
+///
+/// - **Android**: Uses `Location.getTime()` of the last known location
+/// - **iOS/macOS**: Uses `CLLocation.timestamp` of `CLLocationManager.location`
+/// - **Web**: Uses `Position.timestamp` of the cached position
+/// - **Windows** (`windows` feature): Uses `Geocoordinate.Timestamp` of the
+///   last known position
+/// - **Linux** (`linux` feature): Uses the GeoClue2 `Timestamp` of the last
+///   known position
+/// - **Other platforms**: Always returns `None`
+pub fn time_since_last_fix() -> Option<Duration> {
+    #[cfg(target_os = "android")]
+    let fix_time = android::last_fix_time();
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    let fix_time = darwin::last_fix_time();
+    #[cfg(target_arch = "wasm32")]
+    let fix_time = web::last_fix_time();
+    #[cfg(all(target_os = "windows", feature = "windows"))]
+    let fix_time = windows::last_fix_time();
+    #[cfg(all(target_os = "linux", feature = "linux"))]
+    let fix_time = linux::last_fix_time();
+    #[cfg(not(any(
+        target_os = "android",
+        target_os = "ios",
+        target_os = "macos",
+        target_arch = "wasm32",
+        all(target_os = "windows", feature = "windows"),
+        all(target_os = "linux", feature = "linux")
+    )))]
+    let fix_time = unsupported::last_fix_time();
+
+    // A fix timestamped slightly in the future (clock skew) is brand new.
+    Some(now().duration_since(fix_time?).unwrap_or_default())
+}
+
+/// Whether the last known location came from a mock location provider.
+///
+/// Lets apps that rely on the real position, such as check-ins, reject
+/// spoofed fixes, and lets tests check that they run against a mock.
+///
+/// ## Platform behavior
+///
+/// - **`mock` feature**: `true` while a `MockLocationProvider` is installed
+///   on the current thread, on every platform
+/// - **Android**: `Location.isMock()` (API level 31+), or
+///   `Location.isFromMockProvider()` on older devices, of the last location
+///   known to `LocationManager`
+/// - **iOS/macOS**: `CLLocation.sourceInformation.isSimulatedBySoftware` of
+///   `CLLocationManager.location` (iOS 15+, macOS 12+); must be called on
+///   the main thread
+/// - **Other platforms**: Always returns `false`, as they don't report mock
+///   locations
+///
+/// Returns `false` if no location is known or permission hasn't been granted.
+pub fn is_mock_provider() -> bool {
+    #[cfg(feature = "mock")]
+    if MockLocationProvider::is_installed() {
+        return true;
+    }
+
+    #[cfg(target_os = "android")]
+    return android::last_known_is_mock();
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    return darwin::last_location_is_simulated();
+    #[cfg(not(any(target_os = "android", target_os = "ios", target_os = "macos")))]
+    return false;
+}
+
+/// List the location providers available on the device.
+///
+/// Useful for diagnosing why no fix arrives, e.g. when GPS is switched off.
+/// `accuracy_meters` is only known once location permission is granted and
+/// the provider has produced a fix.
+///
+/// ## Platform behavior
+///
+/// - **Android**: Wraps `LocationManager.getAllProviders()` via JNI
+/// - **Other platforms**: Always returns an empty list, as the OS picks the
+///   provider itself
+pub fn list_available_location_providers() -> Vec<LocationProviderInfo> {
+    #[cfg(target_os = "android")]
+    return android::list_providers();
+    #[cfg(not(target_os = "android"))]
+    return Vec::new();
+}
+
+/// Get the expected battery impact of location updates.
+///
+/// Apps can use this to reduce how often they ask for a fix when the battery
+/// is low.
+///
+/// ## Platform behavior
+///
+/// - **Android**: Maps the `Criteria.POWER_*` requirement of the GPS provider,
+///   or the network provider if GPS is off
+/// - **iOS/macOS**: Maps `CLLocationManager.desiredAccuracy`
+/// - **Web**: [`PowerLevel::Low`] if the Geolocation API is available, as the
+///   browser isn't asked for high accuracy
+/// - **Windows** (`windows` feature): [`PowerLevel::Low`] if location access
+///   is allowed, as Windows uses Wi-Fi positioning unless asked for high accuracy
+/// - **Linux** (`linux` feature): [`PowerLevel::Low`] once GeoClue2 has
+///   allowed a client, as it uses Wi-Fi positioning unless asked for exact
+///   accuracy
+/// - **Other platforms**: Always returns [`PowerLevel::None`]
+///
+/// Returns [`PowerLevel::None`] if location permission hasn't been granted.
+pub fn location_power_level() -> PowerLevel {
+    #[cfg(target_os = "android")]
+    return android::power_level();
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    return darwin::power_level();
+    #[cfg(target_arch = "wasm32")]
+    return web::power_level();
+    #[cfg(all(target_os = "windows", feature = "windows"))]
+    return windows::power_level();
+    #[cfg(all(target_os = "linux", feature = "linux"))]
+    return linux::power_level();
+    #[cfg(not(any(
+        target_os = "android",
+        target_os = "ios",
+        target_os = "macos",
+        target_arch = "wasm32",
+        all(target_os = "windows", feature = "windows"),
+        all(target_os = "linux", feature = "linux")
+    )))]
+    return unsupported::power_level();
+}
+
+/// Set the accuracy the platform should aim for.
+///
+/// Applies to every watch started afterwards, overriding
+/// [`LocationOptions::high_accuracy`], including the short one
+/// [`last_known_location`] runs when no fix is cached.
+///
+/// ## Platform behavior
+///
+/// - **iOS/macOS**: Sets `CLLocationManager.desiredAccuracy` to the matching
+///   `kCLLocationAccuracy*` constant. Running watches are updated right away
+///   if this is called on the main thread
+/// - **Other platforms**: Has no effect; use
+///   [`LocationOptions::high_accuracy`] instead
+pub fn set_desired_accuracy(tier: AccuracyTier) {
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    darwin::set_desired_accuracy(tier);
+    #[cfg(not(any(target_os = "ios", target_os = "macos")))]
+    let _ = tier;
+}
+
+/// Get the current location permission state without prompting the user.
+///
+/// ## Platform behavior
+///
+/// - **Android**: Checks `ACCESS_FINE_LOCATION`, `ACCESS_COARSE_LOCATION` and
+///   `ACCESS_BACKGROUND_LOCATION`. Android can't tell an unasked permission
+///   from a denied one, so `Denied` is only reported after
+///   [`request_location_permission`] has been called in this process
+/// - **iOS/macOS**: Maps `CLLocationManager.authorizationStatus`, reporting
+///   reduced accuracy as [`PermissionStatus::GrantedCoarse`]
+/// - **Web**: Returns the value cached by [`query_permission_status_async`]
+///   or by the last position request, and `NotDetermined` before either
+/// - **Windows** (`windows` feature): Maps `Geolocator.LocationStatus`
+/// - **Linux** (`linux` feature): GeoClue2 can't be queried without starting
+///   a client, so this reports whether the last `Start()` was allowed, and
+///   `NotDetermined` before any
+/// - **Other platforms**: Always returns [`PermissionStatus::Restricted`]
+///
+/// Emits [`LocationEvent::PermissionGranted`] or
+/// [`LocationEvent::PermissionDenied`] when the status changed since the
+/// last call on this thread.
+pub fn permission_status() -> PermissionStatus {
+    #[cfg(target_os = "android")]
+    let status = android::permission_status();
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    let status = darwin::permission_status();
+    #[cfg(target_arch = "wasm32")]
+    let status = web::permission_status();
+    #[cfg(all(target_os = "windows", feature = "windows"))]
+    let status = windows::permission_status();
+    #[cfg(all(target_os = "linux", feature = "linux"))]
+    let status = linux::permission_status();
+    #[cfg(not(any(
+        target_os = "android",
+        target_os = "ios",
+        target_os = "macos",
+        target_arch = "wasm32",
+        all(target_os = "windows", feature = "windows"),
+        all(target_os = "linux", feature = "linux")
+    )))]
+    let status = unsupported::permission_status();
+
+    events::permission_seen(status);
+    status
+}
+
+/// Query the current location permission state.
+///
+/// On web this asks the browser's Permissions API and refreshes the value
+/// returned by [`permission_status`]. Elsewhere the state is available
+/// synchronously and this returns [`permission_status`] directly.
+pub async fn query_permission_status_async() -> PermissionStatus {
+    #[cfg(target_arch = "wasm32")]
+    {
+        let status = web::query_permission_status().await;
+        events::permission_seen(status);
+        return status;
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    return permission_status();
+}
+
+/// Current wall-clock time.
+///
+/// `SystemTime::now()` panics on `wasm32-unknown-unknown`, so the browser
+/// clock is used there instead.
+pub(crate) fn now() -> SystemTime {
+    #[cfg(target_arch = "wasm32")]
+    return UNIX_EPOCH + Duration::from_secs_f64(js_sys::Date::now() / 1000.0);
+    #[cfg(not(target_arch = "wasm32"))]
+    return SystemTime::now();
+}
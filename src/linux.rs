@@ -0,0 +1,272 @@
+//! Linux implementation using GeoClue2 over D-Bus
+//!
+//! Each watch opens its own system bus connection and GeoClue client, so
+//! closing the connection is enough to release the client. GeoClue asks
+//! its agent (usually the desktop environment) whether the app identified by
+//! `DesktopId` may use location.
+
+use crate::watch::LocationCallback;
+use crate::{Error, Location, LocationOptions, PermissionStatus, PowerLevel, Result};
+use std::sync::{mpsc, Arc, Mutex, PoisonError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use zbus::blocking::Connection;
+use zbus::proxy::CacheProperties;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath};
+
+/// `GClueAccuracyLevel` values
+const ACCURACY_NEIGHBORHOOD: u32 = 5;
+const ACCURACY_EXACT: u32 = 8;
+
+/// How long `last_known` waits for the first fix
+const LAST_KNOWN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Most recent fix from any GeoClue client in this process
+static LAST_LOCATION: Mutex<Option<Location>> = Mutex::new(None);
+
+/// Outcome of the last `Start()` call
+static PERMISSION: Mutex<Option<PermissionStatus>> = Mutex::new(None);
+
+#[zbus::proxy(
+    interface = "org.freedesktop.GeoClue2.Manager",
+    default_service = "org.freedesktop.GeoClue2",
+    default_path = "/org/freedesktop/GeoClue2/Manager"
+)]
+trait Manager {
+    fn get_client(&self) -> zbus::Result<OwnedObjectPath>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.GeoClue2.Client",
+    default_service = "org.freedesktop.GeoClue2"
+)]
+trait Client {
+    fn start(&self) -> zbus::Result<()>;
+
+    fn stop(&self) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn set_desktop_id(&self, desktop_id: &str) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn set_requested_accuracy_level(&self, level: u32) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn set_distance_threshold(&self, meters: u32) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn location_updated(&self, old: ObjectPath<'_>, current: ObjectPath<'_>) -> zbus::Result<()>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.GeoClue2.Location",
+    default_service = "org.freedesktop.GeoClue2"
+)]
+trait GeoClueLocation {
+    #[zbus(property)]
+    fn latitude(&self) -> zbus::Result<f64>;
+
+    #[zbus(property)]
+    fn longitude(&self) -> zbus::Result<f64>;
+
+    #[zbus(property)]
+    fn accuracy(&self) -> zbus::Result<f64>;
+
+    #[zbus(property)]
+    fn altitude(&self) -> zbus::Result<f64>;
+
+    #[zbus(property)]
+    fn speed(&self) -> zbus::Result<f64>;
+
+    #[zbus(property)]
+    fn heading(&self) -> zbus::Result<f64>;
+
+    #[zbus(property)]
+    fn timestamp(&self) -> zbus::Result<(u64, u64)>;
+}
+
+/// Request location access by starting a GeoClue client
+///
+/// Returns whether `Start()` succeeded, which requires the GeoClue agent to
+/// allow this app.
+pub fn request_permission() -> bool {
+    start_client(&LocationOptions::default()).is_ok()
+}
+
+/// Get the access state seen by the last `Start()` call
+///
+/// GeoClue can't be asked without starting a client, so this reports
+/// `NotDetermined` until a location has been requested.
+pub fn permission_status() -> PermissionStatus {
+    PERMISSION
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .unwrap_or(PermissionStatus::NotDetermined)
+}
+
+/// Get the last known location
+///
+/// Returns the cached fix if there is one and otherwise starts a client and
+/// waits at most one second for its first fix.
+pub fn last_known() -> Option<Location> {
+    if let Some(location) = cached_location() {
+        return Some(location);
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    let sender = Mutex::new(sender);
+    let _watch = watch(
+        &LocationOptions::default(),
+        Arc::new(move |result| {
+            let _ = sender
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .send(result);
+        }),
+    )
+    .ok()?;
+    receiver.recv_timeout(LAST_KNOWN_TIMEOUT).ok()?.ok()
+}
+
+/// Get the most recent fix seen by this process, without starting a client
+pub fn cached_location() -> Option<Location> {
+    *LAST_LOCATION.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Get the time of the last known location fix
+pub fn last_fix_time() -> Option<SystemTime> {
+    last_known()?.timestamp
+}
+
+/// Expected battery impact of location updates
+///
+/// GeoClue uses Wi-Fi and IP based positioning unless exact accuracy is
+/// requested, so an allowed client reports `Low`.
+pub fn power_level() -> PowerLevel {
+    if permission_status().is_granted() {
+        PowerLevel::Low
+    } else {
+        PowerLevel::None
+    }
+}
+
+/// A started GeoClue client; dropping it stops the client and closes its
+/// connection, which ends the signal thread
+pub struct Watch {
+    connection: Connection,
+    client: ClientProxyBlocking<'static>,
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        let _ = self.client.stop();
+        let _ = self.connection.clone().close();
+    }
+}
+
+/// Start delivering `LocationUpdated` signals to `callback`
+pub fn watch(options: &LocationOptions, callback: LocationCallback) -> Result<Watch> {
+    let (connection, client) = start_client(options)?;
+    let updates = client
+        .receive_location_updated()
+        .map_err(|_| Error::Unknown)?;
+
+    let location_connection = connection.clone();
+    std::thread::spawn(move || {
+        for signal in updates {
+            let Ok(args) = signal.args() else {
+                continue;
+            };
+            match read_location(&location_connection, args.current()) {
+                Ok(location) => {
+                    cache(location);
+                    callback(Ok(location));
+                }
+                Err(_) => callback(Err(Error::TemporarilyUnavailable)),
+            }
+        }
+    });
+
+    Ok(Watch { connection, client })
+}
+
+/// Connect to the system bus and start a GeoClue client
+fn start_client(options: &LocationOptions) -> Result<(Connection, ClientProxyBlocking<'static>)> {
+    let connection = Connection::system().map_err(|_| Error::PermanentlyUnavailable)?;
+    let path = ManagerProxyBlocking::new(&connection)
+        .and_then(|manager| manager.get_client())
+        .map_err(|_| Error::PermanentlyUnavailable)?;
+    let client = ClientProxyBlocking::builder(&connection)
+        .path(path)
+        .and_then(|builder| builder.build())
+        .map_err(|_| Error::Unknown)?;
+
+    let accuracy = if options.high_accuracy {
+        ACCURACY_EXACT
+    } else {
+        ACCURACY_NEIGHBORHOOD
+    };
+    client
+        .set_desktop_id(&desktop_id())
+        .and_then(|()| client.set_requested_accuracy_level(accuracy))
+        .and_then(|()| client.set_distance_threshold(options.distance_filter.max(0.0) as u32))
+        .map_err(|_| Error::Unknown)?;
+
+    match client.start() {
+        Ok(()) => {
+            set_permission(PermissionStatus::GrantedFine);
+            Ok((connection, client))
+        }
+        Err(error) => {
+            if matches!(
+                zbus::fdo::Error::from(error),
+                zbus::fdo::Error::AccessDenied(_)
+            ) {
+                set_permission(PermissionStatus::Denied);
+                Err(Error::AuthorizationDenied)
+            } else {
+                Err(Error::TemporarilyUnavailable)
+            }
+        }
+    }
+}
+
+/// Read the properties of the GeoClue location object at `path`
+fn read_location(connection: &Connection, path: &ObjectPath<'_>) -> zbus::Result<Location> {
+    let location = GeoClueLocationProxyBlocking::builder(connection)
+        .path(path.clone().into_owned())?
+        .cache_properties(CacheProperties::No)
+        .build()?;
+    let (seconds, micros) = location.timestamp()?;
+
+    // GeoClue reports unknown values as -DBL_MAX (altitude) or -1
+    Ok(Location {
+        latitude: location.latitude()?,
+        longitude: location.longitude()?,
+        altitude: location
+            .altitude()
+            .ok()
+            .filter(|altitude| *altitude > f64::MIN),
+        horizontal_accuracy: location.accuracy().ok(),
+        vertical_accuracy: None,
+        speed: location.speed().ok().filter(|speed| *speed >= 0.0),
+        course: location.heading().ok().filter(|heading| *heading >= 0.0),
+        timestamp: Some(UNIX_EPOCH + Duration::from_secs(seconds) + Duration::from_micros(micros)),
+    })
+}
+
+/// Identify the app to the GeoClue agent by its executable name, which is
+/// expected to match its `.desktop` file
+fn desktop_id() -> String {
+    std::env::current_exe()
+        .ok()
+        .and_then(|path| Some(path.file_stem()?.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| env!("CARGO_PKG_NAME").to_string())
+}
+
+fn cache(location: Location) {
+    *LAST_LOCATION.lock().unwrap_or_else(PoisonError::into_inner) = Some(location);
+}
+
+fn set_permission(status: PermissionStatus) {
+    *PERMISSION.lock().unwrap_or_else(PoisonError::into_inner) = Some(status);
+}
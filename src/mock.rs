@@ -0,0 +1,113 @@
+//! Scripted locations for tests
+//!
+//! Replaces the platform behind [`last_known_location`](crate::last_known_location)
+//! and [`request_location_permission`](crate::request_location_permission)
+//! on the current thread, so code calling them can be tested without a
+//! device.
+
+use crate::Location;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+thread_local! {
+    static MOCK: RefCell<Option<MockState>> = const { RefCell::new(None) };
+}
+
+struct MockState {
+    locations: VecDeque<Location>,
+    permission_requested: bool,
+}
+
+/// A thread-local stand-in for the platform location service.
+///
+/// While installed, every call to
+/// [`last_known_location`](crate::last_known_location) or
+/// [`last_known_location_full`](crate::last_known_location_full) on this
+/// thread takes the next scripted location, and returns `None` once they
+/// are used up. [`request_location_permission`](crate::request_location_permission)
+/// returns `true` without prompting and is recorded. Watches and
+/// [`get_location`](crate::get_location) still use the platform.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::{
+///     is_mock_provider, last_known_location, request_location_permission, Location,
+///     MockLocationProvider,
+/// };
+///
+/// let berlin = Location {
+///     latitude: 52.52,
+///     longitude: 13.405,
+///     altitude: None,
+///     horizontal_accuracy: Some(5.0),
+///     vertical_accuracy: None,
+///     speed: None,
+///     course: None,
+///     timestamp: None,
+/// };
+/// MockLocationProvider::install(vec![berlin]);
+/// assert!(is_mock_provider());
+///
+/// assert!(request_location_permission());
+/// assert!(MockLocationProvider::permission_was_requested());
+/// assert_eq!(last_known_location(), Some((52.52, 13.405)));
+/// assert_eq!(last_known_location(), None);
+///
+/// MockLocationProvider::reset();
+/// ```
+pub struct MockLocationProvider;
+
+impl MockLocationProvider {
+    /// Replace the platform on this thread with `locations`, returned in
+    /// order.
+    ///
+    /// Installing again replaces the remaining locations and clears the
+    /// permission request flag.
+    pub fn install(locations: Vec<Location>) {
+        MOCK.with(|mock| {
+            *mock.borrow_mut() = Some(MockState {
+                locations: locations.into(),
+                permission_requested: false,
+            });
+        });
+    }
+
+    /// Restore the platform behavior on this thread.
+    pub fn reset() {
+        MOCK.with(|mock| mock.borrow_mut().take());
+    }
+
+    /// Whether a mock is installed on this thread.
+    pub fn is_installed() -> bool {
+        MOCK.with(|mock| mock.borrow().is_some())
+    }
+
+    /// Whether [`request_location_permission`](crate::request_location_permission)
+    /// was called since the mock was installed.
+    pub fn permission_was_requested() -> bool {
+        MOCK.with(|mock| {
+            mock.borrow()
+                .as_ref()
+                .is_some_and(|state| state.permission_requested)
+        })
+    }
+}
+
+/// The next scripted location, or `None` if no mock is installed
+pub(crate) fn next_location() -> Option<Option<Location>> {
+    MOCK.with(|mock| {
+        let mut mock = mock.borrow_mut();
+        let state = mock.as_mut()?;
+        Some(state.locations.pop_front())
+    })
+}
+
+/// Record a permission request, returning whether a mock is installed
+pub(crate) fn request_permission() -> bool {
+    MOCK.with(|mock| match mock.borrow_mut().as_mut() {
+        Some(state) => {
+            state.permission_requested = true;
+            true
+        }
+        None => false,
+    })
+}
@@ -0,0 +1,85 @@
+//! Permission, fix age and accuracy checked in one place
+
+use crate::observable::latest_fix;
+use crate::{now, permission_status, Coordinates, PermissionStatus};
+use std::time::{Duration, SystemTime};
+
+static MONITOR: LocationMonitor = LocationMonitor { _private: () };
+
+/// Answers "is permission granted, how old is the fix and how accurate is
+/// it?" from the fixes delivered to the location watches.
+///
+/// There is a single monitor, returned by [`global_monitor`]. It reads the
+/// same state as [`location_observable`](crate::location_observable), so
+/// without a running watch there is no fix.
+///
+/// ```rust,no_run
+/// use dioxus_mobile_geolocation::{global_monitor, watch_location};
+/// use std::time::Duration;
+///
+/// let handle = watch_location(|_| {});
+/// // ... later
+/// let monitor = global_monitor();
+/// if monitor.is_fresh(Duration::from_secs(30), 50.0) {
+///     println!("Recent fix: {:?}", monitor.last_fix());
+/// } else {
+///     println!("Waiting for a fix, permission: {:?}", monitor.permission());
+/// }
+/// # drop(handle);
+/// ```
+#[derive(Debug)]
+pub struct LocationMonitor {
+    _private: (),
+}
+
+/// The location monitor shared by the whole app.
+pub fn global_monitor() -> &'static LocationMonitor {
+    &MONITOR
+}
+
+impl LocationMonitor {
+    /// The current location permission, see [`permission_status`].
+    pub fn permission(&self) -> PermissionStatus {
+        permission_status()
+    }
+
+    /// The coordinates of the most recent fix.
+    pub fn last_fix(&self) -> Option<Coordinates> {
+        latest_fix().map(|(location, _)| location.coordinates())
+    }
+
+    /// How long ago the most recent fix was taken.
+    ///
+    /// Measured from the fix's own timestamp, or from when it arrived if
+    /// the platform reports none.
+    pub fn fix_age(&self) -> Option<Duration> {
+        let (location, received) = latest_fix()?;
+        Some(age(location.timestamp.unwrap_or(received)))
+    }
+
+    /// The horizontal accuracy of the most recent fix in meters, if the
+    /// platform reported one.
+    pub fn fix_accuracy(&self) -> Option<f64> {
+        latest_fix().and_then(|(location, _)| location.horizontal_accuracy)
+    }
+
+    /// Whether the most recent fix is at most `max_age` old and accurate to
+    /// `min_accuracy_m` meters or better.
+    ///
+    /// A fix without a reported accuracy is never fresh.
+    pub fn is_fresh(&self, max_age: Duration, min_accuracy_m: f64) -> bool {
+        let Some((location, received)) = latest_fix() else {
+            return false;
+        };
+        let accurate = location
+            .horizontal_accuracy
+            .is_some_and(|accuracy| accuracy <= min_accuracy_m);
+        accurate && age(location.timestamp.unwrap_or(received)) <= max_age
+    }
+}
+
+/// Time since `taken`. A fix timestamped slightly in the future (clock
+/// skew) is brand new.
+fn age(taken: SystemTime) -> Duration {
+    now().duration_since(taken).unwrap_or_default()
+}
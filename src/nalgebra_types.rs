@@ -0,0 +1,22 @@
+//! nalgebra interop
+//!
+//! Points use the GeoJSON axis order: `x` is longitude and `y` is latitude,
+//! the reverse of the latitude-first order used by [`Coordinates::new`].
+
+use crate::Coordinates;
+use nalgebra::Point2;
+
+impl From<Coordinates> for Point2<f64> {
+    /// Convert to a point with `x = longitude` and `y = latitude`.
+    fn from(coords: Coordinates) -> Self {
+        Point2::new(coords.longitude, coords.latitude)
+    }
+}
+
+impl From<Point2<f64>> for Coordinates {
+    /// Convert a point with `x = longitude` and `y = latitude`, normalizing
+    /// as [`Coordinates::new`] does.
+    fn from(point: Point2<f64>) -> Self {
+        Coordinates::new(point.y, point.x)
+    }
+}
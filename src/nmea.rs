@@ -0,0 +1,456 @@
+//! NMEA 0183 sentences from external GPS receivers
+//!
+//! Bluetooth and USB receivers stream sentences such as
+//! `$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47`
+//! over a serial port. This module parses the sentences that carry a
+//! position or satellite information, so desktop apps can use such a
+//! receiver when the platform has no location service.
+//!
+//! Sentences from any talker are accepted, e.g. `$GNGGA` from a receiver
+//! that combines GPS and GLONASS, not only `$GPGGA`.
+
+use crate::Location;
+use std::fmt;
+use std::io::{BufRead, BufReader, Read};
+use std::ops::Range;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Meters per second in a knot.
+const METERS_PER_SECOND_PER_KNOT: f64 = 1852.0 / 3600.0;
+
+/// Typical range error of a consumer receiver in meters, multiplied by the
+/// horizontal dilution of precision to estimate the accuracy of a fix
+const RANGE_ERROR_METERS: f64 = 5.0;
+
+/// Why a line couldn't be parsed by [`NmeaSentence::parse`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NmeaError {
+    /// The line doesn't end in a `*hh` checksum.
+    MissingChecksum,
+    /// The checksum doesn't match the sentence, e.g. because of a
+    /// transmission error.
+    ChecksumMismatch {
+        /// The checksum at the end of the line.
+        expected: u8,
+        /// The checksum computed from the sentence.
+        actual: u8,
+    },
+    /// The line isn't a `$` sentence or a field can't be read.
+    InvalidFormat,
+    /// A valid sentence of a type this module doesn't parse, such as `GPGSA`.
+    UnsupportedSentence(String),
+    /// Reading from the receiver failed.
+    Io(std::io::ErrorKind),
+}
+
+impl fmt::Display for NmeaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NmeaError::MissingChecksum => write!(f, "NMEA sentence has no checksum"),
+            NmeaError::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "NMEA checksum mismatch: expected {expected:02X}, computed {actual:02X}"
+            ),
+            NmeaError::InvalidFormat => write!(f, "Invalid NMEA sentence"),
+            NmeaError::UnsupportedSentence(address) => {
+                write!(f, "Unsupported NMEA sentence {address}")
+            }
+            NmeaError::Io(kind) => write!(f, "Failed to read NMEA sentences: {kind}"),
+        }
+    }
+}
+
+impl std::error::Error for NmeaError {}
+
+/// A parsed NMEA 0183 sentence.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::nmea::NmeaSentence;
+///
+/// let line = "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
+/// let location = NmeaSentence::parse(line).unwrap().to_location().unwrap();
+/// assert!((location.latitude - 48.1173).abs() < 1e-9);
+/// assert!((location.longitude - 11.516_666_666).abs() < 1e-9);
+/// assert_eq!(location.altitude, Some(545.4));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum NmeaSentence {
+    /// `GGA`: fix data with altitude and precision.
+    Gga(Gga),
+    /// `RMC`: recommended minimum data with speed, course and date.
+    Rmc(Rmc),
+    /// `GSV`: satellites in view. Receivers split the list over several
+    /// sentences.
+    Gsv(Gsv),
+}
+
+/// Fix data from a `GGA` sentence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gga {
+    /// UTC time of day of the fix.
+    pub time: Option<Duration>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    /// `0` without a fix, `1` for GPS, `2` for differential GPS and higher
+    /// values for RTK and estimated fixes.
+    pub fix_quality: u8,
+    /// Number of satellites used for the fix.
+    pub satellites_used: Option<u8>,
+    /// Horizontal dilution of precision.
+    pub hdop: Option<f64>,
+    /// Altitude above mean sea level in meters.
+    pub altitude: Option<f64>,
+    /// Height of the geoid above the WGS-84 ellipsoid in meters.
+    pub geoid_separation: Option<f64>,
+}
+
+/// Recommended minimum data from an `RMC` sentence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rmc {
+    /// UTC time of day of the fix.
+    pub time: Option<Duration>,
+    /// Whether the receiver has a valid fix (status `A`).
+    pub active: bool,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    /// Speed over ground in knots.
+    pub speed_knots: Option<f64>,
+    /// Course over ground in degrees clockwise from true north.
+    pub course: Option<f64>,
+    /// UTC date of the fix.
+    pub date: Option<NmeaDate>,
+}
+
+/// A UTC calendar date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NmeaDate {
+    /// Four-digit year; NMEA's two-digit years are read as 1980-2079.
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+/// One sentence of a `GSV` satellites-in-view list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gsv {
+    /// Number of sentences in the list.
+    pub message_count: u8,
+    /// Position of this sentence in the list, starting at 1.
+    pub message_number: u8,
+    /// Total number of satellites in view.
+    pub satellites_in_view: u8,
+    /// Up to four of the satellites.
+    pub satellites: Vec<SatelliteInfo>,
+}
+
+/// A satellite listed in a `GSV` sentence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SatelliteInfo {
+    /// Satellite ID (PRN number).
+    pub prn: u16,
+    /// Elevation above the horizon in degrees.
+    pub elevation: Option<u8>,
+    /// Azimuth in degrees clockwise from true north.
+    pub azimuth: Option<u16>,
+    /// Signal-to-noise ratio in dB-Hz; `None` while the satellite isn't
+    /// tracked.
+    pub snr: Option<u8>,
+}
+
+impl NmeaSentence {
+    /// Parse one sentence, with or without the trailing line break.
+    ///
+    /// The `*hh` checksum is required and must match.
+    pub fn parse(line: &str) -> Result<NmeaSentence, NmeaError> {
+        let sentence = line
+            .trim_end_matches(['\r', '\n'])
+            .strip_prefix('$')
+            .ok_or(NmeaError::InvalidFormat)?;
+        let (body, checksum) = sentence
+            .rsplit_once('*')
+            .ok_or(NmeaError::MissingChecksum)?;
+        if checksum.len() != 2 {
+            return Err(NmeaError::InvalidFormat);
+        }
+        let expected = u8::from_str_radix(checksum, 16).map_err(|_| NmeaError::InvalidFormat)?;
+        let actual = body.bytes().fold(0, |checksum, byte| checksum ^ byte);
+        if actual != expected {
+            return Err(NmeaError::ChecksumMismatch { expected, actual });
+        }
+
+        let mut fields = body.split(',');
+        let address = fields.next().unwrap_or_default();
+        if address.len() != 5 || !address.is_ascii() {
+            return Err(NmeaError::InvalidFormat);
+        }
+        let fields = Fields(fields.collect());
+
+        match &address[2..] {
+            "GGA" => fields.gga().map(NmeaSentence::Gga),
+            "RMC" => fields.rmc().map(NmeaSentence::Rmc),
+            "GSV" => fields.gsv().map(NmeaSentence::Gsv),
+            _ => Err(NmeaError::UnsupportedSentence(address.to_string())),
+        }
+    }
+
+    /// The fix reported by this sentence.
+    ///
+    /// Returns `None` for `GSV` sentences and for sentences without a valid
+    /// fix. `GGA` fixes have an altitude and an accuracy estimated from the
+    /// HDOP but no timestamp, since the sentence has no date; `RMC` fixes
+    /// have a speed, course and timestamp.
+    pub fn to_location(&self) -> Option<Location> {
+        match self {
+            NmeaSentence::Gga(gga) if gga.fix_quality > 0 => Some(Location {
+                latitude: gga.latitude?,
+                longitude: gga.longitude?,
+                altitude: gga.altitude,
+                horizontal_accuracy: gga.hdop.map(|hdop| hdop * RANGE_ERROR_METERS),
+                vertical_accuracy: None,
+                speed: None,
+                course: None,
+                timestamp: None,
+            }),
+            NmeaSentence::Rmc(rmc) if rmc.active => Some(Location {
+                latitude: rmc.latitude?,
+                longitude: rmc.longitude?,
+                altitude: None,
+                horizontal_accuracy: None,
+                vertical_accuracy: None,
+                speed: rmc
+                    .speed_knots
+                    .map(|knots| knots * METERS_PER_SECOND_PER_KNOT),
+                course: rmc.course,
+                timestamp: rmc.date.zip(rmc.time).and_then(to_system_time),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Reads NMEA sentences line by line from a receiver, e.g. an open serial
+/// port.
+///
+/// Yields one result per non-empty line, so a corrupted or unsupported
+/// sentence doesn't end the stream. Ends when the reader does, or after
+/// yielding an IO error other than a timeout.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::nmea::NmeaReader;
+///
+/// let receiver: &[u8] = b"$GPGSA,A,3,04,05,,09,12,,,24,,,,,2.5,1.3,2.1*39\r\n\
+///     $GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A\r\n";
+/// let locations: Vec<_> = NmeaReader::new(receiver)
+///     .filter_map(|sentence| sentence.ok()?.to_location())
+///     .collect();
+/// assert_eq!(locations.len(), 1);
+/// assert_eq!(locations[0].course, Some(84.4));
+/// ```
+pub struct NmeaReader<R> {
+    reader: BufReader<R>,
+    line: Vec<u8>,
+    /// Set once the reader failed for good
+    done: bool,
+}
+
+impl<R: Read> NmeaReader<R> {
+    /// Wrap `reader` in a buffer and read sentences from it.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+            line: Vec::new(),
+            done: false,
+        }
+    }
+}
+
+impl<R: Read> Iterator for NmeaReader<R> {
+    type Item = Result<NmeaSentence, NmeaError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        loop {
+            self.line.clear();
+            match self.reader.read_until(b'\n', &mut self.line) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(error) => {
+                    // A serial port timing out may deliver more data later
+                    let transient = matches!(
+                        error.kind(),
+                        std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock
+                    );
+                    self.done = !transient;
+                    return Some(Err(NmeaError::Io(error.kind())));
+                }
+            }
+            // Serial noise isn't valid UTF-8; it fails the checksum instead
+            let line = String::from_utf8_lossy(&self.line);
+            let line = line.trim();
+            if !line.is_empty() {
+                return Some(NmeaSentence::parse(line));
+            }
+        }
+    }
+}
+
+/// The comma-separated fields after the address; missing trailing fields
+/// read as empty
+struct Fields<'a>(Vec<&'a str>);
+
+impl Fields<'_> {
+    fn get(&self, index: usize) -> &str {
+        self.0.get(index).copied().unwrap_or_default()
+    }
+
+    /// `$--GGA,hhmmss.ss,llll.ll,a,yyyyy.yy,a,q,nn,h.h,a.a,M,g.g,M,...`
+    fn gga(&self) -> Result<Gga, NmeaError> {
+        Ok(Gga {
+            time: time(self.get(0))?,
+            latitude: coordinate(self.get(1), self.get(2))?,
+            longitude: coordinate(self.get(3), self.get(4))?,
+            fix_quality: optional(self.get(5))?.unwrap_or(0),
+            satellites_used: optional(self.get(6))?,
+            hdop: optional(self.get(7))?,
+            altitude: optional(self.get(8))?,
+            geoid_separation: optional(self.get(10))?,
+        })
+    }
+
+    /// `$--RMC,hhmmss.ss,A,llll.ll,a,yyyyy.yy,a,s.s,c.c,ddmmyy,...`
+    fn rmc(&self) -> Result<Rmc, NmeaError> {
+        Ok(Rmc {
+            time: time(self.get(0))?,
+            active: self.get(1) == "A",
+            latitude: coordinate(self.get(2), self.get(3))?,
+            longitude: coordinate(self.get(4), self.get(5))?,
+            speed_knots: optional(self.get(6))?,
+            course: optional(self.get(7))?,
+            date: date(self.get(8))?,
+        })
+    }
+
+    /// `$--GSV,t,n,s,` followed by up to four `prn,elevation,azimuth,snr`
+    /// groups
+    fn gsv(&self) -> Result<Gsv, NmeaError> {
+        let required = |index| optional(self.get(index))?.ok_or(NmeaError::InvalidFormat);
+        // NMEA 4.1 appends a signal ID, which leaves an incomplete group
+        let satellites = self.0[3.min(self.0.len())..]
+            .chunks_exact(4)
+            .filter(|group| !group[0].is_empty())
+            .map(|group| {
+                Ok(SatelliteInfo {
+                    prn: group[0].parse().map_err(|_| NmeaError::InvalidFormat)?,
+                    elevation: optional(group[1])?,
+                    azimuth: optional(group[2])?,
+                    snr: optional(group[3])?,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Gsv {
+            message_count: required(0)?,
+            message_number: required(1)?,
+            satellites_in_view: required(2)?,
+            satellites,
+        })
+    }
+}
+
+/// Parse a field that may be empty
+fn optional<T: std::str::FromStr>(field: &str) -> Result<Option<T>, NmeaError> {
+    if field.is_empty() {
+        return Ok(None);
+    }
+    field
+        .parse()
+        .map(Some)
+        .map_err(|_| NmeaError::InvalidFormat)
+}
+
+/// A `dddmm.mmmm` angle and its `N`/`S`/`E`/`W` hemisphere as signed
+/// decimal degrees
+fn coordinate(value: &str, hemisphere: &str) -> Result<Option<f64>, NmeaError> {
+    let Some(value) = optional::<f64>(value)? else {
+        return Ok(None);
+    };
+    let degrees = (value / 100.0).trunc();
+    let minutes = value - degrees * 100.0;
+    if !(0.0..60.0).contains(&minutes) {
+        return Err(NmeaError::InvalidFormat);
+    }
+    let (sign, limit) = match hemisphere {
+        "N" => (1.0, 90.0),
+        "S" => (-1.0, 90.0),
+        "E" => (1.0, 180.0),
+        "W" => (-1.0, 180.0),
+        _ => return Err(NmeaError::InvalidFormat),
+    };
+    let decimal = degrees + minutes / 60.0;
+    if decimal > limit {
+        return Err(NmeaError::InvalidFormat);
+    }
+    Ok(Some(sign * decimal))
+}
+
+/// `hhmmss.ss` as the time since midnight
+fn time(field: &str) -> Result<Option<Duration>, NmeaError> {
+    if field.is_empty() {
+        return Ok(None);
+    }
+    if field.len() < 6 || !field.is_ascii() {
+        return Err(NmeaError::InvalidFormat);
+    }
+    let part = |range: Range<usize>| {
+        field[range]
+            .parse::<u64>()
+            .map_err(|_| NmeaError::InvalidFormat)
+    };
+    let (hours, minutes) = (part(0..2)?, part(2..4)?);
+    let seconds: f64 = field[4..].parse().map_err(|_| NmeaError::InvalidFormat)?;
+    // 60 seconds allows for a leap second
+    if hours > 23 || minutes > 59 || !(0.0..61.0).contains(&seconds) {
+        return Err(NmeaError::InvalidFormat);
+    }
+    Ok(Some(
+        Duration::from_secs(hours * 3600 + minutes * 60) + Duration::from_secs_f64(seconds),
+    ))
+}
+
+/// `ddmmyy` as a date
+fn date(field: &str) -> Result<Option<NmeaDate>, NmeaError> {
+    if field.is_empty() {
+        return Ok(None);
+    }
+    if field.len() != 6 || !field.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(NmeaError::InvalidFormat);
+    }
+    let part = |range: Range<usize>| {
+        field[range]
+            .parse::<u8>()
+            .map_err(|_| NmeaError::InvalidFormat)
+    };
+    let (day, month, year) = (part(0..2)?, part(2..4)?, part(4..6)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return Err(NmeaError::InvalidFormat);
+    }
+    Ok(Some(NmeaDate {
+        year: if year >= 80 { 1900 } else { 2000 } + u16::from(year),
+        month,
+        day,
+    }))
+}
+
+/// The instant at `time` after midnight UTC on `date`
+fn to_system_time((date, time): (NmeaDate, Duration)) -> Option<SystemTime> {
+    // Days since the epoch from the civil date (Howard Hinnant's algorithm)
+    let (month, day) = (u64::from(date.month), u64::from(date.day));
+    let year = u64::from(date.year) - u64::from(month <= 2);
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = (era * 146_097 + day_of_era).checked_sub(719_468)?;
+    UNIX_EPOCH.checked_add(Duration::from_secs(days * 86_400) + time)
+}
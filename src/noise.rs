@@ -0,0 +1,92 @@
+//! Simulated GPS noise for stress tests
+//!
+//! Real fixes scatter around the true position; perturbing known coordinates
+//! reproduces that so filters and geofences can be tested without a device.
+
+use crate::geo_math::destination_point;
+use crate::{Coordinates, Location};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use std::cell::RefCell;
+use std::f64::consts::TAU;
+
+/// Seed used by [`NoiseSimulator::new`]
+const DEFAULT_SEED: u64 = 0x5eed;
+
+/// Adds Gaussian noise to coordinates, like a receiver with the given
+/// accuracy.
+///
+/// The north and east offsets are drawn independently with a standard
+/// deviation of `horizontal_sigma_meters`, so about 39% of the perturbed
+/// points lie within one sigma of the original. The random number generator
+/// is seeded, so a test sees the same noise on every run.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::geo_math::distance_meters;
+/// use dioxus_mobile_geolocation::{Coordinates, NoiseSimulator};
+///
+/// let noise = NoiseSimulator::new(5.0, 10.0);
+/// let truth = Coordinates::new(52.52, 13.405);
+/// let fix = noise.perturb(truth);
+/// assert!(distance_meters(truth, fix) < 50.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct NoiseSimulator {
+    /// Standard deviation of the north and east offsets in meters.
+    pub horizontal_sigma_meters: f64,
+    /// Standard deviation of the altitude offset in meters.
+    pub vertical_sigma_meters: f64,
+    rng: RefCell<SmallRng>,
+}
+
+impl NoiseSimulator {
+    /// Create a simulator with a fixed seed.
+    pub fn new(horizontal_sigma_meters: f64, vertical_sigma_meters: f64) -> Self {
+        Self::with_seed(horizontal_sigma_meters, vertical_sigma_meters, DEFAULT_SEED)
+    }
+
+    /// Create a simulator whose noise is determined by `seed`.
+    pub fn with_seed(horizontal_sigma_meters: f64, vertical_sigma_meters: f64, seed: u64) -> Self {
+        Self {
+            horizontal_sigma_meters,
+            vertical_sigma_meters,
+            rng: RefCell::new(SmallRng::seed_from_u64(seed)),
+        }
+    }
+
+    /// `c` moved by a random horizontal offset.
+    pub fn perturb(&self, c: Coordinates) -> Coordinates {
+        let north = self.gaussian(self.horizontal_sigma_meters);
+        let east = self.gaussian(self.horizontal_sigma_meters);
+        let bearing = east.atan2(north).to_degrees();
+        destination_point(c, bearing, north.hypot(east))
+    }
+
+    /// `location` with a random horizontal offset and, if it has an
+    /// altitude, a random vertical offset.
+    ///
+    /// The accuracies are set to the sigmas, as a receiver would report
+    /// them. Other fields are kept.
+    pub fn perturb_location(&self, location: Location) -> Location {
+        let position = self.perturb(location.coordinates());
+        Location {
+            latitude: position.latitude,
+            longitude: position.longitude,
+            altitude: location
+                .altitude
+                .map(|altitude| altitude + self.gaussian(self.vertical_sigma_meters)),
+            horizontal_accuracy: Some(self.horizontal_sigma_meters),
+            vertical_accuracy: location.altitude.map(|_| self.vertical_sigma_meters),
+            ..location
+        }
+    }
+
+    /// A sample from a normal distribution with mean zero (Box-Muller)
+    fn gaussian(&self, sigma: f64) -> f64 {
+        let mut rng = self.rng.borrow_mut();
+        // `1.0 - x` keeps the logarithm's argument in (0, 1]
+        let radius = (-2.0 * (1.0 - rng.random::<f64>()).ln()).sqrt();
+        let angle = TAU * rng.random::<f64>();
+        sigma * radius * angle.cos()
+    }
+}
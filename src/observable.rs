@@ -0,0 +1,111 @@
+//! A comparable snapshot of the location state for UI frameworks
+
+use crate::watch::LocationCallback;
+use crate::{now, permission_status, Coordinates, Error, Location, PermissionStatus, Result};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::SystemTime;
+
+/// Coordinates closer than this many degrees (about a meter) compare equal,
+/// so GPS jitter doesn't count as a change.
+const COORDINATE_PRECISION: f64 = 1e-5;
+
+static LATEST: Mutex<Latest> = Mutex::new(Latest {
+    fix: None,
+    error: None,
+});
+
+/// What the watches have delivered so far
+#[derive(Clone)]
+struct Latest {
+    /// The latest fix, with the time it arrived
+    fix: Option<(Location, SystemTime)>,
+    /// The error since the latest fix
+    error: Option<Error>,
+}
+
+/// The location state as last reported by the location watches.
+///
+/// Two observables are equal when their coordinates agree to within about a
+/// meter (1e-5 degrees), their errors have the same message and their
+/// permission is the same, which makes this a good dependency for
+/// Dioxus's `use_reactive`: a component re-renders only when something
+/// visible changed.
+///
+/// ```rust,no_run
+/// use dioxus_mobile_geolocation::{location_observable, watch_location};
+///
+/// let handle = watch_location(|_| {});
+/// let before = location_observable();
+/// // ... later
+/// if location_observable() != before {
+///     println!("Location state changed: {:?}", location_observable());
+/// }
+/// # drop(handle);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LocationObservable {
+    /// The most recent fix, kept when a later update fails.
+    pub coords: Option<Coordinates>,
+    /// The error from the most recent update, cleared by the next fix.
+    pub error: Option<Error>,
+    /// The location permission when the snapshot was taken.
+    pub permission: PermissionStatus,
+}
+
+impl PartialEq for LocationObservable {
+    fn eq(&self, other: &Self) -> bool {
+        let quantize = |coords: Option<Coordinates>| {
+            coords.map(|coords| {
+                (
+                    (coords.latitude / COORDINATE_PRECISION).round() as i64,
+                    (coords.longitude / COORDINATE_PRECISION).round() as i64,
+                )
+            })
+        };
+        // Error has no PartialEq of its own
+        let message = |error: &Option<Error>| error.as_ref().map(Error::to_string);
+        quantize(self.coords) == quantize(other.coords)
+            && message(&self.error) == message(&other.error)
+            && self.permission == other.permission
+    }
+}
+
+/// Snapshot the location state delivered to the watches so far.
+///
+/// Every watch started through this crate, e.g. [`watch_location`](crate::watch_location)
+/// or [`watch_position`](crate::watch_position), updates the state. Without
+/// a running watch, `coords` and `error` stay `None`.
+pub fn location_observable() -> LocationObservable {
+    let Latest { fix, error } = LATEST
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    LocationObservable {
+        coords: fix.map(|(location, _)| location.coordinates()),
+        error,
+        permission: permission_status(),
+    }
+}
+
+/// The latest fix delivered to any watch, with the time it arrived.
+pub(crate) fn latest_fix() -> Option<(Location, SystemTime)> {
+    LATEST.lock().unwrap_or_else(PoisonError::into_inner).fix
+}
+
+/// Wrap `callback` so every result it gets also updates the state returned
+/// by [`location_observable`].
+pub(crate) fn observe(callback: LocationCallback) -> LocationCallback {
+    Arc::new(move |result: Result<Location>| {
+        {
+            let mut latest = LATEST.lock().unwrap_or_else(PoisonError::into_inner);
+            match &result {
+                Ok(location) => {
+                    latest.fix = Some((*location, now()));
+                    latest.error = None;
+                }
+                Err(error) => latest.error = Some(error.clone()),
+            }
+        }
+        callback(result)
+    })
+}
@@ -0,0 +1,83 @@
+//! Pedometer updates and pedometer-based speed estimates
+//!
+//! For indoor positioning, where GPS speed is unavailable, walking speed can
+//! be estimated from the step cadence reported by `CMPedometer` (iOS) or the
+//! step counter sensor (Android), both delivered by
+//! [`watch_pedometer`](crate::watch_pedometer).
+
+use crate::Result;
+use std::sync::Arc;
+
+#[cfg(target_os = "android")]
+use crate::android as platform;
+#[cfg(target_os = "ios")]
+use crate::darwin as platform;
+
+/// Callback invoked by a platform pedometer watch for each update or error.
+pub(crate) type PedometerCallback = Arc<dyn Fn(Result<PedometerUpdate>) + Send + Sync>;
+
+/// A running platform pedometer watch. Updates stop when it is dropped.
+pub(crate) type PedometerWatch = platform::PedometerWatch;
+
+/// Start pedometer updates, calling `callback` for each update or error.
+pub(crate) fn watch(callback: PedometerCallback) -> Result<PedometerWatch> {
+    platform::watch_pedometer(callback)
+}
+
+/// Activity counted since a pedometer watch started, as passed to
+/// [`watch_pedometer`](crate::watch_pedometer).
+///
+/// Only `steps` is reported everywhere; the other fields are `None` where
+/// the platform doesn't measure them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PedometerUpdate {
+    /// Steps taken.
+    pub steps: u32,
+    /// Distance walked in meters.
+    pub distance: Option<f64>,
+    /// Floors climbed.
+    pub floors_ascending: Option<u32>,
+    /// Floors descended.
+    pub floors_descending: Option<u32>,
+    /// Current pace in seconds per meter.
+    pub current_pace: Option<f64>,
+}
+
+/// Walking speed in meters per second for a cadence of `steps_per_second`
+/// and a stride of `stride_length_meters`.
+///
+/// Negative or non-finite inputs give `0.0`.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::walking_speed_estimate;
+///
+/// assert!((walking_speed_estimate(2.0, 0.7) - 1.4).abs() < 1e-9);
+/// ```
+pub fn walking_speed_estimate(steps_per_second: f64, stride_length_meters: f64) -> f64 {
+    let speed = steps_per_second * stride_length_meters;
+    if steps_per_second >= 0.0 && stride_length_meters >= 0.0 && speed.is_finite() {
+        speed
+    } else {
+        0.0
+    }
+}
+
+/// Typical walking stride length for a person `height_meters` tall, using
+/// the common `0.415 × height` estimate.
+pub fn default_stride_length(height_meters: f64) -> f64 {
+    0.415 * height_meters.max(0.0)
+}
+
+/// Platforms without a pedometer API
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod platform {
+    use super::PedometerCallback;
+    use crate::{Error, Result};
+
+    pub struct PedometerWatch;
+
+    pub fn watch_pedometer(_callback: PedometerCallback) -> Result<PedometerWatch> {
+        Err(Error::PermanentlyUnavailable)
+    }
+}
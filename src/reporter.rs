@@ -0,0 +1,294 @@
+//! Periodic location reports to an HTTP endpoint
+//!
+//! Reports are posted from a background thread on native platforms and from
+//! the browser's event loop on the web, so the watch callback never waits
+//! for the network.
+
+use crate::timestamp::to_millis;
+use crate::watch::{LocationCallback, WatchHandle};
+use crate::{now, Location, LocationOptions, Result};
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
+
+/// Most reports kept while the endpoint can't be reached. When the queue is
+/// full, the oldest report is dropped.
+pub const MAX_PENDING_REPORTS: usize = 100;
+
+/// Posts the device's location to an HTTP endpoint at most once per
+/// `interval`.
+///
+/// Each report is a JSON object sent with `POST`:
+///
+/// ```json
+/// {
+///   "device_id": "van-7",
+///   "latitude": 52.52,
+///   "longitude": 13.405,
+///   "accuracy": 5.0,
+///   "timestamp": 1714564800000
+/// }
+/// ```
+///
+/// `accuracy` is the horizontal accuracy in meters, or `null` if unknown.
+/// `timestamp` is in milliseconds since the Unix epoch, taken from the fix
+/// or, if the platform reports none, from when it arrived.
+///
+/// Reports the endpoint doesn't accept with a success status, e.g. while
+/// offline, stay queued and are retried in order whenever a new report is
+/// due, up to [`MAX_PENDING_REPORTS`]. On native platforms they are also
+/// retried every 30 seconds.
+///
+/// ```rust,no_run
+/// use dioxus_mobile_geolocation::LocationReporter;
+/// use std::time::Duration;
+///
+/// let reporter = LocationReporter {
+///     endpoint: "https://fleet.example.com/locations".to_string(),
+///     interval: Duration::from_secs(30),
+///     device_id: "van-7".to_string(),
+/// };
+/// let handle = reporter.start();
+/// # drop(handle);
+/// ```
+///
+/// ## Privacy
+///
+/// This shares the device's location with the server at `endpoint` for as
+/// long as the watch runs. Ask for consent first and only report to a
+/// server you trust.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocationReporter {
+    /// URL the reports are posted to.
+    pub endpoint: String,
+    /// Minimum time between two reports.
+    pub interval: Duration,
+    /// Identifier sent with every report, so the server can tell devices
+    /// apart.
+    pub device_id: String,
+}
+
+impl LocationReporter {
+    /// Watch the location and report it until the handle is dropped.
+    ///
+    /// Uses [`LocationOptions::default`]. Errors aren't reported, and
+    /// reports still queued when the handle is dropped are discarded. See
+    /// [`watch_position`](crate::watch_position) for platform behavior.
+    pub fn start(self) -> WatchHandle {
+        WatchHandle::new(&LocationOptions::default(), (), report_callback(self))
+    }
+}
+
+/// A location callback queueing a report for every fix at least
+/// `interval` after the previous report
+fn report_callback(reporter: LocationReporter) -> LocationCallback {
+    let LocationReporter {
+        endpoint,
+        interval,
+        device_id,
+    } = reporter;
+    let sender = ReportSender::new(endpoint);
+    let last_report = Mutex::new(None::<SystemTime>);
+
+    Arc::new(move |result: Result<Location>| {
+        let Ok(location) = result else {
+            return;
+        };
+        let now = now();
+        {
+            let mut last_report = last_report.lock().unwrap_or_else(PoisonError::into_inner);
+            let too_soon = last_report
+                .is_some_and(|last| now.duration_since(last).unwrap_or_default() < interval);
+            if too_soon {
+                return;
+            }
+            *last_report = Some(now);
+        }
+        sender.send(report(&device_id, &location, now));
+    })
+}
+
+/// The JSON body of a report
+fn report(device_id: &str, location: &Location, received: SystemTime) -> Value {
+    json!({
+        "device_id": device_id,
+        "latitude": location.latitude,
+        "longitude": location.longitude,
+        "accuracy": location.horizontal_accuracy,
+        "timestamp": to_millis(location.timestamp.unwrap_or(received)),
+    })
+}
+
+/// Reports not yet accepted by the endpoint, oldest first
+#[derive(Default)]
+struct Queue(VecDeque<Value>);
+
+impl Queue {
+    fn push(&mut self, report: Value) {
+        if self.0.len() == MAX_PENDING_REPORTS {
+            self.0.pop_front();
+        }
+        self.0.push_back(report);
+    }
+}
+
+/// How long the posting thread waits before retrying reports the endpoint
+/// didn't accept
+#[cfg(not(target_arch = "wasm32"))]
+const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Queues reports for a thread that posts them, which exits once the watch
+/// drops the sender
+#[cfg(not(target_arch = "wasm32"))]
+struct ReportSender {
+    queue: Arc<Mutex<Queue>>,
+    /// Wakes the thread. Holds at most one wake-up, since the thread posts
+    /// everything queued whenever it wakes.
+    wake: std::sync::mpsc::SyncSender<()>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ReportSender {
+    fn new(endpoint: String) -> Self {
+        let queue = Arc::<Mutex<Queue>>::default();
+        let (wake, woken) = std::sync::mpsc::sync_channel::<()>(1);
+        std::thread::spawn({
+            let queue = queue.clone();
+            move || {
+                let client = reqwest::blocking::Client::new();
+                let mut posted = true;
+                loop {
+                    // Reports the endpoint didn't accept are retried after a
+                    // while even if no new report arrives
+                    let next = if posted {
+                        woken.recv().map_err(RecvTimeoutError::from)
+                    } else {
+                        woken.recv_timeout(RETRY_INTERVAL)
+                    };
+                    if let Err(RecvTimeoutError::Disconnected) = next {
+                        return;
+                    }
+                    posted = post_queued(&client, &endpoint, &queue);
+                }
+            }
+        });
+        Self { queue, wake }
+    }
+
+    fn send(&self, report: Value) {
+        self.queue
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(report);
+        // A full channel already holds a wake-up
+        let _ = self.wake.try_send(());
+    }
+}
+
+/// Post the queued reports in order, returning whether the endpoint accepted
+/// all of them
+#[cfg(not(target_arch = "wasm32"))]
+fn post_queued(client: &reqwest::blocking::Client, endpoint: &str, queue: &Mutex<Queue>) -> bool {
+    loop {
+        let report = {
+            let queue = queue.lock().unwrap_or_else(PoisonError::into_inner);
+            match queue.0.front() {
+                Some(report) => report.clone(),
+                None => return true,
+            }
+        };
+
+        let sent = client
+            .post(endpoint)
+            .header(reqwest::header::USER_AGENT, USER_AGENT)
+            .json(&report)
+            .send()
+            .and_then(|response| response.error_for_status());
+        // Keep the report and retry later
+        if sent.is_err() {
+            return false;
+        }
+
+        let mut queue = queue.lock().unwrap_or_else(PoisonError::into_inner);
+        // A full queue may have dropped the report in the meantime
+        if queue.0.front() == Some(&report) {
+            queue.0.pop_front();
+        }
+    }
+}
+
+/// Posts reports from the browser's event loop, one request at a time
+#[cfg(target_arch = "wasm32")]
+struct ReportSender {
+    endpoint: Arc<str>,
+    state: Arc<Mutex<SenderState>>,
+}
+
+#[cfg(target_arch = "wasm32")]
+#[derive(Default)]
+struct SenderState {
+    queue: Queue,
+    /// Whether a task is posting the queued reports
+    posting: bool,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl ReportSender {
+    fn new(endpoint: String) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            state: Arc::default(),
+        }
+    }
+
+    fn send(&self, report: Value) {
+        {
+            let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            state.queue.push(report);
+            if std::mem::replace(&mut state.posting, true) {
+                return;
+            }
+        }
+
+        let endpoint = self.endpoint.clone();
+        let state = self.state.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let client = reqwest::Client::new();
+            loop {
+                let report = {
+                    let mut state = state.lock().unwrap_or_else(PoisonError::into_inner);
+                    match state.queue.0.front() {
+                        Some(report) => report.clone(),
+                        None => {
+                            state.posting = false;
+                            return;
+                        }
+                    }
+                };
+
+                // Browsers send their own user agent header
+                let sent = match client.post(&*endpoint).json(&report).send().await {
+                    Ok(response) => response.error_for_status().is_ok(),
+                    Err(_) => false,
+                };
+
+                let mut state = state.lock().unwrap_or_else(PoisonError::into_inner);
+                // Keep the report and retry with the next one
+                if !sent {
+                    state.posting = false;
+                    return;
+                }
+                // A full queue may have dropped the report in the meantime
+                if state.queue.0.front() == Some(&report) {
+                    state.queue.0.pop_front();
+                }
+            }
+        });
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
@@ -0,0 +1,242 @@
+//! S2 geometry cell identifiers
+//!
+//! Implements the cell ID encoding used by Google's S2 library: points are
+//! projected onto the six faces of a cube, mapped through S2's quadratic
+//! projection and ordered along a Hilbert curve. The resulting 64-bit IDs are
+//! compatible with other S2 implementations (BigQuery GIS, s2geometry, etc).
+
+use crate::Coordinates;
+use std::sync::OnceLock;
+
+/// Deepest level supported by S2 cell IDs.
+pub const MAX_LEVEL: u8 = 30;
+
+const LOOKUP_BITS: u32 = 4;
+const SWAP_MASK: usize = 0x01;
+const INVERT_MASK: usize = 0x02;
+const POS_BITS: u32 = 2 * MAX_LEVEL as u32 + 1;
+const MAX_SIZE: u32 = 1 << MAX_LEVEL;
+const MAX_SI_TI: f64 = (1u64 << (MAX_LEVEL + 1)) as f64;
+
+/// Hilbert curve sub-cell positions for each orientation.
+const POS_TO_IJ: [[usize; 4]; 4] = [[0, 1, 3, 2], [0, 2, 3, 1], [3, 2, 0, 1], [3, 1, 0, 2]];
+const POS_TO_ORIENTATION: [usize; 4] = [SWAP_MASK, 0, 0, INVERT_MASK | SWAP_MASK];
+
+struct LookupTables {
+    pos: [u16; 1 << (2 * LOOKUP_BITS + 2)],
+    ij: [u16; 1 << (2 * LOOKUP_BITS + 2)],
+}
+
+fn lookup_tables() -> &'static LookupTables {
+    static TABLES: OnceLock<LookupTables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let mut tables = LookupTables {
+            pos: [0; 1 << (2 * LOOKUP_BITS + 2)],
+            ij: [0; 1 << (2 * LOOKUP_BITS + 2)],
+        };
+        for orientation in [0, SWAP_MASK, INVERT_MASK, SWAP_MASK | INVERT_MASK] {
+            init_lookup_cell(&mut tables, 0, 0, 0, orientation, 0, orientation);
+        }
+        tables
+    })
+}
+
+fn init_lookup_cell(
+    tables: &mut LookupTables,
+    level: u32,
+    i: usize,
+    j: usize,
+    orig_orientation: usize,
+    pos: usize,
+    orientation: usize,
+) {
+    if level == LOOKUP_BITS {
+        let ij = (i << LOOKUP_BITS) + j;
+        tables.pos[(ij << 2) + orig_orientation] = ((pos << 2) + orientation) as u16;
+        tables.ij[(pos << 2) + orig_orientation] = ((ij << 2) + orientation) as u16;
+        return;
+    }
+
+    let r = POS_TO_IJ[orientation];
+    for (index, sub) in r.iter().enumerate() {
+        init_lookup_cell(
+            tables,
+            level + 1,
+            (i << 1) + (sub >> 1),
+            (j << 1) + (sub & 1),
+            orig_orientation,
+            (pos << 2) + index,
+            orientation ^ POS_TO_ORIENTATION[index],
+        );
+    }
+}
+
+impl Coordinates {
+    /// Encode these coordinates as an S2 cell ID at the given `level` (0-30).
+    ///
+    /// Levels above 30 are clamped to 30 (leaf cells, roughly 1cm²).
+    ///
+    /// ```rust
+    /// use dioxus_mobile_geolocation::Coordinates;
+    ///
+    /// let origin = Coordinates { latitude: 0.0, longitude: 0.0 };
+    /// assert_eq!(origin.to_s2_cell_id(30), 0x1000000000000001);
+    /// ```
+    pub fn to_s2_cell_id(&self, level: u8) -> u64 {
+        let (face, u, v) = xyz_to_face_uv(lat_lng_to_xyz(self.latitude, self.longitude));
+        let i = st_to_ij(uv_to_st(u));
+        let j = st_to_ij(uv_to_st(v));
+        let leaf = face_ij_to_cell_id(face, i, j);
+        cell_id_parent(leaf, level.min(MAX_LEVEL))
+    }
+}
+
+/// Decode an S2 cell ID, returning the centre of its ancestor cell at `level`.
+///
+/// Returns `None` if `cell_id` is not a valid S2 cell ID or `level` is deeper
+/// than the cell itself.
+pub fn s2_cell_to_coordinates(cell_id: u64, level: u8) -> Option<Coordinates> {
+    if !is_valid_cell_id(cell_id) || level > cell_id_level(cell_id) {
+        return None;
+    }
+
+    let id = cell_id_parent(cell_id, level);
+    let (face, i, j) = cell_id_to_face_ij(id);
+
+    // Non-leaf cells encode (i, j) of a leaf next to the centre; `delta`
+    // moves it onto the exact centre in (si, ti) space.
+    let delta = if level == MAX_LEVEL {
+        1
+    } else if ((i as u64 ^ (id >> 2)) & 1) != 0 {
+        2
+    } else {
+        0
+    };
+    let s = (2 * i as u64 + delta) as f64 / MAX_SI_TI;
+    let t = (2 * j as u64 + delta) as f64 / MAX_SI_TI;
+
+    let (x, y, z) = face_uv_to_xyz(face, st_to_uv(s), st_to_uv(t));
+    let latitude = z.atan2((x * x + y * y).sqrt()).to_degrees();
+    let longitude = y.atan2(x).to_degrees();
+    Some(Coordinates {
+        latitude,
+        longitude,
+    })
+}
+
+fn is_valid_cell_id(id: u64) -> bool {
+    let lsb = id & id.wrapping_neg();
+    (id >> POS_BITS) < 6 && (lsb & 0x1555555555555555) != 0
+}
+
+fn cell_id_level(id: u64) -> u8 {
+    MAX_LEVEL - (id.trailing_zeros() / 2) as u8
+}
+
+fn cell_id_parent(id: u64, level: u8) -> u64 {
+    let lsb = 1u64 << (2 * (MAX_LEVEL - level) as u32);
+    (id & lsb.wrapping_neg()) | lsb
+}
+
+fn lat_lng_to_xyz(latitude: f64, longitude: f64) -> (f64, f64, f64) {
+    let (phi, theta) = (latitude.to_radians(), longitude.to_radians());
+    (phi.cos() * theta.cos(), phi.cos() * theta.sin(), phi.sin())
+}
+
+fn xyz_to_face_uv((x, y, z): (f64, f64, f64)) -> (u8, f64, f64) {
+    let (ax, ay, az) = (x.abs(), y.abs(), z.abs());
+    let mut face = if ax > ay {
+        if ax > az {
+            0
+        } else {
+            2
+        }
+    } else if ay > az {
+        1
+    } else {
+        2
+    };
+    if [x, y, z][face as usize] < 0.0 {
+        face += 3;
+    }
+
+    let (u, v) = match face {
+        0 => (y / x, z / x),
+        1 => (-x / y, z / y),
+        2 => (-x / z, -y / z),
+        3 => (z / x, y / x),
+        4 => (z / y, -x / y),
+        _ => (-y / z, -x / z),
+    };
+    (face, u, v)
+}
+
+fn face_uv_to_xyz(face: u8, u: f64, v: f64) -> (f64, f64, f64) {
+    match face {
+        0 => (1.0, u, v),
+        1 => (-u, 1.0, v),
+        2 => (-u, -v, 1.0),
+        3 => (-1.0, -v, -u),
+        4 => (v, -1.0, -u),
+        _ => (v, u, -1.0),
+    }
+}
+
+fn uv_to_st(u: f64) -> f64 {
+    if u >= 0.0 {
+        0.5 * (1.0 + 3.0 * u).sqrt()
+    } else {
+        1.0 - 0.5 * (1.0 - 3.0 * u).sqrt()
+    }
+}
+
+fn st_to_uv(s: f64) -> f64 {
+    if s >= 0.5 {
+        (1.0 / 3.0) * (4.0 * s * s - 1.0)
+    } else {
+        (1.0 / 3.0) * (1.0 - 4.0 * (1.0 - s) * (1.0 - s))
+    }
+}
+
+fn st_to_ij(s: f64) -> u32 {
+    ((MAX_SIZE as f64 * s).floor() as i64).clamp(0, MAX_SIZE as i64 - 1) as u32
+}
+
+fn face_ij_to_cell_id(face: u8, i: u32, j: u32) -> u64 {
+    let tables = lookup_tables();
+    let mask = (1u32 << LOOKUP_BITS) - 1;
+    let mut n = (face as u64) << (POS_BITS - 1);
+    let mut bits = face as usize & SWAP_MASK;
+
+    for k in (0..8).rev() {
+        bits += (((i >> (k * LOOKUP_BITS)) & mask) as usize) << (LOOKUP_BITS + 2);
+        bits += (((j >> (k * LOOKUP_BITS)) & mask) as usize) << 2;
+        bits = tables.pos[bits] as usize;
+        n |= ((bits >> 2) as u64) << (k * 2 * LOOKUP_BITS);
+        bits &= SWAP_MASK | INVERT_MASK;
+    }
+
+    n * 2 + 1
+}
+
+fn cell_id_to_face_ij(id: u64) -> (u8, u32, u32) {
+    let tables = lookup_tables();
+    let face = (id >> POS_BITS) as u8;
+    let (mut i, mut j) = (0u32, 0u32);
+    let mut bits = face as usize & SWAP_MASK;
+
+    for k in (0..8).rev() {
+        let nbits = if k == 7 {
+            MAX_LEVEL as u32 - 7 * LOOKUP_BITS
+        } else {
+            LOOKUP_BITS
+        };
+        bits += (((id >> (k * 2 * LOOKUP_BITS + 1)) & ((1 << (2 * nbits)) - 1)) as usize) << 2;
+        bits = tables.ij[bits] as usize;
+        i += ((bits >> (LOOKUP_BITS + 2)) as u32) << (k * LOOKUP_BITS);
+        j += (((bits >> 2) & ((1 << LOOKUP_BITS) - 1)) as u32) << (k * LOOKUP_BITS);
+        bits &= SWAP_MASK | INVERT_MASK;
+    }
+
+    (face, i, j)
+}
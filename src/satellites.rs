@@ -0,0 +1,53 @@
+//! GNSS satellite status
+//!
+//! Only Android reports the satellites in view (`GnssStatus`); elsewhere
+//! [`watch_gps_status`](crate::watch_gps_status) reports none.
+
+use crate::Result;
+use std::sync::Arc;
+
+#[cfg(target_os = "android")]
+use crate::android as platform;
+
+/// Callback invoked by a platform satellite watch with the satellites of
+/// each status update.
+pub(crate) type SatelliteCallback = Arc<dyn Fn(Vec<GpsSatellite>) + Send + Sync>;
+
+/// A running platform satellite watch. Updates stop when it is dropped.
+pub(crate) type SatelliteWatch = platform::SatelliteWatch;
+
+/// Start satellite status updates, calling `callback` for each status.
+pub(crate) fn watch(callback: SatelliteCallback) -> Result<SatelliteWatch> {
+    platform::watch_satellites(callback)
+}
+
+/// A GNSS satellite in view, as passed to
+/// [`watch_gps_status`](crate::watch_gps_status).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GpsSatellite {
+    /// Identifier of the satellite within its constellation, e.g. the PRN
+    /// code for GPS.
+    pub prn: u8,
+    /// Signal strength (carrier-to-noise density) in dB-Hz.
+    pub snr: f32,
+    /// Direction of the satellite in degrees clockwise from north.
+    pub azimuth: f32,
+    /// Height of the satellite above the horizon in degrees.
+    pub elevation: f32,
+    /// Whether the satellite was used in the most recent fix.
+    pub used_in_fix: bool,
+}
+
+/// Platforms without satellite status
+#[cfg(not(target_os = "android"))]
+mod platform {
+    use super::SatelliteCallback;
+    use crate::{Error, Result};
+
+    pub struct SatelliteWatch;
+
+    pub fn watch_satellites(_callback: SatelliteCallback) -> Result<SatelliteWatch> {
+        Err(Error::PermanentlyUnavailable)
+    }
+}
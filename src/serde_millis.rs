@@ -0,0 +1,49 @@
+//! `SystemTime` as milliseconds since the Unix epoch
+//!
+//! serde's own representation is a `{ secs_since_epoch, nanos_since_epoch }`
+//! map, which is awkward to store in a database column or read from
+//! JavaScript. Use with `#[serde(with = "serde_millis")]`; times before the
+//! epoch are negative, and anything below a millisecond is dropped.
+
+use crate::timestamp::{from_millis, to_millis};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::time::SystemTime;
+
+pub fn serialize<S: Serializer>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error> {
+    to_millis(*time).serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SystemTime, D::Error> {
+    let millis = i64::deserialize(deserializer)?;
+    from_millis(millis).ok_or_else(|| D::Error::custom(OUT_OF_RANGE))
+}
+
+const OUT_OF_RANGE: &str = "timestamp out of range";
+
+/// The same for `Option<SystemTime>`, with `None` as `null`
+pub mod option {
+    use super::OUT_OF_RANGE;
+    use crate::timestamp::{from_millis, to_millis};
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::time::SystemTime;
+
+    pub fn serialize<S: Serializer>(
+        time: &Option<SystemTime>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        time.map(to_millis).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<SystemTime>, D::Error> {
+        match Option::<i64>::deserialize(deserializer)? {
+            Some(millis) => from_millis(millis)
+                .map(Some)
+                .ok_or_else(|| D::Error::custom(OUT_OF_RANGE)),
+            None => Ok(None),
+        }
+    }
+}
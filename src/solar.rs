@@ -0,0 +1,105 @@
+//! Solar position
+//!
+//! Implements the NOAA solar position algorithm, accurate to about 0.01° for
+//! dates between 1800 and 2100. Useful for solar energy estimates and
+//! photography "golden hour" tools.
+
+use crate::Coordinates;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+impl Coordinates {
+    /// Position of the sun as seen from these coordinates at `datetime`.
+    ///
+    /// Returns `(azimuth, elevation)` in degrees. Azimuth is measured
+    /// clockwise from true north; elevation is the apparent angle above the
+    /// horizon including atmospheric refraction, negative when the sun has
+    /// set.
+    pub fn to_sun_position(&self, datetime: SystemTime) -> (f64, f64) {
+        let unix_seconds = match datetime.duration_since(UNIX_EPOCH) {
+            Ok(since) => since.as_secs_f64(),
+            Err(before) => -before.duration().as_secs_f64(),
+        };
+        let julian_day = unix_seconds / 86_400.0 + 2_440_587.5;
+        let jc = (julian_day - 2_451_545.0) / 36_525.0;
+
+        let mean_longitude = (280.46646 + jc * (36000.76983 + jc * 0.0003032)).rem_euclid(360.0);
+        let mean_anomaly = 357.52911 + jc * (35999.05029 - 0.0001537 * jc);
+        let eccentricity = 0.016708634 - jc * (0.000042037 + 0.0000001267 * jc);
+
+        let m = mean_anomaly.to_radians();
+        let center = m.sin() * (1.914602 - jc * (0.004817 + 0.000014 * jc))
+            + (2.0 * m).sin() * (0.019993 - 0.000101 * jc)
+            + (3.0 * m).sin() * 0.000289;
+        let omega = (125.04 - 1934.136 * jc).to_radians();
+        let apparent_longitude =
+            (mean_longitude + center - 0.00569 - 0.00478 * omega.sin()).to_radians();
+
+        let mean_obliquity =
+            23.0 + (26.0 + (21.448 - jc * (46.815 + jc * (0.00059 - jc * 0.001813))) / 60.0) / 60.0;
+        let obliquity = (mean_obliquity + 0.00256 * omega.cos()).to_radians();
+        let declination = (obliquity.sin() * apparent_longitude.sin()).asin();
+
+        let l = mean_longitude.to_radians();
+        let y = (obliquity / 2.0).tan().powi(2);
+        let equation_of_time = 4.0
+            * (y * (2.0 * l).sin() - 2.0 * eccentricity * m.sin()
+                + 4.0 * eccentricity * y * m.sin() * (2.0 * l).cos()
+                - 0.5 * y * y * (4.0 * l).sin()
+                - 1.25 * eccentricity * eccentricity * (2.0 * m).sin())
+            .to_degrees();
+
+        let utc_minutes = unix_seconds.rem_euclid(86_400.0) / 60.0;
+        let true_solar_time =
+            (utc_minutes + equation_of_time + 4.0 * self.longitude).rem_euclid(1440.0);
+        let hour_angle = if true_solar_time < 0.0 {
+            true_solar_time / 4.0 + 180.0
+        } else {
+            true_solar_time / 4.0 - 180.0
+        };
+
+        let latitude = self.latitude.to_radians();
+        let cos_zenith = (latitude.sin() * declination.sin()
+            + latitude.cos() * declination.cos() * hour_angle.to_radians().cos())
+        .clamp(-1.0, 1.0);
+        let zenith = cos_zenith.acos();
+
+        let azimuth_denominator = latitude.cos() * zenith.sin();
+        let azimuth = if azimuth_denominator.abs() < 1e-12 {
+            // Sun at the zenith or observer at a pole: azimuth is undefined.
+            if latitude > 0.0 {
+                180.0
+            } else {
+                0.0
+            }
+        } else {
+            let cos_azimuth = ((latitude.sin() * zenith.cos() - declination.sin())
+                / azimuth_denominator)
+                .clamp(-1.0, 1.0);
+            let angle = cos_azimuth.acos().to_degrees();
+            if hour_angle > 0.0 {
+                (angle + 180.0).rem_euclid(360.0)
+            } else {
+                (540.0 - angle).rem_euclid(360.0)
+            }
+        };
+
+        let elevation = 90.0 - zenith.to_degrees();
+        (azimuth, elevation + atmospheric_refraction(elevation))
+    }
+}
+
+/// Approximate atmospheric refraction in degrees for a geometric elevation.
+fn atmospheric_refraction(elevation: f64) -> f64 {
+    let tan_e = elevation.to_radians().tan();
+    let arc_seconds = if elevation > 85.0 {
+        0.0
+    } else if elevation > 5.0 {
+        58.1 / tan_e - 0.07 / tan_e.powi(3) + 0.000086 / tan_e.powi(5)
+    } else if elevation > -0.575 {
+        1735.0
+            + elevation * (-518.2 + elevation * (103.4 + elevation * (-12.79 + elevation * 0.711)))
+    } else {
+        -20.772 / tan_e
+    };
+    arc_seconds / 3600.0
+}
@@ -0,0 +1,189 @@
+//! XYZ map tile math
+//!
+//! Helpers for the Web Mercator "slippy map" tiling scheme used by
+//! OpenStreetMap, Mapbox, Google Maps and most other tile servers.
+
+use crate::geo_math::{CoordinateBounds, Polyline};
+use crate::Coordinates;
+use std::f64::consts::PI;
+use std::ops::RangeInclusive;
+
+/// Latitude limit of the Web Mercator projection.
+const MAX_MERCATOR_LATITUDE: f64 = 85.051_128_779_806_59;
+
+/// Deepest zoom level whose tile columns and rows fit in a `u32`.
+const MAX_ZOOM: u8 = 31;
+
+/// Expand `tile_url_template` for every tile covering `bounds` at each zoom
+/// level in `zoom_range`.
+///
+/// The template's `{z}`, `{x}` and `{y}` placeholders are replaced, e.g.
+/// `"https://tile.openstreetmap.org/{z}/{x}/{y}.png"`. At most `max_tiles`
+/// URLs are returned, lowest zoom levels first, so a large region or zoom
+/// range can't trigger an unbounded prefetch. Zoom levels above 31 are
+/// skipped.
+pub fn tile_urls_for_bounds(
+    bounds: &CoordinateBounds,
+    zoom_range: RangeInclusive<u8>,
+    tile_url_template: &str,
+    max_tiles: usize,
+) -> Vec<String> {
+    let mut urls = Vec::new();
+    let zoom_range = *zoom_range.start()..=(*zoom_range.end()).min(MAX_ZOOM);
+
+    for zoom in zoom_range {
+        let (x_min, y_min) = tile_xy(bounds.north, bounds.west, zoom);
+        let (x_max, y_max) = tile_xy(bounds.south, bounds.east, zoom);
+
+        // Boxes crossing the antimeridian wrap around the last tile column.
+        let n = u64::from(tile_count(zoom));
+        let (x_min, x_max) = (u64::from(x_min), u64::from(x_max));
+        let width = (x_max + n - x_min) % n + 1;
+        let columns = (x_min..x_min + width).map(|x| (x % n) as u32);
+
+        for x in columns {
+            for y in y_min..=y_max {
+                if urls.len() >= max_tiles {
+                    return urls;
+                }
+                urls.push(expand_template(tile_url_template, x, y, zoom));
+            }
+        }
+    }
+
+    urls
+}
+
+/// `(x, y, zoom)` of every tile at `zoom` in the tile range covering
+/// `route`, widened by `padding_tiles` on each side.
+///
+/// Use it to prefetch the tiles along a route for offline use. The range is
+/// the rectangle of tiles around the route's bounding box, so a diagonal
+/// route includes tiles that don't touch it. Padding wraps around the
+/// antimeridian and stops at the first and last tile rows. An empty route,
+/// or a `zoom` above 31, covers no tiles.
+///
+/// At most `max_tiles` tiles are returned, column by column from the west,
+/// so a long route at a deep zoom can't trigger an unbounded prefetch.
+///
+/// ```rust
+/// use dioxus_mobile_geolocation::geo_math::Polyline;
+/// use dioxus_mobile_geolocation::{tile_coverage_for_polyline, Coordinates};
+///
+/// let route = Polyline::new(vec![
+///     Coordinates::new(52.5200, 13.4050),
+///     Coordinates::new(52.5163, 13.2800),
+/// ]);
+/// assert_eq!(
+///     tile_coverage_for_polyline(&route, 12, 0, 100),
+///     [(2199, 1343, 12), (2200, 1343, 12)]
+/// );
+/// assert_eq!(tile_coverage_for_polyline(&route, 12, 1, 100).len(), 4 * 3);
+/// assert_eq!(tile_coverage_for_polyline(&route, 12, 1, 5).len(), 5);
+/// ```
+pub fn tile_coverage_for_polyline(
+    route: &Polyline,
+    zoom: u8,
+    padding_tiles: u8,
+    max_tiles: usize,
+) -> Vec<(u32, u32, u8)> {
+    let Some(bounds) = route.bounding_box().filter(|_| zoom <= MAX_ZOOM) else {
+        return Vec::new();
+    };
+    let (x_min, y_min) = tile_xy(bounds.north, bounds.west, zoom);
+    let (x_max, y_max) = tile_xy(bounds.south, bounds.east, zoom);
+    let last_row = tile_count(zoom) - 1;
+    let rows = y_min.saturating_sub(u32::from(padding_tiles))
+        ..=(y_max + u32::from(padding_tiles)).min(last_row);
+
+    // Count the columns modulo `n` so routes crossing the antimeridian wrap
+    let n = u64::from(tile_count(zoom));
+    let padding = u64::from(padding_tiles);
+    let (x_min, x_max) = (u64::from(x_min), u64::from(x_max));
+    let width = ((x_max + n - x_min) % n + 1 + 2 * padding).min(n);
+    let first_column = (x_min + n - padding % n) % n;
+
+    let mut tiles = Vec::new();
+    for column in 0..width {
+        let x = ((first_column + column) % n) as u32;
+        for y in rows.clone() {
+            if tiles.len() >= max_tiles {
+                return tiles;
+            }
+            tiles.push((x, y, zoom));
+        }
+    }
+    tiles
+}
+
+impl Coordinates {
+    /// Bounds of the XYZ tile containing these coordinates at `zoom`.
+    ///
+    /// Latitudes beyond the Web Mercator limit of ±85.05° fall in the first
+    /// or last tile row.
+    ///
+    /// ```rust
+    /// use dioxus_mobile_geolocation::Coordinates;
+    ///
+    /// let bounds = Coordinates::new(48.8584, 2.2945).to_tile_bounds(1);
+    /// assert_eq!((bounds.west, bounds.east), (0.0, 180.0));
+    /// assert_eq!(bounds.south, 0.0);
+    /// ```
+    pub fn to_tile_bounds(&self, zoom: u8) -> CoordinateBounds {
+        let (x, y) = tile_xy(self.latitude, self.longitude, zoom);
+        CoordinateBounds::from_tile_xyz(x, y, zoom)
+    }
+}
+
+impl CoordinateBounds {
+    /// Bounds of the XYZ tile at column `x` and row `y` of `zoom`.
+    ///
+    /// Columns and rows past the last tile are clamped to it.
+    pub fn from_tile_xyz(x: u32, y: u32, zoom: u8) -> CoordinateBounds {
+        let n = tile_count(zoom);
+        let (x, y) = (x.min(n - 1), y.min(n - 1));
+        CoordinateBounds {
+            north: tile_row_latitude(y, n),
+            south: tile_row_latitude(y + 1, n),
+            east: tile_column_longitude(x + 1, n),
+            west: tile_column_longitude(x, n),
+        }
+    }
+}
+
+/// Number of tiles along each axis at `zoom`.
+fn tile_count(zoom: u8) -> u32 {
+    1u32 << zoom.min(MAX_ZOOM)
+}
+
+/// Tile column and row containing the given point at `zoom`.
+fn tile_xy(latitude: f64, longitude: f64, zoom: u8) -> (u32, u32) {
+    let n = tile_count(zoom) as f64;
+    let latitude = latitude
+        .clamp(-MAX_MERCATOR_LATITUDE, MAX_MERCATOR_LATITUDE)
+        .to_radians();
+    let x = ((longitude + 180.0) / 360.0 * n).floor();
+    let y = ((1.0 - latitude.tan().asinh() / PI) / 2.0 * n).floor();
+    let max = n - 1.0;
+    (x.clamp(0.0, max) as u32, y.clamp(0.0, max) as u32)
+}
+
+/// Longitude of the western edge of tile column `x` out of `n`.
+fn tile_column_longitude(x: u32, n: u32) -> f64 {
+    f64::from(x) / f64::from(n) * 360.0 - 180.0
+}
+
+/// Latitude of the northern edge of tile row `y` out of `n`.
+fn tile_row_latitude(y: u32, n: u32) -> f64 {
+    (PI * (1.0 - 2.0 * f64::from(y) / f64::from(n)))
+        .sinh()
+        .atan()
+        .to_degrees()
+}
+
+fn expand_template(template: &str, x: u32, y: u32, zoom: u8) -> String {
+    template
+        .replace("{z}", &zoom.to_string())
+        .replace("{x}", &x.to_string())
+        .replace("{y}", &y.to_string())
+}
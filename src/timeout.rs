@@ -0,0 +1,135 @@
+//! Timeouts for single location requests
+//!
+//! The crate doesn't depend on an async runtime, so there is no timer future
+//! to await. Instead one thread, started with the first timeout, serves the
+//! timeouts of every request. Browsers time out requests themselves.
+
+use crate::watch::LocationCallback;
+use crate::Error;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Condvar, Mutex, MutexGuard, Once, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Pending timeouts by deadline and id, with the callback each one calls
+type Timeouts = BTreeMap<(Instant, u64), LocationCallback>;
+
+static TIMEOUTS: Mutex<Timeouts> = Mutex::new(BTreeMap::new());
+/// Wakes the timer thread when a timeout is added
+static TIMEOUT_ADDED: Condvar = Condvar::new();
+static NEXT_TIMEOUT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A running timeout. Dropping it cancels the timeout.
+pub(crate) struct Timeout(Option<(Instant, u64)>);
+
+impl Drop for Timeout {
+    fn drop(&mut self) {
+        if let Some(key) = self.0 {
+            lock_timeouts().remove(&key);
+        }
+    }
+}
+
+/// Call `callback` with [`Error::TemporarilyUnavailable`] after `timeout`,
+/// unless the returned [`Timeout`] is dropped first.
+pub(crate) fn start_timeout(timeout: Duration, callback: LocationCallback) -> Timeout {
+    static TIMER: Once = Once::new();
+    TIMER.call_once(|| {
+        std::thread::spawn(run_timeouts);
+    });
+
+    // A timeout too long to represent never fires
+    let Some(deadline) = Instant::now().checked_add(timeout) else {
+        return Timeout(None);
+    };
+    let key = (deadline, NEXT_TIMEOUT_ID.fetch_add(1, Ordering::Relaxed));
+    lock_timeouts().insert(key, callback);
+    TIMEOUT_ADDED.notify_one();
+    Timeout(Some(key))
+}
+
+fn lock_timeouts() -> MutexGuard<'static, Timeouts> {
+    TIMEOUTS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Body of the timer thread: call each callback once its deadline passes
+fn run_timeouts() {
+    let mut timeouts = lock_timeouts();
+    loop {
+        let now = Instant::now();
+        let next = timeouts
+            .first_key_value()
+            .map(|(&(deadline, _), _)| deadline);
+        timeouts = match next {
+            Some(deadline) if deadline <= now => {
+                let due = timeouts.pop_first();
+                // The callback may start or cancel other timeouts
+                drop(timeouts);
+                if let Some((_, callback)) = due {
+                    callback(Err(Error::TemporarilyUnavailable));
+                }
+                lock_timeouts()
+            }
+            Some(deadline) => {
+                let (timeouts, _) = TIMEOUT_ADDED
+                    .wait_timeout(timeouts, deadline - now)
+                    .unwrap_or_else(PoisonError::into_inner);
+                timeouts
+            }
+            None => TIMEOUT_ADDED
+                .wait(timeouts)
+                .unwrap_or_else(PoisonError::into_inner),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::sync::Arc;
+
+    /// Start a timeout that sends its result to the returned receiver
+    fn timeout_after(millis: u64) -> (Timeout, mpsc::Receiver<crate::Result<crate::Location>>) {
+        let (sender, receiver) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        let callback: LocationCallback = Arc::new(move |result| {
+            let _ = sender.lock().unwrap().send(result);
+        });
+        (
+            start_timeout(Duration::from_millis(millis), callback),
+            receiver,
+        )
+    }
+
+    #[test]
+    fn timeouts_fire_in_deadline_order_unless_dropped() {
+        let (_late, late) = timeout_after(200);
+        let (cancelled, cancelled_results) = timeout_after(50);
+        let (_early, early) = timeout_after(100);
+        drop(cancelled);
+
+        let wait = Duration::from_secs(5);
+        assert!(matches!(
+            early.recv_timeout(wait),
+            Ok(Err(Error::TemporarilyUnavailable))
+        ));
+        assert!(late.try_recv().is_err());
+        assert!(matches!(
+            late.recv_timeout(wait),
+            Ok(Err(Error::TemporarilyUnavailable))
+        ));
+        assert!(cancelled_results.try_recv().is_err());
+    }
+
+    #[test]
+    fn unrepresentable_timeouts_never_fire() {
+        let (sender, receiver) = mpsc::channel::<()>();
+        let callback: LocationCallback = Arc::new(move |_| {
+            let _ = sender.send(());
+        });
+        let timeout = start_timeout(Duration::MAX, callback);
+        assert!(timeout.0.is_none());
+        assert!(receiver.try_recv().is_err());
+    }
+}
@@ -0,0 +1,57 @@
+//! Timestamp formats for the exporters: ISO 8601 for KML and GPX, and
+//! milliseconds since the Unix epoch for serde, GeoJSON and location reports
+
+#[cfg(any(feature = "serde", feature = "geojson", feature = "reporter"))]
+use std::time::Duration;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Format `time` as an ISO 8601 UTC timestamp, e.g. `2024-05-01T12:00:00Z`.
+pub(crate) fn format_timestamp(time: SystemTime) -> String {
+    let seconds = match time.duration_since(UNIX_EPOCH) {
+        Ok(since) => since.as_secs() as i64,
+        Err(before) => -(before.duration().as_secs_f64().ceil() as i64),
+    };
+    let (days, seconds_of_day) = (seconds.div_euclid(86_400), seconds.rem_euclid(86_400));
+
+    // Civil date from days since the epoch (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60
+    )
+}
+
+/// Milliseconds since the Unix epoch, negative before it. Anything below a
+/// millisecond is dropped.
+#[cfg(any(feature = "serde", feature = "geojson", feature = "reporter"))]
+pub(crate) fn to_millis(time: SystemTime) -> i64 {
+    let clamp = |duration: Duration| i64::try_from(duration.as_millis()).unwrap_or(i64::MAX);
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(after) => clamp(after),
+        Err(before) => -clamp(before.duration()),
+    }
+}
+
+/// The inverse of [`to_millis`], or `None` if the time is outside the
+/// range `SystemTime` can represent on this platform
+#[cfg(any(feature = "serde", feature = "geojson"))]
+pub(crate) fn from_millis(millis: i64) -> Option<SystemTime> {
+    let offset = Duration::from_millis(millis.unsigned_abs());
+    if millis >= 0 {
+        UNIX_EPOCH.checked_add(offset)
+    } else {
+        UNIX_EPOCH.checked_sub(offset)
+    }
+}
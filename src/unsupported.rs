@@ -1,10 +1,36 @@
+use crate::watch::LocationCallback;
+use crate::{Error, Location, LocationOptions, PermissionStatus, PowerLevel, Result};
+use std::time::SystemTime;
+
 /// Unsupported platform stub for request_permission
 pub fn request_permission() -> bool {
     false
 }
 
+/// Unsupported platform stub for permission_status
+pub fn permission_status() -> PermissionStatus {
+    PermissionStatus::Restricted
+}
+
 /// Unsupported platform stub for last_known
-pub fn last_known() -> Option<(f64, f64)> {
+pub fn last_known() -> Option<Location> {
+    None
+}
+
+/// Unsupported platform stub for last_fix_time
+pub fn last_fix_time() -> Option<SystemTime> {
     None
 }
 
+/// Unsupported platform stub for power_level
+pub fn power_level() -> PowerLevel {
+    PowerLevel::None
+}
+
+/// Unsupported platform stub for Watch; no watch can be started
+pub enum Watch {}
+
+/// Unsupported platform stub for watch
+pub fn watch(_options: &LocationOptions, _callback: LocationCallback) -> Result<Watch> {
+    Err(Error::PermanentlyUnavailable)
+}
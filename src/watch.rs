@@ -0,0 +1,829 @@
+//! Location requests on top of the platform watch primitives
+//!
+//! Every platform delivers fixes through callbacks registered with its
+//! location service (`platform::watch`). This module turns those callbacks
+//! into futures, streams and callback handles.
+
+use crate::events::{self, LocationEvent};
+use crate::geo_math::{dead_reckoning_position, distance_meters};
+use crate::heading::{self, Heading, HeadingCallback, HeadingWatch};
+use crate::observable::observe;
+use crate::satellites::SatelliteWatch;
+use crate::{now, AccuracyTier, Coordinates, Error, Location, LocationOptions, Result};
+use futures_channel::{mpsc, oneshot};
+use futures_core::Stream;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
+
+#[cfg(target_os = "android")]
+use crate::android as platform;
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+use crate::darwin as platform;
+#[cfg(all(target_os = "linux", feature = "linux"))]
+use crate::linux as platform;
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "ios",
+    target_os = "macos",
+    target_arch = "wasm32",
+    all(target_os = "windows", feature = "windows"),
+    all(target_os = "linux", feature = "linux")
+)))]
+use crate::unsupported as platform;
+#[cfg(target_arch = "wasm32")]
+use crate::web as platform;
+#[cfg(all(target_os = "windows", feature = "windows"))]
+use crate::windows as platform;
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "ios",
+    target_os = "macos",
+    target_arch = "wasm32"
+))]
+use platform::{watch_foreground_only, ForegroundWatch};
+// Platforms without an app lifecycle are always in the foreground
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "ios",
+    target_os = "macos",
+    target_arch = "wasm32"
+)))]
+use platform::{watch as watch_foreground_only, Watch as ForegroundWatch};
+
+/// Callback invoked by a platform watch for each fix or error.
+///
+/// Android calls it from the main looper thread, so it must be `Send + Sync`.
+pub(crate) type LocationCallback = Arc<dyn Fn(Result<Location>) + Send + Sync>;
+
+/// Stream of location updates returned by [`watch_position`](crate::watch_position).
+///
+/// Yields a `Result<Location>` for every fix or error the platform reports.
+/// If updates can't be started at all, the stream yields that error and
+/// ends. Dropping the stream stops the updates.
+#[must_use = "location updates stop when the stream is dropped"]
+pub struct LocationStream {
+    receiver: mpsc::UnboundedReceiver<Result<Location>>,
+    watch: Option<platform::Watch>,
+}
+
+impl LocationStream {
+    pub(crate) fn new(options: &LocationOptions) -> Self {
+        let (sender, receiver) = mpsc::unbounded();
+        let callback: LocationCallback = {
+            let sender = sender.clone();
+            observe(Arc::new(move |result| {
+                let _ = sender.unbounded_send(result);
+            }))
+        };
+
+        let watch = match platform::watch(options, callback) {
+            Ok(watch) => {
+                events::emit(LocationEvent::ServiceStarted);
+                Some(watch)
+            }
+            Err(error) => {
+                let _ = sender.unbounded_send(Err(error));
+                None
+            }
+        };
+
+        // Only the watch's callback keeps the channel open, so the stream
+        // ends right after the error if the watch couldn't start.
+        drop(sender);
+
+        Self { receiver, watch }
+    }
+}
+
+impl Stream for LocationStream {
+    type Item = Result<Location>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = Pin::new(&mut self.receiver).poll_next(cx);
+        // Emit from the polling thread, which is where UI code subscribes
+        if let Poll::Ready(Some(result)) = &poll {
+            match result {
+                Ok(location) => events::emit(LocationEvent::FixObtained(location.coordinates())),
+                Err(error) => events::emit(LocationEvent::Error(error.clone())),
+            }
+        }
+        poll
+    }
+}
+
+impl Drop for LocationStream {
+    fn drop(&mut self) {
+        if self.watch.take().is_some() {
+            events::emit(LocationEvent::ServiceStopped);
+        }
+    }
+}
+
+/// Stream merging two [`LocationStream`]s, yielding only fixes at least as
+/// accurate as the latest fix of the other stream.
+///
+/// Use it to run a precise and a coarse watch side by side, such as GPS and
+/// network location, and follow whichever is currently better. A fix
+/// without a `horizontal_accuracy` only passes while the other stream has no
+/// fix with one. A stream's latest fix counts until that stream yields an
+/// error or ends. Errors from either stream are passed on, and the merged
+/// stream ends once both streams have ended.
+///
+/// ```rust,no_run
+/// use dioxus_mobile_geolocation::{watch_position, LocationCoalescer, LocationOptions};
+///
+/// let gps = watch_position(&LocationOptions {
+///     high_accuracy: true,
+///     ..LocationOptions::default()
+/// });
+/// let network = watch_position(&LocationOptions::default());
+/// let best = LocationCoalescer::new(gps, network);
+/// # drop(best);
+/// ```
+#[must_use = "location updates stop when the stream is dropped"]
+pub struct LocationCoalescer {
+    streams: [Option<LocationStream>; 2],
+    latest: [Option<Location>; 2],
+}
+
+impl LocationCoalescer {
+    /// Merge the fixes of `primary` and `secondary`.
+    ///
+    /// When both have a fix ready, `primary`'s is yielded first.
+    pub fn new(primary: LocationStream, secondary: LocationStream) -> Self {
+        Self {
+            streams: [Some(primary), Some(secondary)],
+            latest: [None, None],
+        }
+    }
+}
+
+impl Stream for LocationCoalescer {
+    type Item = Result<Location>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            let mut open = false;
+            let mut skipped = false;
+            for index in 0..2 {
+                let Some(stream) = &mut this.streams[index] else {
+                    continue;
+                };
+                match Pin::new(stream).poll_next(cx) {
+                    Poll::Pending => open = true,
+                    Poll::Ready(None) => {
+                        this.streams[index] = None;
+                        this.latest[index] = None;
+                    }
+                    Poll::Ready(Some(Err(error))) => {
+                        this.latest[index] = None;
+                        return Poll::Ready(Some(Err(error)));
+                    }
+                    Poll::Ready(Some(Ok(location))) => {
+                        this.latest[index] = Some(location);
+                        if is_at_least_as_accurate(&location, this.latest[1 - index].as_ref()) {
+                            return Poll::Ready(Some(Ok(location)));
+                        }
+                        // Poll the stream again so it wakes us for its next fix
+                        open = true;
+                        skipped = true;
+                    }
+                }
+            }
+            if !skipped {
+                return if open {
+                    Poll::Pending
+                } else {
+                    Poll::Ready(None)
+                };
+            }
+        }
+    }
+}
+
+/// Whether `location` is at least as accurate as `other`, counting a
+/// missing accuracy as worse than any known one
+fn is_at_least_as_accurate(location: &Location, other: Option<&Location>) -> bool {
+    let Some(other) = other.and_then(|other| other.horizontal_accuracy) else {
+        return true;
+    };
+    location
+        .horizontal_accuracy
+        .is_some_and(|accuracy| accuracy <= other)
+}
+
+/// Wrap `callback` so every fix and error is also emitted as a
+/// [`LocationEvent`], on the thread the platform delivers it on.
+fn emitting(callback: LocationCallback) -> LocationCallback {
+    Arc::new(move |result: Result<Location>| {
+        match &result {
+            Ok(location) => events::emit(LocationEvent::FixObtained(location.coordinates())),
+            Err(error) => events::emit(LocationEvent::Error(error.clone())),
+        }
+        callback(result)
+    })
+}
+
+/// Handle to a callback-based location watch such as
+/// [`watch_location`](crate::watch_location).
+///
+/// Holds the `context` passed to
+/// [`watch_location_with_context`](crate::watch_location_with_context), or
+/// `()` for watches without one. Dropping the handle stops the updates.
+#[must_use = "location updates stop when the handle is dropped"]
+pub struct WatchHandle<T = ()> {
+    watch: Option<platform::Watch>,
+    heading: Option<HeadingWatch>,
+    foreground: Option<ForegroundWatch>,
+    sensor: Option<SensorWatch>,
+    context: T,
+}
+
+/// A running watch of a sensor other than the location, kept alive by a
+/// [`WatchHandle`]
+pub(crate) enum SensorWatch {
+    Satellites(SatelliteWatch),
+    #[cfg(feature = "barometer")]
+    Altitude(crate::barometer::AltitudeWatch),
+    #[cfg(feature = "pedometer")]
+    Pedometer(crate::pedometer::PedometerWatch),
+    #[cfg(feature = "activity-recognition")]
+    Activity(crate::activity::ActivityWatch),
+}
+
+impl<T> WatchHandle<T> {
+    /// Start a watch that calls `callback` for every fix or error.
+    ///
+    /// If updates can't be started, `callback` is called once with the error
+    /// before this returns.
+    pub(crate) fn new(options: &LocationOptions, context: T, callback: LocationCallback) -> Self {
+        Self::without_fix_events(options, context, emitting(callback))
+    }
+
+    /// Like [`WatchHandle::new`], but leaves emitting
+    /// [`LocationEvent::FixObtained`] and [`LocationEvent::Error`] to the
+    /// caller, for one-shot reads that emit only the result they return.
+    fn without_fix_events(
+        options: &LocationOptions,
+        context: T,
+        callback: LocationCallback,
+    ) -> Self {
+        let callback = observe(callback);
+        let watch = match platform::watch(options, callback.clone()) {
+            Ok(watch) => {
+                events::emit(LocationEvent::ServiceStarted);
+                Some(watch)
+            }
+            Err(error) => {
+                callback(Err(error));
+                None
+            }
+        };
+        Self {
+            watch,
+            heading: None,
+            foreground: None,
+            sensor: None,
+            context,
+        }
+    }
+
+    /// Start a watch that calls `callback` for every fix or error, failing
+    /// if updates can't be started.
+    pub(crate) fn try_new(
+        options: &LocationOptions,
+        context: T,
+        callback: LocationCallback,
+    ) -> Result<Self> {
+        let callback = observe(emitting(callback));
+        let watch = platform::watch(options, callback)?;
+        events::emit(LocationEvent::ServiceStarted);
+        Ok(Self {
+            watch: Some(watch),
+            heading: None,
+            foreground: None,
+            sensor: None,
+            context,
+        })
+    }
+
+    /// Start a watch that calls `callback` for every fix or error while the
+    /// app is in the foreground, and stops the updates while it isn't.
+    ///
+    /// If updates can't be started, `callback` is called once with the error
+    /// before this returns.
+    pub(crate) fn foreground_only(
+        options: &LocationOptions,
+        context: T,
+        callback: LocationCallback,
+    ) -> Self {
+        let callback = observe(emitting(callback));
+        let foreground = match watch_foreground_only(options, callback.clone()) {
+            Ok(foreground) => {
+                events::emit(LocationEvent::ServiceStarted);
+                Some(foreground)
+            }
+            Err(error) => {
+                callback(Err(error));
+                None
+            }
+        };
+        Self {
+            watch: None,
+            heading: None,
+            foreground,
+            sensor: None,
+            context,
+        }
+    }
+
+    /// Keep `sensor` running until the handle is dropped, or return an
+    /// inactive handle if it couldn't start.
+    pub(crate) fn sensor(context: T, sensor: Option<SensorWatch>) -> Self {
+        Self {
+            watch: None,
+            heading: None,
+            foreground: None,
+            sensor,
+            context,
+        }
+    }
+
+    /// The context this watch was started with.
+    pub fn context(&self) -> &T {
+        &self.context
+    }
+
+    /// Whether the platform is delivering updates to this watch.
+    ///
+    /// `false` if the watch failed to start.
+    pub fn is_active(&self) -> bool {
+        self.watch.is_some() || self.foreground.is_some() || self.sensor.is_some()
+    }
+}
+
+impl<T> Drop for WatchHandle<T> {
+    fn drop(&mut self) {
+        if self.watch.take().is_some() || self.foreground.take().is_some() {
+            events::emit(LocationEvent::ServiceStopped);
+        }
+    }
+}
+
+/// Adapt a coordinates callback to the platform callback type.
+pub(crate) fn coordinates_callback(
+    callback: impl Fn(Result<Coordinates>) + Send + Sync + 'static,
+) -> LocationCallback {
+    Arc::new(move |result: Result<Location>| {
+        callback(result.map(|location| location.coordinates()))
+    })
+}
+
+/// Longest gap after the last fix that is bridged by dead reckoning
+const DEAD_RECKONING_MAX_GAP: Duration = Duration::from_secs(60);
+
+/// Like [`coordinates_callback`], but replaces
+/// [`Error::TemporarilyUnavailable`] with a [`dead_reckoning_position`]
+/// estimate from the last fix's speed and course, for up to
+/// [`DEAD_RECKONING_MAX_GAP`] after it was taken.
+pub(crate) fn dead_reckoning_callback(
+    callback: impl Fn(Result<Coordinates>) + Send + Sync + 'static,
+) -> LocationCallback {
+    let last_fix = Mutex::new(None::<(Location, SystemTime)>);
+    Arc::new(move |result: Result<Location>| {
+        let mut last_fix = last_fix.lock().unwrap_or_else(PoisonError::into_inner);
+        let estimate = match &result {
+            Ok(location) => {
+                *last_fix = Some((*location, now()));
+                None
+            }
+            Err(Error::TemporarilyUnavailable) => last_fix.and_then(|(location, received)| {
+                let speed = location
+                    .speed
+                    .filter(|speed| speed.is_finite() && *speed > 0.0)?;
+                let course = location.course.filter(|course| course.is_finite())?;
+                let taken = location.timestamp.unwrap_or(received);
+                let gap = now().duration_since(taken).unwrap_or_default();
+                (gap <= DEAD_RECKONING_MAX_GAP).then(|| {
+                    dead_reckoning_position((location.coordinates(), taken), course, speed)
+                })
+            }),
+            Err(_) => None,
+        };
+        drop(last_fix);
+        match estimate {
+            Some(coords) => callback(Ok(coords)),
+            None => callback(result.map(|location| location.coordinates())),
+        }
+    })
+}
+
+/// Start a watch calling `callback` with the compass heading while the
+/// device moves slower than `switch_speed_ms`, and with the GPS course
+/// while it moves faster.
+///
+/// Location errors switch back to the compass, and compass errors are
+/// skipped, so `callback` still gets the course if there is no compass.
+pub(crate) fn heading_compass(
+    switch_speed_ms: f64,
+    callback: impl Fn(f64) + Send + Sync + 'static,
+) -> WatchHandle {
+    let callback = Arc::new(callback);
+    // Whether the latest fix was fast enough to follow its course
+    let moving = Arc::new(AtomicBool::new(false));
+
+    let location_callback: LocationCallback = {
+        let callback = callback.clone();
+        let moving = moving.clone();
+        Arc::new(move |result: Result<Location>| {
+            let course = result.ok().and_then(|location| {
+                let fast = location.speed.is_some_and(|speed| speed >= switch_speed_ms);
+                location.course.filter(|_| fast)
+            });
+            moving.store(course.is_some(), Ordering::Relaxed);
+            if let Some(course) = course {
+                callback(course);
+            }
+        })
+    };
+    let heading_callback: HeadingCallback = Arc::new(move |result: Result<Heading>| {
+        let Ok(heading) = result else {
+            return;
+        };
+        if !moving.load(Ordering::Relaxed) {
+            callback(heading.true_heading.unwrap_or(heading.magnetic_heading));
+        }
+    });
+
+    // The course is only reliable with GPS fixes
+    let options = LocationOptions {
+        high_accuracy: true,
+        ..LocationOptions::default()
+    };
+    let mut handle = WatchHandle::new(&options, (), location_callback);
+    handle.heading = heading::watch(heading_callback).ok();
+    handle
+}
+
+/// Change between two successive fixes of a watch, passed to
+/// [`watch_location_diffs`](crate::watch_location_diffs).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LocationDiff {
+    /// The earlier fix.
+    pub previous: Location,
+    /// The new fix.
+    pub current: Location,
+    /// Great-circle distance between the two fixes in meters.
+    pub distance_m: f64,
+    /// Time between the two fixes, if both have a timestamp and they are in
+    /// order.
+    pub elapsed: Option<Duration>,
+}
+
+impl LocationDiff {
+    fn new(previous: Location, current: Location) -> Self {
+        let elapsed = previous
+            .timestamp
+            .zip(current.timestamp)
+            .and_then(|(previous, current)| current.duration_since(previous).ok());
+        Self {
+            previous,
+            current,
+            distance_m: distance_meters(previous.coordinates(), current.coordinates()),
+            elapsed,
+        }
+    }
+
+    /// Average speed between the two fixes in meters per second.
+    ///
+    /// Returns `None` if [`elapsed`](Self::elapsed) is unknown or zero.
+    pub fn speed_mps(&self) -> Option<f64> {
+        let seconds = self.elapsed?.as_secs_f64();
+        (seconds > 0.0).then(|| self.distance_m / seconds)
+    }
+
+    /// Change of course in degrees, positive when turning clockwise, in
+    /// `(-180, 180]`.
+    ///
+    /// Returns `None` unless both fixes report a course.
+    pub fn course_change(&self) -> Option<f64> {
+        let change = (self.current.course? - self.previous.course?).rem_euclid(360.0);
+        Some(if change > 180.0 {
+            change - 360.0
+        } else {
+            change
+        })
+    }
+
+    /// Rate of turn in degrees per second, positive when turning clockwise.
+    ///
+    /// Returns `None` unless both fixes report a course and time has passed
+    /// between them.
+    pub fn turn_rate_deg_per_s(&self) -> Option<f64> {
+        let seconds = self.elapsed?.as_secs_f64();
+        let change = self.course_change()?;
+        (seconds > 0.0).then(|| change / seconds)
+    }
+}
+
+/// Adapt a diff callback to the platform callback type, remembering the
+/// previous fix. Errors are skipped.
+pub(crate) fn diff_callback(
+    callback: impl Fn(LocationDiff) + Send + Sync + 'static,
+) -> LocationCallback {
+    let previous = Mutex::new(None);
+    Arc::new(move |result: Result<Location>| {
+        let Ok(current) = result else {
+            return;
+        };
+        let previous = previous
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .replace(current);
+        if let Some(previous) = previous {
+            callback(LocationDiff::new(previous, current));
+        }
+    })
+}
+
+/// Adapt a coordinates callback to the platform callback type, passing on
+/// only fixes more accurate than the best one so far. Errors are passed on
+/// and reset the best accuracy, as the platform's updates were interrupted.
+pub(crate) fn accuracy_improvement_callback(
+    callback: impl Fn(Result<Coordinates>) + Send + Sync + 'static,
+) -> LocationCallback {
+    let best_accuracy = Mutex::new(None::<f64>);
+    Arc::new(move |result: Result<Location>| {
+        let mut best_accuracy = best_accuracy.lock().unwrap_or_else(PoisonError::into_inner);
+        let location = match result {
+            Ok(location) => location,
+            Err(error) => {
+                *best_accuracy = None;
+                drop(best_accuracy);
+                callback(Err(error));
+                return;
+            }
+        };
+        // A fix without an accuracy can't be compared, so it never counts
+        // as an improvement
+        let Some(accuracy) = location.horizontal_accuracy else {
+            return;
+        };
+        if best_accuracy.is_some_and(|best| accuracy >= best) {
+            return;
+        }
+        *best_accuracy = Some(accuracy);
+        drop(best_accuracy);
+        callback(Ok(location.coordinates()));
+    })
+}
+
+/// Adapt a coordinates callback to the platform callback type, passing on
+/// at most one fix per `interval`. Errors are passed on right away.
+///
+/// Platforms have no timer to hook into, so once an interval has passed the
+/// next fix, the newest one, is passed on and starts a new interval; the
+/// fixes arriving before then are superseded by it. Intervals are measured with the fixes' timestamps, or
+/// the time they arrive if they have none.
+pub(crate) fn min_interval_callback(
+    interval: Duration,
+    callback: impl Fn(Result<Coordinates>) + Send + Sync + 'static,
+) -> LocationCallback {
+    // End of the current interval
+    let interval_end = Mutex::new(None::<SystemTime>);
+    Arc::new(move |result: Result<Location>| {
+        let location = match result {
+            Ok(location) => location,
+            Err(error) => return callback(Err(error)),
+        };
+
+        let time = location.timestamp.unwrap_or_else(now);
+        {
+            let mut interval_end = interval_end.lock().unwrap_or_else(PoisonError::into_inner);
+            if interval_end.is_some_and(|end| time < end) {
+                return;
+            }
+            *interval_end = Some(time + interval);
+        }
+        callback(Ok(location.coordinates()));
+    })
+}
+
+/// Accuracy asked for by [`adaptive_callback`] while the battery is at or
+/// above the threshold
+const ADAPTIVE_HIGH_ACCURACY: AccuracyTier = AccuracyTier::Best;
+
+/// Accuracy asked for by [`adaptive_callback`] while the battery is below
+/// the threshold
+const ADAPTIVE_LOW_ACCURACY: AccuracyTier = AccuracyTier::HundredMeters;
+
+/// Adapt a coordinates callback to the platform callback type, lowering the
+/// desired accuracy while the battery is below `threshold_percent` and
+/// restoring it once charged.
+///
+/// The battery is checked with every update. Set the high accuracy before
+/// starting the watch.
+pub(crate) fn adaptive_callback(
+    threshold_percent: u8,
+    callback: impl Fn(Result<Coordinates>) + Send + Sync + 'static,
+) -> LocationCallback {
+    let tier = Mutex::new(ADAPTIVE_HIGH_ACCURACY);
+    Arc::new(move |result: Result<Location>| {
+        // An unknown level never counts as low
+        let low = battery_level_percent().is_some_and(|level| level < threshold_percent);
+        let wanted = if low {
+            ADAPTIVE_LOW_ACCURACY
+        } else {
+            ADAPTIVE_HIGH_ACCURACY
+        };
+        let mut tier = tier.lock().unwrap_or_else(PoisonError::into_inner);
+        if *tier != wanted {
+            *tier = wanted;
+            crate::set_desired_accuracy(wanted);
+        }
+        drop(tier);
+        callback(result.map(|location| location.coordinates()));
+    })
+}
+
+/// Battery charge in percent, where the platform reports it and can also
+/// change the accuracy of a running watch
+fn battery_level_percent() -> Option<u8> {
+    #[cfg(target_os = "ios")]
+    return crate::darwin::battery_level_percent();
+    #[cfg(not(target_os = "ios"))]
+    return None;
+}
+
+/// Resolve with the first fix or error of a new watch, then stop the watch.
+///
+/// Fails with [`Error::TemporarilyUnavailable`] if nothing arrives within
+/// `options.timeout`.
+pub(crate) async fn first_update(options: &LocationOptions) -> Result<Location> {
+    let (resolve, receiver) = resolve_once();
+
+    // Dropping the handle once this function returns stops the updates
+    let _handle = WatchHandle::without_fix_events(options, (), resolve.clone());
+
+    // Browsers time out watches themselves, with `options.timeout`
+    #[cfg(not(target_arch = "wasm32"))]
+    let _cancel_timeout = crate::timeout::start_timeout(options.timeout, resolve);
+
+    receiver.await.unwrap_or(Err(Error::Unknown))
+}
+
+/// A callback that passes on only the first result it's called with, and
+/// the receiver of that result.
+fn resolve_once() -> (LocationCallback, oneshot::Receiver<Result<Location>>) {
+    let (sender, receiver) = oneshot::channel();
+    let sender = Mutex::new(Some(sender));
+    let resolve: LocationCallback = Arc::new(move |result: Result<Location>| {
+        let sender = sender.lock().unwrap_or_else(PoisonError::into_inner).take();
+        if let Some(sender) = sender {
+            let _ = sender.send(result);
+        }
+    });
+    (resolve, receiver)
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "ios",
+    target_os = "macos",
+    all(target_os = "windows", feature = "windows"),
+    all(target_os = "linux", feature = "linux")
+))]
+pub(crate) use native::get_location;
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "ios",
+    target_os = "macos",
+    all(target_os = "windows", feature = "windows"),
+    all(target_os = "linux", feature = "linux")
+))]
+mod native {
+    use super::{platform, resolve_once};
+    use crate::timeout::start_timeout;
+    use crate::{now, Error, Location, LocationOptions, Result};
+
+    /// Resolve with the platform's cached fix if it is at most
+    /// `options.maximum_age` old, and otherwise with the first new fix.
+    pub(crate) async fn get_location(options: &LocationOptions) -> Result<Location> {
+        #[cfg(target_os = "android")]
+        let cached = platform::last_known();
+        #[cfg(any(
+            target_os = "ios",
+            target_os = "macos",
+            all(target_os = "windows", feature = "windows"),
+            all(target_os = "linux", feature = "linux")
+        ))]
+        let cached = platform::cached_location();
+
+        let fresh = cached.filter(|location| {
+            location.timestamp.is_some_and(|timestamp| {
+                now().duration_since(timestamp).unwrap_or_default() <= options.maximum_age
+            })
+        });
+        if let Some(location) = fresh {
+            return Ok(location);
+        }
+
+        let (resolve, receiver) = resolve_once();
+
+        // Dropping the watch once this function returns stops the updates
+        let _watch = platform::watch(options, resolve.clone())?;
+        let _cancel_timeout = start_timeout(options.timeout, resolve);
+
+        receiver.await.unwrap_or(Err(Error::Unknown))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed `fixes` through a diff callback, with an error after each, and
+    /// return the course change of every diff
+    fn course_changes(fixes: impl IntoIterator<Item = Location>) -> Vec<Option<f64>> {
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let callback = diff_callback({
+            let changes = changes.clone();
+            move |diff: LocationDiff| changes.lock().unwrap().push(diff.course_change())
+        });
+        for fix in fixes {
+            callback(Ok(fix));
+            // Errors don't break the pairing
+            callback(Err(Error::TemporarilyUnavailable));
+        }
+        let changes = changes.lock().unwrap();
+        changes.clone()
+    }
+
+    fn heading(course: f64) -> Location {
+        Location {
+            course: Some(course),
+            ..Location::from(Coordinates::new(52.52, 13.405))
+        }
+    }
+
+    #[test]
+    fn course_change_wraps_around_north() {
+        assert_eq!(
+            course_changes([heading(350.0), heading(10.0)]),
+            [Some(20.0)]
+        );
+        assert_eq!(
+            course_changes([heading(10.0), heading(350.0)]),
+            [Some(-20.0)]
+        );
+    }
+
+    #[test]
+    fn course_change_is_at_most_half_a_turn() {
+        let fixes = [90.0, 270.0, 90.0, 100.0].map(heading);
+        assert_eq!(
+            course_changes(fixes),
+            [Some(180.0), Some(180.0), Some(10.0)]
+        );
+        assert_eq!(course_changes([heading(0.0), heading(0.0)]), [Some(0.0)]);
+    }
+
+    #[test]
+    fn min_interval_passes_on_the_fix_that_ends_the_interval() {
+        let delivered = Arc::new(Mutex::new(Vec::new()));
+        let callback = min_interval_callback(Duration::from_secs(10), {
+            let delivered = delivered.clone();
+            move |result: Result<Coordinates>| {
+                delivered.lock().unwrap().push(result.unwrap().latitude)
+            }
+        });
+        let start = now();
+        for (seconds, latitude) in [
+            (0, 1.0),
+            (4, 2.0),
+            (9, 3.0),
+            (12, 4.0),
+            (15, 5.0),
+            (22, 6.0),
+        ] {
+            callback(Ok(Location {
+                timestamp: Some(start + Duration::from_secs(seconds)),
+                ..Location::from(Coordinates::new(latitude, 0.0))
+            }));
+        }
+        assert_eq!(*delivered.lock().unwrap(), [1.0, 4.0, 6.0]);
+    }
+
+    #[test]
+    fn course_change_needs_both_courses() {
+        let no_course = Location::from(Coordinates::new(52.52, 13.405));
+        assert_eq!(course_changes([no_course, heading(10.0)]), [None]);
+        assert_eq!(course_changes([heading(10.0), no_course]), [None]);
+    }
+}
@@ -3,14 +3,45 @@
 //! Uses the browser's Geolocation API to access location data.
 //! Since the browser API is asynchronous, this module provides both sync and async interfaces.
 //! The sync `last_known()` function returns cached position if available.
+//! The last fix is also kept in `localStorage` so it survives page reloads.
 
-use std::cell::RefCell;
+use crate::events::{self, LocationEvent};
+use crate::geo_math::distance_meters;
+use crate::heading::{Heading, HeadingCallback};
+use crate::watch::LocationCallback;
+use crate::{now, Coordinates, Error, Location, LocationOptions, PermissionStatus, PowerLevel};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
-use web_sys::{Position, PositionError, PositionOptions};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{DeviceOrientationEvent, Document, Event, Position, PositionError, PositionOptions};
 
 thread_local! {
-    static CACHED_POSITION: RefCell<Option<(f64, f64)>> = RefCell::new(None);
+    static CACHED_POSITION: RefCell<Option<Location>> = RefCell::new(None);
+    static LAST_ERROR: RefCell<Option<Error>> = RefCell::new(None);
+    static CACHED_PERMISSION: Cell<Option<PermissionStatus>> = const { Cell::new(None) };
+    static PERMISSION_LISTENER: RefCell<Option<PermissionListener>> = const { RefCell::new(None) };
+}
+
+/// The geolocation permission and its `change` listener, kept for the
+/// lifetime of the page
+type PermissionListener = (web_sys::PermissionStatus, Closure<dyn FnMut(Event)>);
+
+/// `GeolocationPositionError.PERMISSION_DENIED`
+const PERMISSION_DENIED: u16 = 1;
+
+impl From<PositionError> for Error {
+    fn from(error: PositionError) -> Self {
+        if error.code() == PERMISSION_DENIED {
+            set_cached_permission(PermissionStatus::Denied);
+        }
+        Error::WebError {
+            code: error.code(),
+            message: error.message(),
+        }
+    }
 }
 
 /// Request location permission
@@ -34,23 +65,196 @@ pub fn request_permission() -> bool {
     get_current_position_sync()
 }
 
+/// Get the permission state cached by `query_permission_status()`
+///
+/// Returns `NotDetermined` until the Permissions API has been queried or a
+/// position request has succeeded or been denied.
+pub fn permission_status() -> PermissionStatus {
+    CACHED_PERMISSION
+        .with(Cell::get)
+        .unwrap_or(PermissionStatus::NotDetermined)
+}
+
+/// Query `navigator.permissions` for the geolocation permission and cache it
+///
+/// Browsers don't distinguish precise from approximate location, so a
+/// granted permission is reported as `GrantedFine`. Falls back to the cached
+/// value if the Permissions API is unavailable.
+pub async fn query_permission_status() -> PermissionStatus {
+    let Some(query) = web_sys::window()
+        .and_then(|window| window.navigator().permissions().ok())
+        .and_then(|permissions| {
+            let descriptor = js_sys::Object::new();
+            js_sys::Reflect::set(&descriptor, &"name".into(), &"geolocation".into()).ok()?;
+            permissions.query(&descriptor).ok()
+        })
+    else {
+        return permission_status();
+    };
+
+    let Ok(state) = JsFuture::from(query).await else {
+        return permission_status();
+    };
+    let permission = state.unchecked_into::<web_sys::PermissionStatus>();
+    let status = permission_from_state(permission.state());
+    set_cached_permission(status);
+    listen_for_permission_changes(permission);
+    status
+}
+
+fn permission_from_state(state: web_sys::PermissionState) -> PermissionStatus {
+    match state {
+        web_sys::PermissionState::Granted => PermissionStatus::GrantedFine,
+        web_sys::PermissionState::Denied => PermissionStatus::Denied,
+        _ => PermissionStatus::NotDetermined,
+    }
+}
+
+/// Keep the cached permission current, and emit permission events, when the
+/// user changes it in the browser's site settings. Only the first call adds
+/// the `change` listener.
+fn listen_for_permission_changes(permission: web_sys::PermissionStatus) {
+    PERMISSION_LISTENER.with(|listener| {
+        let mut listener = listener.borrow_mut();
+        if listener.is_some() {
+            return;
+        }
+
+        let on_change = {
+            let permission = permission.clone();
+            Closure::wrap(Box::new(move |_: Event| {
+                let status = permission_from_state(permission.state());
+                set_cached_permission(status);
+                events::permission_seen(status);
+            }) as Box<dyn FnMut(Event)>)
+        };
+        permission.set_onchange(Some(on_change.as_ref().unchecked_ref()));
+        *listener = Some((permission, on_change));
+    });
+}
+
+fn set_cached_permission(status: PermissionStatus) {
+    CACHED_PERMISSION.with(|cached| cached.set(Some(status)));
+}
+
 /// Get the last known (cached) location
 ///
 /// Returns the cached location if one was previously obtained via `get_current_position_sync()`.
 /// Returns `None` if no location has been cached yet.
 ///
 /// For web, you should call `get_current_position_sync()` first to populate the cache.
-pub fn last_known() -> Option<(f64, f64)> {
-    CACHED_POSITION.with(|pos| *pos.borrow())
+/// Until then, falls back to the fix persisted by a previous page load.
+pub fn last_known() -> Option<Location> {
+    CACHED_POSITION
+        .with(|pos| *pos.borrow())
+        .or_else(|| load_persisted_location().map(Location::from))
+}
+
+/// `localStorage` key holding the last fix
+const STORAGE_KEY: &str = "dioxus-mobile-geolocation:last-location";
+
+/// Store `c` in `localStorage` as JSON
+///
+/// Called automatically whenever a new fix is cached. Silently does nothing
+/// if storage is unavailable (e.g. disabled by the user or full).
+pub fn persist_location(c: Coordinates) {
+    let Some(storage) = local_storage() else {
+        return;
+    };
+
+    let object = js_sys::Object::new();
+    let _ = js_sys::Reflect::set(&object, &"latitude".into(), &c.latitude.into());
+    let _ = js_sys::Reflect::set(&object, &"longitude".into(), &c.longitude.into());
+    if let Some(json) = js_sys::JSON::stringify(&object)
+        .ok()
+        .and_then(|json| json.as_string())
+    {
+        let _ = storage.set_item(STORAGE_KEY, &json);
+    }
+}
+
+/// Load the fix stored by `persist_location()`
+///
+/// Returns `None` if nothing was stored or the stored value is malformed.
+pub fn load_persisted_location() -> Option<Coordinates> {
+    let json = local_storage()?.get_item(STORAGE_KEY).ok()??;
+    let object = js_sys::JSON::parse(&json).ok()?;
+    let latitude = js_sys::Reflect::get(&object, &"latitude".into())
+        .ok()?
+        .as_f64()?;
+    let longitude = js_sys::Reflect::get(&object, &"longitude".into())
+        .ok()?
+        .as_f64()?;
+    Some(Coordinates::new(latitude, longitude))
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+/// Get the time of the cached location fix
+pub fn last_fix_time() -> Option<SystemTime> {
+    CACHED_POSITION.with(|pos| pos.borrow().and_then(|location| location.timestamp))
+}
+
+/// Expected battery impact of location requests
+///
+/// Browsers don't report which positioning source they use. Requests made
+/// by `get_current_position_sync()` don't ask for high accuracy, so the
+/// browser is free to answer from Wi-Fi or IP location.
+pub fn power_level() -> PowerLevel {
+    let available = web_sys::window()
+        .map(|window| window.navigator().geolocation().is_ok())
+        .unwrap_or(false);
+    if available {
+        PowerLevel::Low
+    } else {
+        PowerLevel::None
+    }
+}
+
+/// Get the error from the most recent failed `get_current_position_sync()`
+/// request or position watch
+///
+/// Returns `None` if nothing has failed, or if a later position arrived.
+pub fn last_position_error() -> Option<Error> {
+    LAST_ERROR.with(|error| error.borrow().clone())
 }
 
 /// Update the cached position (internal use)
-fn update_cached_position(lat: f64, lon: f64) {
+fn update_cached_position(position: &Position) {
+    set_cached_permission(PermissionStatus::GrantedFine);
+    let location = location_from_position(position);
     CACHED_POSITION.with(|pos| {
-        *pos.borrow_mut() = Some((lat, lon));
+        *pos.borrow_mut() = Some(location);
+    });
+    persist_location(location.coordinates());
+    LAST_ERROR.with(|error| {
+        *error.borrow_mut() = None;
     });
 }
 
+/// Convert a `GeolocationPosition` to a `Location`
+///
+/// Browsers report `NaN` for the heading of a stationary device, and `null`
+/// for any field they can't determine.
+fn location_from_position(position: &Position) -> Location {
+    let coords = position.coords();
+    let finite = |value: Option<f64>| value.filter(|value| value.is_finite());
+
+    Location {
+        latitude: coords.latitude(),
+        longitude: coords.longitude(),
+        altitude: finite(coords.altitude()),
+        horizontal_accuracy: finite(Some(coords.accuracy())),
+        vertical_accuracy: finite(coords.altitude_accuracy()),
+        speed: finite(coords.speed()),
+        course: finite(coords.heading()),
+        // `Position.timestamp` is in milliseconds since the epoch
+        timestamp: Some(UNIX_EPOCH + Duration::from_millis(position.timestamp().max(0.0) as u64)),
+    }
+}
+
 /// Get current position synchronously by triggering the async API
 ///
 /// This function initiates the geolocation request and returns immediately.
@@ -71,13 +275,16 @@ pub fn get_current_position_sync() -> bool {
 
     // Create success callback
     let success = Closure::wrap(Box::new(move |pos: Position| {
-        let coords = pos.coords();
-        update_cached_position(coords.latitude(), coords.longitude());
+        update_cached_position(&pos);
     }) as Box<dyn FnMut(Position)>);
 
-    // Create error callback
-    let error = Closure::wrap(Box::new(move |_err: PositionError| {
-        // Silently ignore errors for the sync API
+    // Create error callback, keeping the browser's error for `last_position_error()`
+    let error = Closure::wrap(Box::new(move |err: PositionError| {
+        let err = Error::from(err);
+        LAST_ERROR.with(|last| {
+            *last.borrow_mut() = Some(err.clone());
+        });
+        events::emit(LocationEvent::Error(err));
     }) as Box<dyn FnMut(PositionError)>);
 
     let options = PositionOptions::new();
@@ -98,6 +305,286 @@ pub fn get_current_position_sync() -> bool {
     result.is_ok()
 }
 
+/// Request a fresh position and resolve once the browser answers
+///
+/// The result is also cached for `last_known()`.
+pub async fn get_location(options: &LocationOptions) -> crate::Result<Location> {
+    let geolocation = geolocation()?;
+    let position_options = position_options(options);
+
+    let mut request = Ok(());
+    let promise = js_sys::Promise::new(&mut |resolve, reject| {
+        request = geolocation.get_current_position_with_error_callback_and_options(
+            &resolve,
+            Some(&reject),
+            &position_options,
+        );
+    });
+    // The promise never settles if the request couldn't be made
+    request.map_err(|_| Error::Unknown)?;
+
+    match JsFuture::from(promise).await {
+        Ok(position) => {
+            let position: Position = position.unchecked_into();
+            update_cached_position(&position);
+            Ok(location_from_position(&position))
+        }
+        Err(error) => Err(error.unchecked_into::<PositionError>().into()),
+    }
+}
+
+/// A running `watchPosition()` request. The watch is cleared when dropped.
+pub struct Watch {
+    id: i32,
+    _on_position: Closure<dyn FnMut(Position)>,
+    _on_error: Closure<dyn FnMut(PositionError)>,
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        if let Ok(geolocation) = geolocation() {
+            geolocation.clear_watch(self.id);
+        }
+    }
+}
+
+/// Start `navigator.geolocation.watchPosition()`, calling `callback` for each
+/// position or error
+///
+/// Positions are also cached for `last_known()`, and errors are kept for
+/// `last_position_error()`.
+pub fn watch(options: &LocationOptions, callback: LocationCallback) -> crate::Result<Watch> {
+    let geolocation = geolocation()?;
+
+    let on_position = {
+        let callback = callback.clone();
+        let distance_filter = options.distance_filter;
+        let mut last_reported: Option<Coordinates> = None;
+        Closure::wrap(Box::new(move |position: Position| {
+            update_cached_position(&position);
+            let location = location_from_position(&position);
+
+            // Browsers have no distance filter, so apply it here
+            let too_close = last_reported.is_some_and(|last| {
+                distance_meters(last, location.coordinates()) < distance_filter
+            });
+            if !too_close {
+                last_reported = Some(location.coordinates());
+                callback(Ok(location));
+            }
+        }) as Box<dyn FnMut(Position)>)
+    };
+    let on_error = Closure::wrap(Box::new(move |error: PositionError| {
+        let error = Error::from(error);
+        LAST_ERROR.with(|last| {
+            *last.borrow_mut() = Some(error.clone());
+        });
+        callback(Err(error));
+    }) as Box<dyn FnMut(PositionError)>);
+
+    let id = geolocation
+        .watch_position_with_error_callback_and_options(
+            on_position.as_ref().unchecked_ref(),
+            Some(on_error.as_ref().unchecked_ref()),
+            &position_options(options),
+        )
+        .map_err(|_| Error::Unknown)?;
+
+    // Watch the permission too, so revoking it mid-watch emits
+    // `LocationEvent::PermissionDenied`
+    wasm_bindgen_futures::spawn_local(async {
+        events::permission_seen(query_permission_status().await);
+    });
+
+    Ok(Watch {
+        id,
+        _on_position: on_position,
+        _on_error: on_error,
+    })
+}
+
+/// A location watch that is cleared while the page is hidden; dropping it
+/// clears the watch and removes the `visibilitychange` listener
+pub struct ForegroundWatch {
+    document: Document,
+    listener: Closure<dyn FnMut(Event)>,
+    _watch: Rc<RefCell<Option<Watch>>>,
+}
+
+impl Drop for ForegroundWatch {
+    fn drop(&mut self) {
+        let _ = self.document.remove_event_listener_with_callback(
+            "visibilitychange",
+            self.listener.as_ref().unchecked_ref(),
+        );
+    }
+}
+
+/// Start `navigator.geolocation.watchPosition()`, clearing the watch while
+/// the page is hidden and starting a new one when it is visible again
+///
+/// If the page is hidden already, the first watch starts once it is shown.
+pub fn watch_foreground_only(
+    options: &LocationOptions,
+    callback: LocationCallback,
+) -> crate::Result<ForegroundWatch> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or(Error::PermanentlyUnavailable)?;
+    let watch = if document.hidden() {
+        None
+    } else {
+        Some(self::watch(options, callback.clone())?)
+    };
+    let watch = Rc::new(RefCell::new(watch));
+
+    let listener = {
+        let document = document.clone();
+        let watch = watch.clone();
+        let options = *options;
+        Closure::wrap(Box::new(move |_event: Event| {
+            if document.hidden() {
+                // Release the borrow before clearing the watch
+                let cleared = watch.borrow_mut().take();
+                drop(cleared);
+            } else if watch.borrow().is_none() {
+                match self::watch(&options, callback.clone()) {
+                    Ok(started) => *watch.borrow_mut() = Some(started),
+                    Err(error) => callback(Err(error)),
+                }
+            }
+        }) as Box<dyn FnMut(Event)>)
+    };
+    document
+        .add_event_listener_with_callback("visibilitychange", listener.as_ref().unchecked_ref())
+        .map_err(|_| Error::Unknown)?;
+
+    Ok(ForegroundWatch {
+        document,
+        listener,
+        _watch: watch,
+    })
+}
+
+/// A device orientation listener; dropping it removes the listener
+pub struct HeadingWatch {
+    event: &'static str,
+    listener: Closure<dyn FnMut(DeviceOrientationEvent)>,
+}
+
+impl Drop for HeadingWatch {
+    fn drop(&mut self) {
+        if let Some(window) = web_sys::window() {
+            let _ = window.remove_event_listener_with_callback(
+                self.event,
+                self.listener.as_ref().unchecked_ref(),
+            );
+        }
+    }
+}
+
+/// Listen for device orientation events, calling `callback` with the
+/// compass heading of each one
+///
+/// Chrome only reports headings relative to north in
+/// `deviceorientationabsolute`, so that event is preferred when it exists.
+/// Events without a reference to north are skipped.
+pub fn watch_heading(callback: HeadingCallback) -> crate::Result<HeadingWatch> {
+    let window = web_sys::window().ok_or(Error::PermanentlyUnavailable)?;
+    let event =
+        if js_sys::Reflect::has(&window, &"ondeviceorientationabsolute".into()).unwrap_or(false) {
+            "deviceorientationabsolute"
+        } else {
+            "deviceorientation"
+        };
+
+    let listener = Closure::wrap(Box::new(move |event: DeviceOrientationEvent| {
+        if let Some(heading) = heading_from_event(&event) {
+            callback(Ok(heading));
+        }
+    }) as Box<dyn FnMut(DeviceOrientationEvent)>);
+    window
+        .add_event_listener_with_callback(event, listener.as_ref().unchecked_ref())
+        .map_err(|_| Error::Unknown)?;
+
+    Ok(HeadingWatch { event, listener })
+}
+
+/// Read the compass heading of an orientation event
+///
+/// Safari reports it as `webkitCompassHeading`; other browsers report
+/// `alpha`, which counts counterclockwise from north when the event is
+/// `absolute`.
+fn heading_from_event(event: &DeviceOrientationEvent) -> Option<Heading> {
+    let webkit = |name: &str| js_sys::Reflect::get(event, &name.into()).ok()?.as_f64();
+    let (magnetic_heading, accuracy) = match webkit("webkitCompassHeading") {
+        Some(heading) => (heading, webkit("webkitCompassAccuracy").unwrap_or(-1.0)),
+        None if event.absolute() => ((360.0 - event.alpha()?) % 360.0, -1.0),
+        None => return None,
+    };
+
+    Some(Heading {
+        magnetic_heading,
+        true_heading: None,
+        accuracy,
+        timestamp: now(),
+    })
+}
+
+fn geolocation() -> crate::Result<web_sys::Geolocation> {
+    web_sys::window()
+        .and_then(|window| window.navigator().geolocation().ok())
+        .ok_or(Error::PermanentlyUnavailable)
+}
+
+fn position_options(options: &LocationOptions) -> PositionOptions {
+    let position_options = PositionOptions::new();
+    position_options.set_enable_high_accuracy(options.high_accuracy);
+    position_options.set_timeout(duration_to_millis(options.timeout));
+    position_options.set_maximum_age(duration_to_millis(options.maximum_age));
+    position_options
+}
+
+/// `PositionOptions` take milliseconds as an unsigned 32-bit integer
+fn duration_to_millis(duration: Duration) -> u32 {
+    duration.as_millis().min(u32::MAX as u128) as u32
+}
+
+/// Preset constructors for `web_sys::PositionOptions`
+///
+/// ```rust,no_run
+/// use dioxus_mobile_geolocation::PositionOptionsExt;
+/// use web_sys::PositionOptions;
+///
+/// let options = PositionOptions::new_high_accuracy();
+/// ```
+pub trait PositionOptionsExt {
+    /// GPS accuracy, a 10 second timeout and no cached positions
+    fn new_high_accuracy() -> Self;
+
+    /// Network accuracy, a 30 second timeout and cached positions up to a
+    /// minute old
+    fn new_low_power() -> Self;
+}
+
+impl PositionOptionsExt for PositionOptions {
+    fn new_high_accuracy() -> Self {
+        let options = PositionOptions::new();
+        options.set_enable_high_accuracy(true);
+        options.set_timeout(10_000);
+        options.set_maximum_age(0);
+        options
+    }
+
+    fn new_low_power() -> Self {
+        let options = PositionOptions::new();
+        options.set_enable_high_accuracy(false);
+        options.set_timeout(30_000);
+        options.set_maximum_age(60_000);
+        options
+    }
+}
+
 /// Get current position asynchronously (proper web implementation)
 ///
 /// This is the recommended way to get location on web platforms for more control.
@@ -0,0 +1,210 @@
+//! Windows implementation using the WinRT `Windows.Devices.Geolocation` API
+//!
+//! Every call creates its own `Geolocator`; the OS shares the underlying
+//! location service between them. Fixes are cached so that `last_known`
+//! doesn't have to wait for the service.
+
+use crate::watch::LocationCallback;
+use crate::{Error, Location, LocationOptions, PermissionStatus, PowerLevel, Result};
+use ::windows::Devices::Geolocation::{
+    GeolocationAccessStatus, Geolocator, Geoposition, PositionAccuracy, PositionChangedEventArgs,
+    PositionStatus, StatusChangedEventArgs,
+};
+use ::windows::Foundation::{DateTime, TimeSpan, TypedEventHandler};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Ticks (100 ns) between 1601-01-01, the WinRT epoch, and 1970-01-01
+const UNIX_EPOCH_TICKS: i64 = 116_444_736_000_000_000;
+
+/// How long `last_known` waits for the location service
+const LAST_KNOWN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Most recent fix from any `Geolocator` in this process
+static LAST_LOCATION: Mutex<Option<Location>> = Mutex::new(None);
+
+/// Request location access
+///
+/// Packaged apps show the system consent prompt the first time; unpackaged
+/// apps get the state of the "Let desktop apps access your location"
+/// setting. Blocks until the user answers.
+pub fn request_permission() -> bool {
+    Geolocator::RequestAccessAsync()
+        .and_then(|request| request.get())
+        .is_ok_and(|status| status == GeolocationAccessStatus::Allowed)
+}
+
+/// Get the current access state without prompting
+pub fn permission_status() -> PermissionStatus {
+    let Ok(status) = Geolocator::new().and_then(|geolocator| geolocator.LocationStatus()) else {
+        return PermissionStatus::Restricted;
+    };
+    match status {
+        PositionStatus::Ready | PositionStatus::Initializing | PositionStatus::NoData => {
+            PermissionStatus::GrantedFine
+        }
+        PositionStatus::Disabled => PermissionStatus::Denied,
+        PositionStatus::NotAvailable => PermissionStatus::Restricted,
+        _ => PermissionStatus::NotDetermined,
+    }
+}
+
+/// Get the last known location
+///
+/// Returns the cached fix if there is one and otherwise asks the location
+/// service for any position it has, waiting at most one second.
+pub fn last_known() -> Option<Location> {
+    if let Some(location) = cached_location() {
+        return Some(location);
+    }
+
+    let geolocator = Geolocator::new().ok()?;
+    let position = geolocator
+        .GetGeopositionAsyncWithAgeAndTimeout(
+            time_span(Duration::MAX),
+            time_span(LAST_KNOWN_TIMEOUT),
+        )
+        .and_then(|request| request.get())
+        .ok()?;
+    let location = location_from_position(&position)?;
+    cache(location);
+    Some(location)
+}
+
+/// Get the most recent fix seen by this process, without starting updates
+pub fn cached_location() -> Option<Location> {
+    *LAST_LOCATION.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Get the time of the last known location fix
+pub fn last_fix_time() -> Option<SystemTime> {
+    last_known()?.timestamp
+}
+
+/// Expected battery impact of location updates
+///
+/// Windows picks Wi-Fi or IP based positioning unless high accuracy is
+/// requested, so an available location service reports `Low`.
+pub fn power_level() -> PowerLevel {
+    if permission_status().is_granted() {
+        PowerLevel::Low
+    } else {
+        PowerLevel::None
+    }
+}
+
+/// Location updates for one `Geolocator`; dropping it removes the handlers
+pub struct Watch {
+    geolocator: Geolocator,
+    position_token: i64,
+    status_token: i64,
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        let _ = self.geolocator.RemovePositionChanged(self.position_token);
+        let _ = self.geolocator.RemoveStatusChanged(self.status_token);
+    }
+}
+
+/// Start delivering `PositionChanged` updates to `callback`
+pub fn watch(options: &LocationOptions, callback: LocationCallback) -> Result<Watch> {
+    let geolocator = Geolocator::new().map_err(|_| Error::PermanentlyUnavailable)?;
+    let accuracy = if options.high_accuracy {
+        PositionAccuracy::High
+    } else {
+        PositionAccuracy::Default
+    };
+    geolocator
+        .SetDesiredAccuracy(accuracy)
+        .and_then(|()| geolocator.SetMovementThreshold(options.distance_filter.max(0.0)))
+        .map_err(|_| Error::Unknown)?;
+
+    let on_position = {
+        let callback = callback.clone();
+        TypedEventHandler::new(
+            move |_: &Option<Geolocator>, args: &Option<PositionChangedEventArgs>| {
+                let location = args
+                    .as_ref()
+                    .and_then(|args| args.Position().ok())
+                    .and_then(|position| location_from_position(&position));
+                if let Some(location) = location {
+                    cache(location);
+                    callback(Ok(location));
+                }
+                Ok(())
+            },
+        )
+    };
+    let on_status = TypedEventHandler::new(
+        move |_: &Option<Geolocator>, args: &Option<StatusChangedEventArgs>| {
+            let status = args.as_ref().and_then(|args| args.Status().ok());
+            match status {
+                Some(PositionStatus::Disabled) => callback(Err(Error::AuthorizationDenied)),
+                Some(PositionStatus::NotAvailable) => callback(Err(Error::PermanentlyUnavailable)),
+                _ => {}
+            }
+            Ok(())
+        },
+    );
+
+    let position_token = geolocator
+        .PositionChanged(&on_position)
+        .map_err(|_| Error::Unknown)?;
+    let status_token = match geolocator.StatusChanged(&on_status) {
+        Ok(token) => token,
+        Err(_) => {
+            let _ = geolocator.RemovePositionChanged(position_token);
+            return Err(Error::Unknown);
+        }
+    };
+
+    Ok(Watch {
+        geolocator,
+        position_token,
+        status_token,
+    })
+}
+
+fn cache(location: Location) {
+    *LAST_LOCATION.lock().unwrap_or_else(PoisonError::into_inner) = Some(location);
+}
+
+/// Convert a `Geoposition` to a `Location`
+fn location_from_position(position: &Geoposition) -> Option<Location> {
+    let coordinate = position.Coordinate().ok()?;
+    let point = coordinate.Point().ok()?.Position().ok()?;
+    let vertical_accuracy = coordinate
+        .AltitudeAccuracy()
+        .and_then(|accuracy| accuracy.Value())
+        .ok();
+
+    Some(Location {
+        latitude: point.Latitude,
+        longitude: point.Longitude,
+        altitude: vertical_accuracy.map(|_| point.Altitude),
+        horizontal_accuracy: coordinate.Accuracy().ok(),
+        vertical_accuracy,
+        speed: coordinate.Speed().and_then(|speed| speed.Value()).ok(),
+        course: coordinate
+            .Heading()
+            .and_then(|heading| heading.Value())
+            .ok()
+            .filter(|heading| !heading.is_nan()),
+        timestamp: coordinate.Timestamp().ok().map(system_time),
+    })
+}
+
+fn time_span(duration: Duration) -> TimeSpan {
+    TimeSpan {
+        Duration: i64::try_from(duration.as_nanos() / 100).unwrap_or(i64::MAX),
+    }
+}
+
+fn system_time(date_time: DateTime) -> SystemTime {
+    let ticks = date_time
+        .UniversalTime
+        .saturating_sub(UNIX_EPOCH_TICKS)
+        .max(0);
+    UNIX_EPOCH + Duration::from_micros(ticks as u64 / 10)
+}
@@ -0,0 +1,119 @@
+use dioxus_mobile_geolocation::geo::{destination_point, haversine_distance, BoundingBox};
+use dioxus_mobile_geolocation::Coordinates;
+
+const BERLIN: BoundingBox = BoundingBox {
+    north: 52.68,
+    south: 52.34,
+    east: 13.76,
+    west: 13.09,
+};
+
+/// Crosses the antimeridian
+const FIJI: BoundingBox = BoundingBox {
+    north: -12.0,
+    south: -21.0,
+    east: -178.0,
+    west: 177.0,
+};
+
+#[test]
+fn contains_points_inside_and_on_the_edge() {
+    assert!(BERLIN.contains(Coordinates::new(52.52, 13.405)));
+    assert!(BERLIN.contains(Coordinates::new(52.68, 13.09)));
+    assert!(!BERLIN.contains(Coordinates::new(52.70, 13.405)));
+    assert!(!BERLIN.contains(Coordinates::new(52.52, 13.80)));
+}
+
+#[test]
+fn contains_across_the_antimeridian() {
+    assert!(FIJI.contains(Coordinates::new(-17.7, 178.1)));
+    assert!(FIJI.contains(Coordinates::new(-16.8, -179.9)));
+    assert!(FIJI.contains(Coordinates::new(-16.8, 180.0)));
+    assert!(!FIJI.contains(Coordinates::new(-17.7, 170.0)));
+    assert!(!FIJI.contains(Coordinates::new(-17.7, -170.0)));
+    assert!(!FIJI.contains(Coordinates::new(-17.7, 0.0)));
+}
+
+#[test]
+fn intersects_overlapping_and_touching_boxes() {
+    let potsdam = BoundingBox {
+        north: 52.45,
+        south: 52.33,
+        east: 13.20,
+        west: 12.90,
+    };
+    let touching = BoundingBox {
+        north: 52.34,
+        south: 52.20,
+        east: 13.50,
+        west: 13.30,
+    };
+    let hamburg = BoundingBox {
+        north: 53.74,
+        south: 53.39,
+        east: 10.33,
+        west: 9.73,
+    };
+    assert!(BERLIN.intersects(&potsdam));
+    assert!(potsdam.intersects(&BERLIN));
+    assert!(BERLIN.intersects(&touching));
+    assert!(!BERLIN.intersects(&hamburg));
+}
+
+#[test]
+fn intersects_across_the_antimeridian() {
+    let east_of_dateline = BoundingBox {
+        north: -15.0,
+        south: -18.0,
+        east: -170.0,
+        west: -179.5,
+    };
+    let west_of_dateline = BoundingBox {
+        north: -15.0,
+        south: -18.0,
+        east: 179.0,
+        west: 170.0,
+    };
+    let greenwich = BoundingBox {
+        north: -15.0,
+        south: -18.0,
+        east: 1.0,
+        west: -1.0,
+    };
+    assert!(FIJI.intersects(&east_of_dateline));
+    assert!(FIJI.intersects(&west_of_dateline));
+    assert!(east_of_dateline.intersects(&FIJI));
+    assert!(!FIJI.intersects(&greenwich));
+    assert!(!east_of_dateline.intersects(&west_of_dateline));
+}
+
+#[test]
+fn from_center_covers_the_radius_and_no_more() {
+    let center = Coordinates::new(52.52, 13.405);
+    let bounds = BoundingBox::from_center(center, 10_000.0);
+    for bearing in [0.0, 90.0, 180.0, 270.0] {
+        assert!(bounds.contains(destination_point(center, bearing, 9_900.0)));
+        assert!(!bounds.contains(destination_point(center, bearing, 10_500.0)));
+    }
+}
+
+#[test]
+fn expand_moves_every_edge_out() {
+    let expanded = BERLIN.expand(1_000.0);
+    assert!(expanded.north > BERLIN.north && expanded.south < BERLIN.south);
+    assert!(expanded.east > BERLIN.east && expanded.west < BERLIN.west);
+
+    let north_of_berlin = destination_point(Coordinates::new(52.68, 13.405), 0.0, 900.0);
+    assert!(!BERLIN.contains(north_of_berlin));
+    assert!(expanded.contains(north_of_berlin));
+}
+
+#[test]
+fn diagonal_m_spans_the_corners() {
+    let corners = haversine_distance(
+        Coordinates::new(BERLIN.south, BERLIN.west),
+        Coordinates::new(BERLIN.north, BERLIN.east),
+    );
+    assert!((BERLIN.diagonal_m() - corners).abs() < 1e-6);
+    assert!((BERLIN.diagonal_m() - 59_000.0).abs() < 2_000.0);
+}
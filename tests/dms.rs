@@ -0,0 +1,201 @@
+use dioxus_mobile_geolocation::{Coordinates, ParseError};
+
+/// Whole arcseconds are about 3e-4 degrees apart
+const ARCSECOND: f64 = 1.0 / 3600.0;
+
+fn assert_close(coords: Coordinates, latitude: f64, longitude: f64) {
+    assert!(
+        (coords.latitude - latitude).abs() < ARCSECOND / 2.0,
+        "latitude {} != {latitude}",
+        coords.latitude
+    );
+    assert!(
+        (coords.longitude - longitude).abs() < ARCSECOND / 2.0,
+        "longitude {} != {longitude}",
+        coords.longitude
+    );
+}
+
+#[test]
+fn formats_with_hemisphere_letters() {
+    let pittsburgh = Coordinates::new(40.446111, -79.982222);
+    assert_eq!(pittsburgh.to_dms(), "40°26'46\"N 079°58'56\"W");
+
+    let sydney = Coordinates::new(-33.868820, 151.209296);
+    assert_eq!(sydney.to_dms(), "33°52'08\"S 151°12'33\"E");
+}
+
+#[test]
+fn formats_the_equator_and_prime_meridian_as_north_and_east() {
+    assert_eq!(
+        Coordinates::new(0.0, 0.0).to_dms(),
+        "00°00'00\"N 000°00'00\"E"
+    );
+    // Rounds to zero, so no hemisphere flip
+    assert_eq!(
+        Coordinates::new(-0.0000001, -0.0000001).to_dms(),
+        "00°00'00\"N 000°00'00\"E"
+    );
+}
+
+#[test]
+fn formats_the_antimeridian_and_poles() {
+    let east = Coordinates {
+        latitude: 90.0,
+        longitude: 180.0,
+    };
+    assert_eq!(east.to_dms(), "90°00'00\"N 180°00'00\"E");
+
+    let west = Coordinates {
+        latitude: -90.0,
+        longitude: -180.0,
+    };
+    assert_eq!(west.to_dms(), "90°00'00\"S 180°00'00\"W");
+}
+
+#[test]
+fn rounding_carries_into_minutes_and_degrees() {
+    // 10°59'59.8" rounds up to 11°00'00"
+    let coords = Coordinates::new(10.0 + 59.0 / 60.0 + 59.8 / 3600.0, 0.0);
+    assert_eq!(coords.to_dms(), "11°00'00\"N 000°00'00\"E");
+}
+
+#[test]
+fn parses_its_own_output() {
+    for (latitude, longitude) in [
+        (40.446111, -79.982222),
+        (-33.868820, 151.209296),
+        (-54.801912, -68.302951),
+        (0.0, 0.0),
+        (51.477928, 0.0),
+        (-16.5, 180.0),
+        (65.0, -180.0),
+    ] {
+        let coords = Coordinates {
+            latitude,
+            longitude,
+        };
+        let parsed = Coordinates::parse_dms(&coords.to_dms()).unwrap();
+        assert_close(parsed, latitude, longitude);
+    }
+}
+
+#[test]
+fn parses_negative_latitudes() {
+    let south = Coordinates::parse_dms("33°52'08\"S 151°12'33\"E").unwrap();
+    assert_close(south, -33.868889, 151.209167);
+
+    let signed = Coordinates::parse_dms("-33°52'08\" 151°12'33\"").unwrap();
+    assert_close(signed, -33.868889, 151.209167);
+}
+
+#[test]
+fn parses_the_prime_meridian() {
+    let greenwich = Coordinates::parse_dms("51°28'40\"N 0°00'05\"W").unwrap();
+    assert_close(greenwich, 51.477778, -0.001389);
+
+    let zero = Coordinates::parse_dms("51°28'40\"N 0°E").unwrap();
+    assert_close(zero, 51.477778, 0.0);
+}
+
+#[test]
+fn parses_the_antimeridian() {
+    let east = Coordinates::parse_dms("16°30'S 180°E").unwrap();
+    assert_close(east, -16.5, 180.0);
+
+    let west = Coordinates::parse_dms("16°30'S 180°W").unwrap();
+    assert_close(west, -16.5, -180.0);
+}
+
+#[test]
+fn accepts_common_variants() {
+    let expected = (40.446111, -79.982222);
+    for input in [
+        "40°26'46\"N 79°58'56\"W",
+        "40d26'46\"N 79d58'56\"W",
+        "40D 26' 46\" N, 79D 58' 56\" W",
+        "40°26′46″N 79°58′56″W",
+        "40°26'46''N 79°58'56''W",
+        "40°26'46N 79°58'56W",
+        "N40°26'46\" W79°58'56\"",
+        "40°26'46\"n 79°58'56\"w",
+        "40°26'46\", -79°58'56\"",
+        "+40°26'46\" -79°58'56\"",
+        "  40°26'46\"N 79°58'56\"W  ",
+    ] {
+        let parsed =
+            Coordinates::parse_dms(input).unwrap_or_else(|error| panic!("{input}: {error}"));
+        assert_close(parsed, expected.0, expected.1);
+    }
+}
+
+#[test]
+fn accepts_decimal_minutes_and_seconds() {
+    let minutes = Coordinates::parse_dms("40°26.7667'N 79°58.9333'W").unwrap();
+    assert_close(minutes, 40.446111, -79.982222);
+
+    let seconds = Coordinates::parse_dms("40°26'46.4\"N 79°58'55.9\"W").unwrap();
+    assert_close(seconds, 40.446222, -79.982194);
+
+    let degrees = Coordinates::parse_dms("40.446111°N 79.982222°W").unwrap();
+    assert_close(degrees, 40.446111, -79.982222);
+}
+
+#[test]
+fn accepts_longitude_first_with_hemisphere_letters() {
+    let parsed = Coordinates::parse_dms("79°58'56\"W 40°26'46\"N").unwrap();
+    assert_close(parsed, 40.446111, -79.982222);
+}
+
+#[test]
+fn rejects_empty_input() {
+    assert_eq!(
+        Coordinates::parse_dms("").unwrap_err(),
+        ParseError::EmptyInput
+    );
+    assert_eq!(
+        Coordinates::parse_dms(" \t\n").unwrap_err(),
+        ParseError::EmptyInput
+    );
+}
+
+#[test]
+fn rejects_out_of_range_values() {
+    assert_eq!(
+        Coordinates::parse_dms("91°00'00\"N 10°00'00\"E").unwrap_err(),
+        ParseError::OutOfRange(91.0)
+    );
+    assert_eq!(
+        Coordinates::parse_dms("90°30'S 0°E").unwrap_err(),
+        ParseError::OutOfRange(-90.5)
+    );
+    assert_eq!(
+        Coordinates::parse_dms("10°N 180°30'W").unwrap_err(),
+        ParseError::OutOfRange(-180.5)
+    );
+}
+
+#[test]
+fn rejects_malformed_input() {
+    for input in [
+        "40.446111, -79.982222",
+        "40°26'46\"N",
+        "40°26'46\"N 79°58'56\"W 12°",
+        "40°61'00\"N 79°58'56\"W",
+        "40°26'60\"N 79°58'56\"W",
+        "40.5°26'N 79°W",
+        "40°26.5'10\"N 79°W",
+        "-40°26'46\"S 79°58'56\"W",
+        "40°26'46\"N 79°58'56\"S",
+        "79°58'56\"E 40°26'46\"W",
+        "N40°26'46\"S 79°58'56\"W",
+        "forty°N 79°W",
+        "40°26 79°58",
+    ] {
+        assert_eq!(
+            Coordinates::parse_dms(input).unwrap_err(),
+            ParseError::InvalidFormat,
+            "{input}"
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use dioxus_mobile_geolocation::geo_math::distance_meters;
+use dioxus_mobile_geolocation::{
+    Coordinates, ExponentialMovingAverage, LocationFilter, SmoothingFilter,
+};
+
+fn assert_near(actual: Coordinates, expected: Coordinates) {
+    let off = distance_meters(actual, expected);
+    assert!(off < 0.01, "{actual:?} is {off} m from {expected:?}");
+}
+
+#[test]
+fn smoothing_filter_averages_only_the_window() {
+    let mut filter = SmoothingFilter::with_weights(vec![1.0, 1.0]);
+    assert_near(
+        filter.update(Coordinates::new(10.0, 0.0)),
+        Coordinates::new(10.0, 0.0),
+    );
+    assert_near(
+        filter.update(Coordinates::new(20.0, 0.0)),
+        Coordinates::new(15.0, 0.0),
+    );
+    // The first fix has left the window
+    assert_near(
+        filter.update(Coordinates::new(40.0, 0.0)),
+        Coordinates::new(30.0, 0.0),
+    );
+}
+
+#[test]
+fn smoothing_filter_weights_recent_fixes_more() {
+    let mut filter = SmoothingFilter::new(3);
+    assert_eq!(filter.window(), 3);
+    filter.update(Coordinates::new(0.0, 0.0));
+    filter.update(Coordinates::new(0.0, 0.0));
+    // Weights 1, 2 and 3 from oldest to newest: 6 * 3 / 6
+    assert_near(
+        filter.update(Coordinates::new(6.0, 0.0)),
+        Coordinates::new(3.0, 0.0),
+    );
+    assert_eq!(SmoothingFilter::new(0).window(), 1);
+}
+
+#[test]
+fn smoothing_filter_reset_forgets_the_history() {
+    let mut filter = SmoothingFilter::new(4);
+    filter.update(Coordinates::new(10.0, 10.0));
+    filter.update(Coordinates::new(11.0, 11.0));
+    filter.reset();
+    let fix = Coordinates::new(-30.0, 100.0);
+    assert_near(filter.update(fix), fix);
+}
+
+#[test]
+fn smoothing_filter_averages_across_the_antimeridian() {
+    let mut filter = SmoothingFilter::with_weights(vec![1.0, 1.0]);
+    filter.update(Coordinates::new(0.0, 179.0));
+    let average = filter.update(Coordinates::new(0.0, -179.0));
+    assert_near(average, Coordinates::new(0.0, 180.0));
+}
+
+#[test]
+fn exponential_moving_average_blends_by_alpha() {
+    let mut filter = ExponentialMovingAverage::with_alpha(0.25);
+    filter.update(Coordinates::new(0.0, 0.0));
+    assert_near(
+        filter.update(Coordinates::new(8.0, 4.0)),
+        Coordinates::new(2.0, 1.0),
+    );
+    assert_near(
+        filter.update(Coordinates::new(8.0, 4.0)),
+        Coordinates::new(3.5, 1.75),
+    );
+}
+
+#[test]
+fn exponential_moving_average_clamps_alpha() {
+    // Above 1 follows every fix
+    let mut filter = ExponentialMovingAverage::with_alpha(5.0);
+    filter.update(Coordinates::new(0.0, 0.0));
+    let fix = Coordinates::new(10.0, 20.0);
+    assert_near(filter.update(fix), fix);
+
+    // Zero or below barely moves, but still moves
+    let mut filter = ExponentialMovingAverage::with_alpha(-1.0);
+    let start = Coordinates::new(0.0, 0.0);
+    filter.update(start);
+    let estimate = filter.update(Coordinates::new(10.0, 20.0));
+    assert_near(estimate, start);
+    assert!(estimate.latitude > 0.0);
+}
+
+#[test]
+fn exponential_moving_average_reset_starts_over() {
+    let mut filter = ExponentialMovingAverage::new();
+    filter.update(Coordinates::new(10.0, 10.0));
+    filter.reset();
+    let fix = Coordinates::new(-30.0, 100.0);
+    assert_near(filter.update(fix), fix);
+}
+
+#[test]
+fn exponential_moving_average_crosses_the_antimeridian() {
+    let mut filter = ExponentialMovingAverage::with_alpha(0.5);
+    filter.update(Coordinates::new(0.0, 179.0));
+    let estimate = filter.update(Coordinates::new(0.0, -179.0));
+    assert_near(estimate, Coordinates::new(0.0, 180.0));
+}
@@ -0,0 +1,58 @@
+use dioxus_mobile_geolocation::geo_math::{
+    destination_point, distance_meters, initial_bearing, LocationTrail,
+};
+use dioxus_mobile_geolocation::Coordinates;
+use proptest::prelude::*;
+
+/// Difference between two bearings in degrees, in `[0, 180]`
+fn bearing_difference(a: f64, b: f64) -> f64 {
+    let difference = (a - b).rem_euclid(360.0);
+    difference.min(360.0 - difference)
+}
+
+proptest! {
+    #[test]
+    fn distance_to_itself_is_zero(lat in -90.0..=90.0, lon in -180.0..180.0) {
+        let point = Coordinates::new(lat, lon);
+        prop_assert_eq!(distance_meters(point, point), 0.0);
+    }
+
+    #[test]
+    fn destination_point_round_trips(
+        lat in -80.0..80.0,
+        lon in -180.0..180.0,
+        bearing in 0.0..360.0,
+        distance in 1.0..1_000_000.0,
+    ) {
+        let origin = Coordinates::new(lat, lon);
+        let destination = destination_point(origin, bearing, distance);
+        let measured = distance_meters(origin, destination);
+        prop_assert!(
+            (measured - distance).abs() <= distance * 1e-9 + 1e-6,
+            "travelled {distance} m but measured {measured} m"
+        );
+        let measured = initial_bearing(origin, destination);
+        prop_assert!(
+            bearing_difference(measured, bearing) < 1e-6,
+            "set off at {bearing}° but measured {measured}°"
+        );
+    }
+
+    #[test]
+    fn simplify_visvalingam_keeps_the_ends_and_target_count(
+        offsets in prop::collection::vec((-0.01..0.01, -0.01..0.01), 2..200),
+        target in 0usize..250,
+    ) {
+        let points: Vec<Coordinates> = offsets
+            .iter()
+            .map(|(lat, lon)| Coordinates::new(52.52 + lat, 13.405 + lon))
+            .collect();
+        let simplified = LocationTrail::new(points.clone()).simplify_visvalingam(target).points;
+        prop_assert_eq!(simplified.len(), points.len().min(target.max(2)));
+        let ends = |points: &[Coordinates]| {
+            let (first, last) = (points[0], points[points.len() - 1]);
+            [first.latitude, first.longitude, last.latitude, last.longitude]
+        };
+        prop_assert_eq!(ends(&simplified), ends(&points));
+    }
+}
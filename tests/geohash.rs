@@ -0,0 +1,101 @@
+use dioxus_mobile_geolocation::geo_math::distance_meters;
+use dioxus_mobile_geolocation::{Coordinates, GeohashError};
+
+const PLACES: [(f64, f64); 6] = [
+    (57.64911, 10.40744),
+    (-33.868820, 151.209296),
+    (40.446111, -79.982222),
+    (-54.801912, -68.302951),
+    (0.0, 0.0),
+    (89.9, 179.9),
+];
+
+#[test]
+fn encodes_known_geohashes() {
+    assert_eq!(
+        Coordinates::new(57.64911, 10.40744).to_geohash(11),
+        "u4pruydqqvj"
+    );
+    assert_eq!(Coordinates::new(42.605, -5.603).to_geohash(5), "ezs42");
+    assert_eq!(Coordinates::new(0.0, 0.0).to_geohash(1), "s");
+}
+
+#[test]
+fn clamps_the_precision() {
+    let coords = Coordinates::new(57.64911, 10.40744);
+    assert_eq!(coords.to_geohash(0), "u");
+    assert_eq!(coords.to_geohash(12).len(), 12);
+    assert_eq!(coords.to_geohash(40), coords.to_geohash(12));
+}
+
+#[test]
+fn round_trips_within_the_error_radius_at_every_precision() {
+    for (latitude, longitude) in PLACES {
+        let coords = Coordinates::new(latitude, longitude);
+        let mut previous_error = f64::INFINITY;
+        for precision in 1..=12 {
+            let hash = coords.to_geohash(precision);
+            assert_eq!(hash.len(), usize::from(precision));
+
+            let (center, error_m) = Coordinates::from_geohash(&hash).unwrap();
+            assert!(
+                distance_meters(coords, center) <= error_m,
+                "{hash}: {} m from the center, error {error_m} m",
+                distance_meters(coords, center)
+            );
+            assert!(error_m < previous_error, "{hash} is not finer");
+            assert_eq!(center.to_geohash(precision), hash);
+            previous_error = error_m;
+        }
+        // Twelve characters pin the location down to centimeters
+        assert!(previous_error < 0.1);
+    }
+}
+
+#[test]
+fn neighbors_share_a_prefix() {
+    let center = Coordinates::new(57.64911, 10.40744);
+    let hash = center.to_geohash(12);
+    let (cell, _) = Coordinates::from_geohash(&hash[..6]).unwrap();
+    assert!(distance_meters(center, cell) < 1_000.0);
+
+    // A few meters away in every direction stays in the same 6-character cell
+    for (d_lat, d_lon) in [
+        (0.00003, 0.0),
+        (-0.00003, 0.0),
+        (0.0, 0.00005),
+        (0.0, -0.00005),
+    ] {
+        let neighbor = Coordinates::new(center.latitude + d_lat, center.longitude + d_lon);
+        let neighbor_hash = neighbor.to_geohash(12);
+        assert_eq!(neighbor_hash[..6], hash[..6], "{neighbor_hash} vs {hash}");
+        assert_ne!(neighbor_hash, hash);
+    }
+}
+
+#[test]
+fn accepts_upper_case() {
+    let (lower, _) = Coordinates::from_geohash("u4pruydqqvj").unwrap();
+    let (upper, _) = Coordinates::from_geohash("U4PRUYDQQVJ").unwrap();
+    assert_eq!(lower.latitude, upper.latitude);
+    assert_eq!(lower.longitude, upper.longitude);
+}
+
+#[test]
+fn rejects_invalid_geohashes() {
+    assert_eq!(
+        Coordinates::from_geohash("").unwrap_err(),
+        GeohashError::EmptyString
+    );
+    assert_eq!(
+        Coordinates::from_geohash("u4pruydqqvjxx").unwrap_err(),
+        GeohashError::TooLong
+    );
+    for c in ['a', 'i', 'l', 'o', ' ', '-', 'é'] {
+        let hash = format!("u4{c}");
+        assert_eq!(
+            Coordinates::from_geohash(&hash).unwrap_err(),
+            GeohashError::InvalidCharacter(c)
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use dioxus_mobile_geolocation::{Error, Location, LocationHistory};
+use serde_json::json;
+use std::time::{Duration, UNIX_EPOCH};
+
+fn fix() -> Location {
+    Location {
+        latitude: 52.520_008,
+        longitude: 13.404_954,
+        altitude: Some(34.5),
+        horizontal_accuracy: Some(4.2),
+        vertical_accuracy: None,
+        speed: Some(1.3),
+        course: None,
+        timestamp: Some(UNIX_EPOCH + Duration::from_millis(1_700_000_000_123)),
+    }
+}
+
+#[test]
+fn feature_has_longitude_first() {
+    let feature = fix().to_geojson_feature();
+    assert_eq!(
+        feature,
+        json!({
+            "type": "Feature",
+            "geometry": { "type": "Point", "coordinates": [13.404_954, 52.520_008, 34.5] },
+            "properties": { "accuracy": 4.2, "speed": 1.3, "timestamp": 1_700_000_000_123_i64 },
+        })
+    );
+}
+
+#[test]
+fn feature_roundtrips() {
+    let feature = fix().to_geojson_feature();
+    assert_eq!(Location::from_geojson_feature(&feature).unwrap(), fix());
+}
+
+#[test]
+fn feature_without_properties_is_read() {
+    let feature = json!({
+        "type": "Feature",
+        "geometry": { "type": "Point", "coordinates": [-0.1276, 51.5072] },
+        "properties": null,
+    });
+    let location = Location::from_geojson_feature(&feature).unwrap();
+    assert_eq!((location.latitude, location.longitude), (51.5072, -0.1276));
+    assert_eq!(location.altitude, None);
+    assert_eq!(location.timestamp, None);
+}
+
+#[test]
+fn invalid_features_are_rejected() {
+    let invalid = [
+        json!({ "type": "Point", "coordinates": [13.4, 52.5] }),
+        json!({
+            "type": "Feature",
+            "geometry": { "type": "LineString", "coordinates": [[13.4, 52.5], [13.5, 52.6]] },
+        }),
+        json!({ "type": "Feature", "geometry": { "type": "Point", "coordinates": [13.4] } }),
+        json!({ "type": "Feature", "geometry": { "type": "Point", "coordinates": [13.4, "52.5"] } }),
+        json!({ "type": "Feature", "geometry": { "type": "Point", "coordinates": [52.5, 95.0] } }),
+    ];
+    for feature in invalid {
+        assert!(
+            matches!(
+                Location::from_geojson_feature(&feature),
+                Err(Error::InvalidCoordinates)
+            ),
+            "{feature}"
+        );
+    }
+}
+
+#[test]
+fn history_becomes_a_feature_collection() {
+    let mut history = LocationHistory::new(10);
+    history.push(fix());
+    history.push(Location {
+        latitude: 52.521,
+        ..fix()
+    });
+
+    let collection = history.to_geojson_feature_collection();
+    assert_eq!(collection["type"], "FeatureCollection");
+    let features = collection["features"].as_array().unwrap();
+    assert_eq!(features.len(), 2);
+    assert_eq!(features[1]["geometry"]["coordinates"][1], 52.521);
+}
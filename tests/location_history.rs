@@ -0,0 +1,71 @@
+use dioxus_mobile_geolocation::{Location, LocationHistory};
+use std::time::{Duration, UNIX_EPOCH};
+
+fn fix(latitude: f64, seconds: u64) -> Location {
+    Location {
+        latitude,
+        longitude: 13.405,
+        altitude: None,
+        horizontal_accuracy: None,
+        vertical_accuracy: None,
+        speed: None,
+        course: None,
+        timestamp: Some(UNIX_EPOCH + Duration::from_secs(seconds)),
+    }
+}
+
+#[test]
+fn full_history_drops_the_oldest_fix() {
+    let mut history = LocationHistory::new(3);
+    for (index, latitude) in [52.0, 52.1, 52.2, 52.3, 52.4].into_iter().enumerate() {
+        history.push(fix(latitude, index as u64 * 10));
+    }
+
+    assert_eq!(history.len(), 3);
+    let latitudes: Vec<f64> = history.iter().map(|fix| fix.latitude).collect();
+    assert_eq!(latitudes, [52.2, 52.3, 52.4]);
+    assert_eq!(history.duration(), Some(Duration::from_secs(20)));
+}
+
+#[test]
+fn zero_capacity_history_stays_empty() {
+    let mut history = LocationHistory::new(0);
+    history.push(fix(52.0, 0));
+
+    assert!(history.is_empty());
+    assert_eq!(history.duration(), None);
+    assert!(history.bounding_box().is_none());
+}
+
+#[test]
+fn average_speed_needs_elapsed_time() {
+    let mut history = LocationHistory::new(10);
+    history.push(fix(52.520, 0));
+    assert_eq!(history.average_speed_mps(), None);
+
+    history.push(fix(52.521, 100));
+    let speed = history.average_speed_mps().unwrap();
+    assert!((speed - history.total_distance_m() / 100.0).abs() < 1e-9);
+}
+
+#[test]
+fn gpx_export_lists_every_fix() {
+    let mut history = LocationHistory::new(10);
+    history.push(Location {
+        altitude: Some(34.5),
+        ..fix(52.52, 1_714_564_800)
+    });
+    history.push(Location {
+        timestamp: None,
+        ..fix(52.53, 0)
+    });
+
+    let gpx = history.export_gpx();
+    assert!(gpx.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<gpx version=\"1.1\""));
+    assert!(gpx.contains("xmlns=\"http://www.topografix.com/GPX/1/1\""));
+    assert!(gpx.contains(
+        "<trkpt lat=\"52.52\" lon=\"13.405\"><ele>34.5</ele><time>2024-05-01T12:00:00Z</time></trkpt>"
+    ));
+    assert!(gpx.contains("<trkpt lat=\"52.53\" lon=\"13.405\"></trkpt>"));
+    assert!(gpx.ends_with("</trkseg>\n  </trk>\n</gpx>\n"));
+}
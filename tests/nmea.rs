@@ -0,0 +1,137 @@
+use dioxus_mobile_geolocation::nmea::{NmeaError, NmeaReader, NmeaSentence, SatelliteInfo};
+use std::io::{ErrorKind, Read};
+use std::time::{Duration, UNIX_EPOCH};
+
+const GGA: &str = "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
+const RMC: &str = "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A";
+const GSV: &str = "$GPGSV,2,1,08,01,40,083,46,02,17,308,41,12,07,344,39,14,22,228,45*75";
+
+#[test]
+fn gga_has_position_altitude_and_precision() {
+    let NmeaSentence::Gga(gga) = NmeaSentence::parse(GGA).unwrap() else {
+        panic!("not a GGA sentence");
+    };
+    assert_eq!(
+        gga.time,
+        Some(Duration::from_secs(12 * 3600 + 35 * 60 + 19))
+    );
+    assert_eq!(gga.fix_quality, 1);
+    assert_eq!(gga.satellites_used, Some(8));
+    assert_eq!(gga.hdop, Some(0.9));
+    assert_eq!(gga.geoid_separation, Some(46.9));
+
+    let location = NmeaSentence::Gga(gga).to_location().unwrap();
+    assert!((location.latitude - 48.1173).abs() < 1e-9);
+    assert!((location.longitude - 11.516_666_666).abs() < 1e-9);
+    assert_eq!(location.altitude, Some(545.4));
+    assert!(location.horizontal_accuracy.is_some());
+    assert_eq!(location.timestamp, None);
+}
+
+#[test]
+fn rmc_has_speed_course_and_timestamp() {
+    let location = NmeaSentence::parse(RMC).unwrap().to_location().unwrap();
+    assert!((location.speed.unwrap() - 22.4 * 1852.0 / 3600.0).abs() < 1e-9);
+    assert_eq!(location.course, Some(84.4));
+    // 1994-03-23T12:35:19Z
+    assert_eq!(
+        location.timestamp,
+        Some(UNIX_EPOCH + Duration::from_secs(764_426_119))
+    );
+}
+
+#[test]
+fn gsv_lists_satellites() {
+    let NmeaSentence::Gsv(gsv) = NmeaSentence::parse(GSV).unwrap() else {
+        panic!("not a GSV sentence");
+    };
+    assert_eq!((gsv.message_count, gsv.message_number), (2, 1));
+    assert_eq!(gsv.satellites_in_view, 8);
+    assert_eq!(gsv.satellites.len(), 4);
+    assert_eq!(
+        gsv.satellites[0],
+        SatelliteInfo {
+            prn: 1,
+            elevation: Some(40),
+            azimuth: Some(83),
+            snr: Some(46),
+        }
+    );
+    assert!(NmeaSentence::Gsv(gsv).to_location().is_none());
+}
+
+#[test]
+fn other_talkers_are_accepted() {
+    let sentence = NmeaSentence::parse("$GNGGA,,,,,,0,00,99.99,,,,,,*56\r\n").unwrap();
+    // No fix yet
+    assert!(sentence.to_location().is_none());
+}
+
+#[test]
+fn checksum_is_enforced() {
+    let corrupted = GGA.replace("4807", "4808");
+    assert_eq!(
+        NmeaSentence::parse(&corrupted),
+        Err(NmeaError::ChecksumMismatch {
+            expected: 0x47,
+            actual: 0x48,
+        })
+    );
+    assert_eq!(
+        NmeaSentence::parse(GGA.trim_end_matches("*47")),
+        Err(NmeaError::MissingChecksum)
+    );
+}
+
+#[test]
+fn invalid_sentences_are_rejected() {
+    assert_eq!(
+        NmeaSentence::parse("$GPGSA,A,3,04,05,,09,12,,,24,,,,,2.5,1.3,2.1*39"),
+        Err(NmeaError::UnsupportedSentence("GPGSA".to_string()))
+    );
+    assert_eq!(
+        NmeaSentence::parse("GPGGA,123519*47"),
+        Err(NmeaError::InvalidFormat)
+    );
+    // 61 minutes of latitude
+    assert_eq!(
+        NmeaSentence::parse("$GPGGA,123519,4861.000,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*4C"),
+        Err(NmeaError::InvalidFormat)
+    );
+}
+
+#[test]
+fn reader_skips_blank_lines_and_keeps_going_after_errors() {
+    let input = format!("\r\n{GGA}\r\n$GPGGA,garbage*00\r\n{RMC}\r\n");
+    let sentences: Vec<_> = NmeaReader::new(input.as_bytes()).collect();
+    assert_eq!(sentences.len(), 3);
+    assert!(sentences[0].is_ok());
+    assert!(sentences[1].is_err());
+    assert!(matches!(sentences[2], Ok(NmeaSentence::Rmc(_))));
+}
+
+/// Fails every read with the given kind
+struct FailingReader(ErrorKind);
+
+impl Read for FailingReader {
+    fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+        Err(self.0.into())
+    }
+}
+
+#[test]
+fn reader_ends_after_a_permanent_io_error() {
+    let mut reader = NmeaReader::new(FailingReader(ErrorKind::BrokenPipe));
+    assert_eq!(
+        reader.next(),
+        Some(Err(NmeaError::Io(ErrorKind::BrokenPipe)))
+    );
+    assert_eq!(reader.next(), None);
+}
+
+#[test]
+fn reader_keeps_going_after_a_timeout() {
+    let mut reader = NmeaReader::new(FailingReader(ErrorKind::TimedOut));
+    assert_eq!(reader.next(), Some(Err(NmeaError::Io(ErrorKind::TimedOut))));
+    assert_eq!(reader.next(), Some(Err(NmeaError::Io(ErrorKind::TimedOut))));
+}
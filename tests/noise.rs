@@ -0,0 +1,132 @@
+use dioxus_mobile_geolocation::geo_math::{destination_point, distance_meters};
+use dioxus_mobile_geolocation::{
+    Coordinates, KalmanFilter, Location, LocationFilter, NoiseSimulator, SmoothingFilter,
+};
+use std::time::{Duration, SystemTime};
+
+const TRUTH: Coordinates = Coordinates {
+    latitude: 52.52,
+    longitude: 13.405,
+};
+
+fn mean_error(points: &[Coordinates]) -> f64 {
+    points
+        .iter()
+        .map(|&point| distance_meters(TRUTH, point))
+        .sum::<f64>()
+        / points.len() as f64
+}
+
+#[test]
+fn noise_matches_the_horizontal_sigma() {
+    let noise = NoiseSimulator::new(10.0, 0.0);
+    let fixes: Vec<Coordinates> = (0..2_000).map(|_| noise.perturb(TRUTH)).collect();
+
+    // The distance from the truth is Rayleigh distributed, with a mean of
+    // sigma * sqrt(pi / 2)
+    let expected = 10.0 * (std::f64::consts::PI / 2.0).sqrt();
+    assert!(
+        (mean_error(&fixes) - expected).abs() < 1.0,
+        "{}",
+        mean_error(&fixes)
+    );
+
+    let within_sigma = fixes
+        .iter()
+        .filter(|&&fix| distance_meters(TRUTH, fix) <= 10.0)
+        .count();
+    assert!((700..=870).contains(&within_sigma), "{within_sigma}");
+}
+
+#[test]
+fn same_seed_gives_the_same_noise() {
+    let a = NoiseSimulator::with_seed(10.0, 0.0, 7);
+    let b = NoiseSimulator::with_seed(10.0, 0.0, 7);
+    for _ in 0..10 {
+        let (fix_a, fix_b) = (a.perturb(TRUTH), b.perturb(TRUTH));
+        assert_eq!(fix_a.latitude, fix_b.latitude);
+        assert_eq!(fix_a.longitude, fix_b.longitude);
+    }
+}
+
+#[test]
+fn zero_sigma_keeps_the_coordinates() {
+    let noise = NoiseSimulator::new(0.0, 0.0);
+    assert!(distance_meters(TRUTH, noise.perturb(TRUTH)) < 1e-6);
+}
+
+#[test]
+fn smoothing_filter_reduces_noise_on_a_stationary_device() {
+    let noise = NoiseSimulator::new(15.0, 0.0);
+    let mut filter = SmoothingFilter::new(10);
+
+    let raw: Vec<Coordinates> = (0..500).map(|_| noise.perturb(TRUTH)).collect();
+    let smoothed: Vec<Coordinates> = raw.iter().map(|&fix| filter.update(fix)).collect();
+
+    // Skip the warm-up while the window fills
+    let (raw_error, smoothed_error) = (mean_error(&raw[10..]), mean_error(&smoothed[10..]));
+    assert!(
+        smoothed_error < raw_error / 2.0,
+        "smoothed {smoothed_error} m vs raw {raw_error} m"
+    );
+}
+
+/// Mean squared distance in m² between matching points
+fn error_variance(truth: &[Coordinates], points: &[Coordinates]) -> f64 {
+    truth
+        .iter()
+        .zip(points)
+        .map(|(&truth, &point)| distance_meters(truth, point).powi(2))
+        .sum::<f64>()
+        / truth.len() as f64
+}
+
+#[test]
+fn kalman_filter_reduces_noise_on_a_moving_device() {
+    let noise = NoiseSimulator::new(15.0, 0.0);
+    let mut filter = KalmanFilter::new();
+
+    // Walking north-east at 1.5 m/s with a fix every second
+    let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let truth: Vec<Coordinates> = (0..300)
+        .map(|i| destination_point(TRUTH, 30.0, 1.5 * f64::from(i)))
+        .collect();
+    let raw: Vec<Location> = truth
+        .iter()
+        .zip(0..)
+        .map(|(&position, i)| {
+            noise.perturb_location(Location {
+                timestamp: Some(start + Duration::from_secs(i)),
+                ..Location::from(position)
+            })
+        })
+        .collect();
+    let smoothed: Vec<Coordinates> = raw
+        .iter()
+        .map(|fix| filter.update_location(fix).coordinates())
+        .collect();
+    let raw: Vec<Coordinates> = raw.iter().map(Location::coordinates).collect();
+
+    // Skip the warm-up while the velocity settles
+    let (raw_variance, smoothed_variance) = (
+        error_variance(&truth[20..], &raw[20..]),
+        error_variance(&truth[20..], &smoothed[20..]),
+    );
+    assert!(
+        smoothed_variance < raw_variance / 2.0,
+        "smoothed {smoothed_variance} m² vs raw {raw_variance} m²"
+    );
+}
+
+#[test]
+fn kalman_filter_starts_over_after_reset() {
+    let noise = NoiseSimulator::new(15.0, 0.0);
+    let mut filter = KalmanFilter::new();
+    for _ in 0..10 {
+        filter.update(noise.perturb(TRUTH));
+    }
+
+    filter.reset();
+    let elsewhere = Coordinates::new(48.8566, 2.3522);
+    assert!(distance_meters(elsewhere, filter.update(elsewhere)) < 1e-6);
+}
@@ -0,0 +1,45 @@
+use dioxus_mobile_geolocation::{Error, Location};
+use std::time::{Duration, UNIX_EPOCH};
+
+#[test]
+fn location_roundtrips_through_json() {
+    let location = Location {
+        latitude: 52.520_008,
+        longitude: 13.404_954,
+        altitude: Some(34.5),
+        horizontal_accuracy: Some(4.2),
+        vertical_accuracy: None,
+        speed: Some(1.3),
+        course: None,
+        timestamp: Some(UNIX_EPOCH + Duration::from_millis(1_700_000_000_123)),
+    };
+
+    let json = serde_json::to_value(location).unwrap();
+    assert_eq!(json["timestamp"], 1_700_000_000_123_i64);
+
+    let decoded: Location = serde_json::from_value(json).unwrap();
+    assert_eq!(decoded, location);
+}
+
+#[test]
+fn location_without_timestamp_roundtrips() {
+    let location = Location {
+        latitude: -33.868_82,
+        longitude: 151.209_29,
+        altitude: None,
+        horizontal_accuracy: None,
+        vertical_accuracy: None,
+        speed: None,
+        course: None,
+        timestamp: None,
+    };
+
+    let json = serde_json::to_string(&location).unwrap();
+    assert_eq!(serde_json::from_str::<Location>(&json).unwrap(), location);
+}
+
+#[test]
+fn error_is_tagged_by_type() {
+    let json = serde_json::to_value(Error::AuthorizationDenied).unwrap();
+    assert_eq!(json, serde_json::json!({ "type": "AuthorizationDenied" }));
+}